@@ -0,0 +1,299 @@
+//! Priority calibration: even out how different reviewers and models rate severity
+//!
+//! Configured under `calibration`. Rules rewrite a finding's priority by
+//! reviewer, finding type (regex), path (glob) and/or model; the first
+//! matching rule applies. Caps then limit how many findings of a priority a
+//! reviewer may report, moving the least confident extras down a level.
+//! Every change is recorded in the finding's `calibration` list.
+
+use crate::config::{CalibrationConfig, CalibrationRule, Priority};
+use crate::error::ConfigError;
+use crate::parser::{Confidence, Finding, PriorityAdjustment};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::collections::HashMap;
+
+struct CompiledRule {
+    rule: CalibrationRule,
+    finding_type: Option<Regex>,
+    path: Option<GlobMatcher>,
+    reason: String,
+}
+
+/// Compiled `calibration` section
+#[derive(Default)]
+pub struct Calibration {
+    rules: Vec<CompiledRule>,
+    caps: HashMap<String, HashMap<Priority, usize>>,
+}
+
+impl Calibration {
+    pub fn from_config(config: &CalibrationConfig) -> Result<Self, ConfigError> {
+        let mut rules = Vec::new();
+        for (n, rule) in config.rules.iter().enumerate() {
+            if rule.reviewer.is_none()
+                && rule.finding_type.is_none()
+                && rule.path.is_none()
+                && rule.model.is_none()
+                && rule.from.is_empty()
+            {
+                return Err(ConfigError::InvalidCalibration(format!(
+                    "rule {} matches every finding; set reviewer, type, path, model or from",
+                    n + 1
+                )));
+            }
+            let finding_type = match &rule.finding_type {
+                Some(pattern) => Some(Regex::new(pattern).map_err(|e| {
+                    ConfigError::InvalidCalibration(format!("bad type regex '{}': {}", pattern, e))
+                })?),
+                None => None,
+            };
+            let path = match &rule.path {
+                Some(pattern) => Some(
+                    Glob::new(pattern)
+                        .map_err(|e| {
+                            ConfigError::InvalidCalibration(format!(
+                                "bad path glob '{}': {}",
+                                pattern, e
+                            ))
+                        })?
+                        .compile_matcher(),
+                ),
+                None => None,
+            };
+            let reason = rule.reason.clone().unwrap_or_else(|| describe(n + 1, rule));
+            rules.push(CompiledRule {
+                rule: rule.clone(),
+                finding_type,
+                path,
+                reason,
+            });
+        }
+
+        Ok(Self {
+            rules,
+            caps: config.caps.clone(),
+        })
+    }
+
+    /// Rewrite priorities of one model's findings by the first matching rule
+    pub fn apply_rules(&self, findings: &mut [Finding], reviewer_id: &str, model: &str) {
+        for finding in findings {
+            let Some(rule) = self
+                .rules
+                .iter()
+                .find(|r| r.matches(finding, reviewer_id, model))
+            else {
+                continue;
+            };
+            adjust(finding, rule.rule.priority, &rule.reason);
+        }
+    }
+
+    /// Move a reviewer's findings beyond its per-priority caps down a level
+    ///
+    /// Higher confidence findings keep their priority first; ties keep
+    /// reviewer order. Caps are applied from p0 down, so findings moved out
+    /// of p0 count towards the p1 cap.
+    pub fn apply_caps(&self, findings: &mut [Finding], reviewer_id: &str) {
+        let Some(caps) = self.caps.get(reviewer_id) else {
+            return;
+        };
+        for (priority, lower) in [(Priority::P0, Priority::P1), (Priority::P1, Priority::P2)] {
+            let Some(&max) = caps.get(&priority) else {
+                continue;
+            };
+            let mut at_priority: Vec<usize> = (0..findings.len())
+                .filter(|&i| findings[i].priority == priority)
+                .collect();
+            at_priority.sort_by_key(|&i| confidence_rank(findings[i].confidence));
+            let reason = format!("cap: at most {} {} for {}", max, priority, reviewer_id);
+            for &i in at_priority.iter().skip(max) {
+                adjust(&mut findings[i], lower, &reason);
+            }
+        }
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, finding: &Finding, reviewer_id: &str, model: &str) -> bool {
+        let path = finding.file.strip_prefix("./").unwrap_or(&finding.file);
+        self.rule
+            .reviewer
+            .as_deref()
+            .is_none_or(|r| r == reviewer_id)
+            && self.rule.model.as_deref().is_none_or(|m| m == model)
+            && (self.rule.from.is_empty() || self.rule.from.contains(&finding.priority))
+            && self
+                .finding_type
+                .as_ref()
+                .is_none_or(|re| re.is_match(&finding.finding_type))
+            && self.path.as_ref().is_none_or(|m| m.is_match(path))
+    }
+}
+
+/// Default reason for a rule, e.g. "rule 2 (type=^style$, path=tests/**)"
+fn describe(n: usize, rule: &CalibrationRule) -> String {
+    let mut conditions = Vec::new();
+    if let Some(reviewer) = &rule.reviewer {
+        conditions.push(format!("reviewer={}", reviewer));
+    }
+    if let Some(finding_type) = &rule.finding_type {
+        conditions.push(format!("type={}", finding_type));
+    }
+    if let Some(path) = &rule.path {
+        conditions.push(format!("path={}", path));
+    }
+    if let Some(model) = &rule.model {
+        conditions.push(format!("model={}", model));
+    }
+    if !rule.from.is_empty() {
+        let from: Vec<String> = rule.from.iter().map(|p| p.to_string()).collect();
+        conditions.push(format!("from={}", from.join("|")));
+    }
+    format!("rule {} ({})", n, conditions.join(", "))
+}
+
+fn adjust(finding: &mut Finding, to: Priority, reason: &str) {
+    if finding.priority == to {
+        return;
+    }
+    finding.calibration.push(PriorityAdjustment {
+        from: finding.priority,
+        to,
+        reason: reason.to_string(),
+    });
+    finding.priority = to;
+}
+
+fn confidence_rank(confidence: Option<Confidence>) -> u8 {
+    match confidence {
+        Some(Confidence::High) => 0,
+        Some(Confidence::Medium) | None => 1,
+        Some(Confidence::Low) => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file: &str, finding_type: &str, priority: Priority) -> Finding {
+        Finding {
+            id: format!("{}:{}", file, finding_type),
+            title: finding_type.to_string(),
+            finding_type: finding_type.to_string(),
+            file: file.into(),
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn calibration(yaml: &str) -> Calibration {
+        let config: CalibrationConfig = serde_yaml::from_str(yaml).unwrap();
+        Calibration::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_apply_rules() {
+        let calibration = calibration(
+            r#"
+rules:
+  - path: "tests/**"
+    priority: p2
+  - type: "^(style|naming)$"
+    priority: p2
+    reason: style is never urgent
+  - model: gpt-5-codex
+    from: [p0]
+    priority: p1
+"#,
+        );
+        let mut findings = vec![
+            finding("./tests/test_db.py", "sql-injection", Priority::P0),
+            finding("src/app.py", "style", Priority::P1),
+            finding("src/app.py", "sql-injection", Priority::P0),
+            finding("src/app.py", "race", Priority::P2),
+            finding("src/app.py", "stylesheet", Priority::P2),
+        ];
+        calibration.apply_rules(&mut findings, "security", "gpt-5-codex");
+
+        let priorities: Vec<Priority> = findings.iter().map(|f| f.priority).collect();
+        assert_eq!(
+            priorities,
+            vec![
+                Priority::P2,
+                Priority::P2,
+                Priority::P1,
+                Priority::P2,
+                Priority::P2
+            ]
+        );
+        assert_eq!(
+            findings[0].calibration,
+            vec![PriorityAdjustment {
+                from: Priority::P0,
+                to: Priority::P2,
+                reason: "rule 1 (path=tests/**)".to_string(),
+            }]
+        );
+        assert_eq!(
+            findings[1].calibration[0].to_string(),
+            "p1 → p2 (style is never urgent)"
+        );
+        assert_eq!(
+            findings[2].calibration[0].reason,
+            "rule 3 (model=gpt-5-codex, from=p0)"
+        );
+        // Already at the rule's priority, or unmatched: nothing recorded
+        assert!(findings[3].calibration.is_empty());
+        assert!(findings[4].calibration.is_empty());
+
+        let mut other = vec![finding("src/app.py", "sql-injection", Priority::P0)];
+        calibration.apply_rules(&mut other, "security", "opus");
+        assert_eq!(other[0].priority, Priority::P0);
+    }
+
+    #[test]
+    fn test_apply_caps() {
+        let calibration = calibration(
+            r#"
+caps:
+  codex-security: {p0: 1, p1: 2}
+"#,
+        );
+        let mut findings = vec![
+            finding("a.py", "a", Priority::P0),
+            finding("b.py", "b", Priority::P0),
+            finding("c.py", "c", Priority::P1),
+            finding("d.py", "d", Priority::P1),
+        ];
+        findings[1].confidence = Some(Confidence::High);
+
+        let mut uncapped = findings.clone();
+        calibration.apply_caps(&mut uncapped, "claude-security");
+        assert!(uncapped.iter().all(|f| f.calibration.is_empty()));
+
+        calibration.apply_caps(&mut findings, "codex-security");
+        let priorities: Vec<Priority> = findings.iter().map(|f| f.priority).collect();
+        assert_eq!(
+            priorities,
+            vec![Priority::P1, Priority::P0, Priority::P1, Priority::P2]
+        );
+        // a.py moved out of p0 (b.py is more confident) and takes a p1 slot
+        assert_eq!(findings[0].calibration.len(), 1);
+        assert_eq!(
+            findings[3].calibration[0].reason,
+            "cap: at most 2 p1 for codex-security"
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let config: CalibrationConfig =
+            serde_yaml::from_str("rules: [{type: '(', priority: p2}]").unwrap();
+        assert!(Calibration::from_config(&config).is_err());
+        let config: CalibrationConfig = serde_yaml::from_str("rules: [{priority: p2}]").unwrap();
+        assert!(Calibration::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! CLI handler for the `apply` subcommand
+//!
+//! Applies `suggested_patch` diffs from findings onto a new git branch,
+//! one commit per finding.
+
+use crate::cli::issue::load_report_findings;
+use crate::cli::ApplyArgs;
+use crate::config::{Config, Priority};
+use crate::parser::Finding;
+use crate::patch::{apply_patch, git, patch_applies};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+pub fn execute(args: ApplyArgs) -> Result<()> {
+    if args.fingerprints.is_empty() && !args.all {
+        anyhow::bail!("Specify finding fingerprints to apply, or --all");
+    }
+
+    // Load config if exists (for the review target)
+    let config = if args.config.exists() {
+        Config::load(&args.config)?
+    } else {
+        Config::default()
+    };
+    let target = config.target.as_path();
+
+    let priorities = args
+        .priority
+        .as_ref()
+        .map(|list| {
+            list.iter()
+                .map(|p| p.parse::<Priority>().map_err(anyhow::Error::msg))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    let findings = load_report_findings(&args.report_dir)?;
+    let selected = select_findings(
+        &findings,
+        &args.fingerprints,
+        args.all,
+        priorities.as_deref(),
+    );
+
+    if selected.is_empty() {
+        println!("No findings with suggested patches matched.");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        println!("Patches selected ({}):", selected.len());
+        for (fingerprint, finding) in &selected {
+            let patch = finding.suggested_patch.as_deref().unwrap_or_default();
+            let status = match patch_applies(target, patch) {
+                Ok(true) => "applies",
+                Ok(false) => "does not apply",
+                Err(_) => "could not check",
+            };
+            println!(
+                "  {} [{}] {} - {} ({})",
+                fingerprint,
+                finding.priority,
+                finding.title,
+                finding.location(),
+                status
+            );
+        }
+        return Ok(());
+    }
+
+    // Patches are committed one by one, so don't mix them with local edits
+    let dirty = git(target, &["status", "--porcelain", "--untracked-files=no"])?;
+    if !dirty.is_empty() {
+        anyhow::bail!("Working tree has uncommitted changes; commit or stash them first");
+    }
+
+    let original = git(target, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    let branch = args.branch.clone().unwrap_or_else(|| {
+        format!(
+            "polyrev/fixes-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )
+    });
+    let (applied, failed) = apply_on_branch(target, &original, &branch, &selected)?;
+    if applied == 0 {
+        anyhow::bail!("None of the {} selected patches applied", selected.len());
+    }
+
+    println!(
+        "Applied {} patches on branch {} ({} failed)",
+        applied, branch, failed
+    );
+    Ok(())
+}
+
+/// Create `branch` and commit each patch on it, returning (applied, failed)
+///
+/// If a commit fails or nothing applies, the working tree is reset and
+/// `original` checked out again; a branch without commits is deleted.
+fn apply_on_branch(
+    target: &std::path::Path,
+    original: &str,
+    branch: &str,
+    selected: &[(String, &Finding)],
+) -> Result<(usize, usize)> {
+    git(target, &["checkout", "-b", branch])
+        .with_context(|| format!("Failed to create branch {}", branch))?;
+    info!("Created branch {} from {}", branch, original);
+
+    let mut applied = 0;
+    let mut failed = 0;
+    let result = commit_patches(target, selected, &mut applied, &mut failed);
+
+    if result.is_err() || applied == 0 {
+        // Drop a half-applied patch and go back to where we started
+        if let Err(e) = git(target, &["reset", "-q", "--hard"]) {
+            warn!("Failed to reset {}: {}", branch, e);
+        }
+        git(target, &["checkout", "-q", original])?;
+        if applied == 0 {
+            // Nothing landed; don't leave an empty branch behind
+            git(target, &["branch", "-D", branch])?;
+        }
+    }
+    result.with_context(|| {
+        format!(
+            "Stopped after {} patches (kept on branch {}); back on {}",
+            applied, branch, original
+        )
+    })?;
+    Ok((applied, failed))
+}
+
+/// Apply and commit each patch on the current branch, counting successes and failures
+fn commit_patches(
+    target: &std::path::Path,
+    selected: &[(String, &Finding)],
+    applied: &mut usize,
+    failed: &mut usize,
+) -> Result<()> {
+    for (fingerprint, finding) in selected {
+        let patch = finding.suggested_patch.as_deref().unwrap_or_default();
+        if let Err(e) = apply_patch(target, patch) {
+            warn!(
+                "Patch for {} ({}) does not apply: {}",
+                finding.title, fingerprint, e
+            );
+            *failed += 1;
+            continue;
+        }
+
+        let message = format!(
+            "Fix: {}\n\n{}\n\npolyrev fingerprint: {}",
+            finding.title,
+            finding.location(),
+            fingerprint
+        );
+        git(target, &["commit", "-q", "-m", &message])?;
+        info!("Applied: {} ({})", finding.title, fingerprint);
+        *applied += 1;
+    }
+    Ok(())
+}
+
+/// Pick findings with a suggested patch by fingerprint, or all (optionally by priority)
+///
+/// Reduced findings also match the fingerprints of the findings merged into
+/// them, so fingerprints from a run's `report.md` still work after postprocess.
+/// Returns `(fingerprint, finding)` pairs, one per fingerprint.
+fn select_findings<'a>(
+    findings: &'a [(String, Finding)],
+    fingerprints: &[String],
+    all: bool,
+    priorities: Option<&[Priority]>,
+) -> Vec<(String, &'a Finding)> {
+    let mut seen = HashSet::new();
+
+    findings
+        .iter()
+        .filter(|(_, f)| f.suggested_patch.is_some())
+        .filter_map(|(reviewer_id, f)| {
+            let fingerprint = f.fingerprint(reviewer_id);
+            let wanted = if all {
+                priorities.map(|p| p.contains(&f.priority)).unwrap_or(true)
+            } else {
+                let legacy = f.legacy_fingerprint(reviewer_id);
+                fingerprints
+                    .iter()
+                    .any(|fp| *fp == fingerprint || *fp == legacy || f.merged_from.contains(fp))
+            };
+            (wanted && seen.insert(fingerprint.clone())).then_some((fingerprint, f))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, priority: Priority, patch: bool) -> (String, Finding) {
+        let finding = Finding {
+            id: title.to_string(),
+            title: title.to_string(),
+            priority,
+            file: PathBuf::from(format!("{}.py", title)),
+            line: 1,
+            suggested_patch: patch.then(|| "--- a/x\n+++ b/x\n".to_string()),
+            ..Default::default()
+        };
+        ("sec".to_string(), finding)
+    }
+
+    #[test]
+    fn test_select_by_fingerprint_and_priority() {
+        let findings = vec![
+            finding("a", Priority::P0, true),
+            finding("b", Priority::P1, true),
+            finding("c", Priority::P0, false),
+        ];
+
+        let fp = findings[1].1.fingerprint("sec");
+        let picked = select_findings(&findings, std::slice::from_ref(&fp), false, None);
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].0, fp);
+
+        let p0 = select_findings(&findings, &[], true, Some(&[Priority::P0]));
+        assert_eq!(p0.len(), 1);
+        assert_eq!(p0[0].1.title, "a");
+
+        assert_eq!(select_findings(&findings, &[], true, None).len(), 2);
+    }
+
+    #[test]
+    fn test_select_reduced_by_merged_fingerprint() {
+        let (_, raw) = finding("a", Priority::P0, true);
+        let raw_fp = raw.fingerprint("sec");
+        let reduced = Finding {
+            merged_from: vec!["0123abcd".to_string(), raw_fp.clone()],
+            ..raw
+        };
+        let findings = vec![("reduced".to_string(), reduced)];
+
+        let picked = select_findings(&findings, &[raw_fp], false, None);
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].0, findings[0].1.fingerprint("reduced"));
+    }
+
+    #[test]
+    fn test_failed_commit_restores_original_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path();
+        std::fs::write(target.join("a.py"), "x = 1\n").unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "dev@example.com"],
+            &["config", "user.name", "dev"],
+            &["add", "a.py"],
+            &["commit", "-q", "-m", "init"],
+        ] {
+            git(target, args).unwrap();
+        }
+        // Reject every commit
+        let hook = target.join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let (_, mut fix) = finding("a", Priority::P0, true);
+        fix.suggested_patch =
+            Some("--- a/a.py\n+++ b/a.py\n@@ -1 +1 @@\n-x = 1\n+x = 2\n".to_string());
+        let selected = vec![("fp".to_string(), &fix)];
+
+        assert!(apply_on_branch(target, "main", "polyrev/fixes", &selected).is_err());
+        assert_eq!(
+            git(target, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap(),
+            "main"
+        );
+        assert!(git(target, &["status", "--porcelain"]).unwrap().is_empty());
+        assert!(git(target, &["branch", "--list", "polyrev/fixes"])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! CLI handler for the `check` subcommand
+//!
+//! Creates a GitHub check run on the head commit with findings as annotations.
+
+use crate::cli::issue::load_report_findings;
+use crate::cli::CheckArgs;
+use crate::config::Config;
+use crate::github::{create_api_client, CheckRun};
+use crate::output::SourceRevision;
+use crate::patch::git;
+use anyhow::Context;
+use tracing::info;
+
+pub async fn execute(args: CheckArgs) -> anyhow::Result<()> {
+    // Load config if exists (for GitHub settings and target)
+    let config = if args.config.exists() {
+        Config::load(&args.config)?
+    } else {
+        Config::default()
+    };
+
+    let head_sha = match args.sha.clone() {
+        Some(sha) => sha,
+        None => git(&config.target, &["rev-parse", "HEAD"])
+            .context("Failed to resolve HEAD; pass --sha")?,
+    };
+
+    // The verify stage refuted these; they neither annotate nor fail the check
+    let (refuted, findings): (Vec<_>, Vec<_>) = load_report_findings(&args.report_dir)?
+        .into_iter()
+        .map(|(_, finding)| finding)
+        .partition(|finding| finding.is_refuted());
+    if !refuted.is_empty() {
+        info!("{} findings refuted by verify are left out", refuted.len());
+    }
+    let summary_md =
+        std::fs::read_to_string(args.report_dir.join("summary.md")).unwrap_or_default();
+
+    // Findings are relative to the target, annotations to the repository root
+    let prefix = SourceRevision::of_report(&args.report_dir, &config.target)
+        .map(|revision| revision.prefix)
+        .unwrap_or_default();
+
+    let run = CheckRun::new(&args.name, &head_sha, &findings, &summary_md, &prefix);
+
+    if args.dry_run {
+        println!(
+            "DRY RUN - check '{}' on {}: {} ({})",
+            run.name, run.head_sha, run.conclusion, run.title
+        );
+        for annotation in &run.annotations {
+            println!(
+                "  [{}] {}:{} {}",
+                annotation.annotation_level,
+                annotation.path,
+                annotation.start_line,
+                annotation.title
+            );
+        }
+        return Ok(());
+    }
+
+    let repo = args.repo.clone().or_else(|| config.github.repo.clone());
+    let client = create_api_client(&config.github, repo)?;
+    let url = run.publish(&client).await?;
+    println!("Check run '{}' ({}): {}", run.name, run.conclusion, url);
+
+    Ok(())
+}
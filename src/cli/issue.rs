@@ -1,141 +1,562 @@
-use crate::cli::IssueArgs;
-use crate::config::Config;
-use crate::github::{IssueCreator, IssueResult};
+use crate::cli::{ClusterMode, IssueArgs};
+use crate::config::{Config, TrackerKind};
+use crate::output::{Permalinks, SourceRevision, SummaryReport};
+use crate::patch::git;
+use crate::postprocess::FindingCluster;
+use crate::tracker::cluster::{group_findings, ClusterGroup, ClusterKey};
+use crate::tracker::policy::IssuePolicy;
+use crate::tracker::sync::{sync_reviewer, SyncRun, SyncState};
+use crate::tracker::template::IssueTemplate;
+use crate::tracker::{
+    create_tracker, ClusterMember, IssueCreator, IssueResult, IssueSettings, IssueTracker,
+};
+use anyhow::Context;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::Arc;
 use tracing::{info, warn};
 
 pub async fn execute(args: IssueArgs) -> anyhow::Result<()> {
-    // Load config (for GitHub settings)
+    // Load config (for tracker settings)
     let config = Config::load(&args.config)?;
 
     // Determine repo - CLI arg takes precedence, then config
-    let repo = args.repo.clone().or_else(|| config.github.repo.clone());
-    if repo.is_none() && !args.dry_run {
+    let repo = args.repo.clone().or_else(|| match config.tracker {
+        TrackerKind::Github => config.github.repo.clone(),
+        TrackerKind::Gitlab => config.gitlab.project.clone(),
+        TrackerKind::Jira => config.jira.project.clone(),
+        TrackerKind::Linear => config.linear.team.clone(),
+    });
+    if repo.is_none() && !args.dry_run && config.tracker == TrackerKind::Github {
         anyhow::bail!(
             "No GitHub repository specified. Use --repo owner/repo or set github.repo in config"
         );
     }
 
+    if args.sync && !args.files.is_empty() {
+        anyhow::bail!(
+            "--sync needs --report-dir (it reads the run's summary.json), not finding files"
+        );
+    }
+
     // Load findings from files or scan directory
     let findings = if !args.files.is_empty() {
         // Load specific files
         info!("Loading findings from {} specified files", args.files.len());
         load_findings_from_files(&args.files)?
     } else {
-        // Check for reduced.json first (postprocessed findings)
-        let reduced_path = args.report_dir.join("reduced.json");
-        if reduced_path.exists() {
-            info!("Loading reduced findings from {:?}", reduced_path);
-            load_reduced_findings(&reduced_path)?
-        } else {
-            // Fall back to scanning for raw .findings.json files
-            info!("Scanning {:?} for findings", args.report_dir);
-            scan_findings_dir(&args.report_dir)?
-        }
+        load_report_findings(&args.report_dir)?
     };
 
     if findings.is_empty() {
         info!("No findings to create issues for");
-        return Ok(());
+        if !args.sync {
+            return Ok(());
+        }
     }
 
     info!("Found {} findings to process", findings.len());
 
+    // Filing policy: priority threshold, cap on new issues, routing
+    let policy = IssuePolicy::from_config(&config.issue_policy, &config.target)?;
+    let mut order = policy.order(&findings);
+    if order.len() < findings.len() {
+        info!(
+            "{} findings below the policy's min_priority will not be filed",
+            findings.len() - order.len()
+        );
+    }
+    // The verify stage refuted these; their existing issues are left alone
+    let refuted = findings.iter().filter(|(_, f)| f.is_refuted()).count();
+    if refuted > 0 {
+        order.retain(|&i| !findings[i].1.is_refuted());
+        info!("{} findings refuted by verify will not be filed", refuted);
+    }
+
+    // Clusters come from reduced.json, whose findings are the ones just loaded
+    let from_report = args.files.is_empty() && args.report_dir.join("reduced.json").exists();
+    let mut clusters = if from_report {
+        load_cluster_groups(&args.report_dir)?
+    } else {
+        vec![]
+    };
+    if args.clusters.is_some() && !from_report {
+        warn!("--clusters needs reduced.json in --report-dir; filing findings individually");
+    }
+    for cluster in &mut clusters {
+        cluster
+            .members
+            .retain(|&i| policy.files(findings[i].1.priority) && !findings[i].1.is_refuted());
+    }
+    clusters.retain(|c| !c.members.is_empty());
+    // Cluster of each finding, for issue templates
+    let cluster_of: HashMap<usize, &ClusterGroup> = clusters
+        .iter()
+        .flat_map(|c| c.members.iter().map(move |&i| (i, c)))
+        .collect();
+    // Clusters filed as their own issues (--clusters)
+    let filed_clusters: &[ClusterGroup] = if args.clusters.is_some() {
+        &clusters
+    } else {
+        &[]
+    };
+    let queue = filing_queue(
+        &order,
+        filed_clusters,
+        args.clusters == Some(ClusterMode::Inline),
+    );
+
     if args.dry_run {
         info!("DRY RUN - previewing issues:");
-        for (reviewer_id, finding) in &findings {
+        if let Some(max) = policy.max_new_issues() {
+            println!("  At most {} new issues, in this order:", max);
+        }
+        for filing in &queue {
+            let i = match *filing {
+                Filing::Finding(i) => i,
+                Filing::Cluster(c) => {
+                    let cluster = &filed_clusters[c];
+                    println!(
+                        "  Cluster: {} ({} findings)",
+                        cluster.name,
+                        cluster.members.len()
+                    );
+                    for &i in &cluster.members {
+                        println!("    - {}", findings[i].1.title);
+                    }
+                    println!("    Fingerprint: {}", cluster.fingerprint);
+                    println!();
+                    continue;
+                }
+            };
+            let (reviewer_id, finding) = &findings[i];
             println!(
-                "  [{}] {} - {}:{}",
+                "  [{}] {} - {}",
                 finding.priority,
                 finding.title,
-                finding.file.display(),
-                finding.line
+                finding.location()
             );
             println!("    Reviewer: {}", reviewer_id);
             println!("    Fingerprint: {}", finding.fingerprint(reviewer_id));
+            let routing = policy.route(reviewer_id, finding);
+            if let Some(repo) = &routing.repo {
+                println!("    Repo: {}", repo);
+            }
+            if let Some(milestone) = &routing.milestone {
+                println!("    Milestone: {}", milestone);
+            }
+            if !routing.owners.is_empty() {
+                println!("    Owners: {}", routing.owners.join(", "));
+            }
             println!();
         }
+        if args.sync {
+            sync_issues(&args, &config, create_tracker(&config, repo)?, &findings).await?;
+        }
         return Ok(());
     }
 
-    let github_cfg = &config.github;
+    let settings = IssueSettings::from_config(&config);
 
-    if !github_cfg.enabled && !args.dry_run {
+    if !settings.enabled {
         anyhow::bail!("GitHub issue creation is disabled in config. Set github.enabled: true");
     }
 
-    // Create issue creator using config github settings
-    let creator = IssueCreator::new(
-        repo,
-        github_cfg.dedupe && !args.force,
-        github_cfg.dedupe_action,
-        if github_cfg.labels.is_empty() {
-            vec!["polyrev".to_string(), "automated-review".to_string()]
+    // Create issue creator using the configured tracker's settings
+    let agent = settings.auto_fix.agent.clone();
+    let template = IssueTemplate::load(&config.github.template, &config.target)?.map(Arc::new);
+    let permalinks = reviewed_revision(&args, &config)
+        .and_then(|rev| Permalinks::from_config(&config, repo.as_deref(), &rev));
+    let labels = if settings.labels.is_empty() {
+        vec!["polyrev".to_string(), "automated-review".to_string()]
+    } else {
+        settings.labels.clone()
+    };
+    let new_creator = |tracker: Arc<dyn IssueTracker>| {
+        IssueCreator::new(
+            tracker,
+            settings.dedupe && !args.force,
+            settings.dedupe_action,
+            labels.clone(),
+            settings.assignees.clone(),
+            settings.auto_fix.clone(),
+            config.providers.claude_cli.model.clone(),
+        )
+        .map(|creator| {
+            creator
+                .with_template(template.clone())
+                .with_permalinks(permalinks.clone())
+        })
+    };
+    let tracker = create_tracker(&config, repo)?;
+    let creator = new_creator(tracker.clone())?;
+    // Creators for reviewers whose policy files issues elsewhere, by repo
+    let mut repo_creators: HashMap<String, IssueCreator> = HashMap::new();
+    let capped = |tally: &Tally| {
+        policy
+            .max_new_issues()
+            .is_some_and(|max| tally.opened >= max)
+    };
+
+    let mut tally = Tally::default();
+    // Issue each finding was filed as (or deduped to), for cluster task lists
+    let mut filed: HashMap<usize, String> = HashMap::new();
+
+    for filing in &queue {
+        let i = match *filing {
+            Filing::Finding(i) => i,
+            Filing::Cluster(c) => {
+                let cluster = &filed_clusters[c];
+                let members: Vec<ClusterMember> = cluster
+                    .members
+                    .iter()
+                    .map(|&i| ClusterMember {
+                        reviewer_id: &findings[i].0,
+                        finding: &findings[i].1,
+                        issue: filed.get(&i).cloned(),
+                    })
+                    .collect();
+                // Past the cap, an existing cluster issue is still updated
+                let result = if capped(&tally) {
+                    match creator
+                        .update_cluster_only(cluster, &members)
+                        .await
+                        .transpose()
+                    {
+                        Some(result) => result,
+                        None => {
+                            tally.deferred += 1;
+                            continue;
+                        }
+                    }
+                } else {
+                    creator.create_or_update_cluster(cluster, &members).await
+                };
+                tally.record(&cluster.name, result, &agent);
+                continue;
+            }
+        };
+        let (reviewer_id, finding) = &findings[i];
+        let routing = policy.route(reviewer_id, finding);
+        let creator = match &routing.repo {
+            Some(repo) => {
+                if !repo_creators.contains_key(repo) {
+                    let tracker = create_tracker(&config, Some(repo.clone()))?;
+                    repo_creators.insert(repo.clone(), new_creator(tracker)?);
+                }
+                &repo_creators[repo]
+            }
+            None => &creator,
+        };
+
+        // Past the cap, existing issues are still updated but nothing new is opened
+        let result = if capped(&tally) {
+            match creator.update_only(finding, reviewer_id).await.transpose() {
+                Some(result) => result,
+                None => {
+                    tally.deferred += 1;
+                    continue;
+                }
+            }
         } else {
-            github_cfg.labels.clone()
-        },
-        github_cfg.assignees.clone(),
-        github_cfg.auto_fix.clone(),
-        config.providers.claude_cli.model.clone(),
-    )?;
-
-    let mut created = 0;
-    let mut skipped = 0;
-    let mut errors = 0;
-    let mut agents_triggered = 0;
-
-    for (reviewer_id, finding) in &findings {
-        match creator.create_or_update(finding, reviewer_id).await {
-            Ok(IssueResult::Created { url, agent_triggered }) => {
+            creator
+                .create_or_update(finding, reviewer_id, &routing, cluster_of.get(&i).copied())
+                .await
+        };
+        if let Some(issue) = tally.record(&finding.title, result, &agent) {
+            filed.insert(i, issue);
+        }
+    }
+
+    if tally.agents_triggered > 0 {
+        info!(
+            "Done: {} created, {} skipped, {} errors, {} agents triggered",
+            tally.created, tally.skipped, tally.errors, tally.agents_triggered
+        );
+    } else {
+        info!(
+            "Done: {} created, {} skipped, {} errors",
+            tally.created, tally.skipped, tally.errors
+        );
+    }
+    if tally.deferred > 0 {
+        info!(
+            "{} new issues deferred by max_new_issues; they are filed on a later run",
+            tally.deferred
+        );
+    }
+
+    if args.sync {
+        sync_issues(&args, &config, tracker, &findings).await?;
+    }
+
+    if tally.errors > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// One issue to file, in filing order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Filing {
+    /// Index into the findings
+    Finding(usize),
+    /// Index into the filed clusters
+    Cluster(usize),
+}
+
+/// Interleave filed clusters with the policy's finding order
+///
+/// Each cluster is filed where its most urgent member comes up, so
+/// `max_new_issues` doesn't starve clusters behind lower-priority findings.
+/// Its members come right before it (their issues are linked from the
+/// cluster's), or not at all when inlined into the cluster issue.
+fn filing_queue(order: &[usize], clusters: &[ClusterGroup], inline: bool) -> Vec<Filing> {
+    let cluster_of: HashMap<usize, usize> = clusters
+        .iter()
+        .enumerate()
+        .flat_map(|(c, cluster)| cluster.members.iter().map(move |&i| (i, c)))
+        .collect();
+
+    let mut queue = Vec::new();
+    let mut queued: HashSet<Filing> = HashSet::new();
+    for &i in order {
+        let Some(&c) = cluster_of.get(&i) else {
+            queue.push(Filing::Finding(i));
+            continue;
+        };
+        if !queued.insert(Filing::Cluster(c)) {
+            continue;
+        }
+        if !inline {
+            for &member in &clusters[c].members {
+                if queued.insert(Filing::Finding(member)) {
+                    queue.push(Filing::Finding(member));
+                }
+            }
+        }
+        queue.push(Filing::Cluster(c));
+    }
+    queue
+}
+
+/// Issue outcomes for the final summary line
+#[derive(Default)]
+struct Tally {
+    created: usize,
+    /// New issues opened (the subset of `created` counted by `max_new_issues`)
+    opened: usize,
+    /// Findings not filed because `max_new_issues` was reached
+    deferred: usize,
+    skipped: usize,
+    errors: usize,
+    agents_triggered: usize,
+}
+
+impl Tally {
+    /// Log and count one result, returning the issue it landed on
+    fn record(
+        &mut self,
+        title: &str,
+        result: Result<IssueResult, crate::error::TrackerError>,
+        agent: &str,
+    ) -> Option<String> {
+        match result {
+            Ok(IssueResult::Created {
+                url,
+                agent_triggered,
+            }) => {
                 if agent_triggered {
-                    info!("Created: {} -> {} (triggered @{})", finding.title, url, github_cfg.auto_fix.agent);
-                    agents_triggered += 1;
+                    info!("Created: {} -> {} (triggered @{})", title, url, agent);
+                    self.agents_triggered += 1;
                 } else {
-                    info!("Created: {} -> {}", finding.title, url);
+                    info!("Created: {} -> {}", title, url);
                 }
-                created += 1;
+                self.created += 1;
+                self.opened += 1;
+                Some(url)
             }
-            Ok(IssueResult::Skipped { issue_number }) => {
-                info!(
-                    "Skipped (duplicate): {} -> #{}",
-                    finding.title, issue_number
-                );
-                skipped += 1;
+            Ok(IssueResult::Skipped { issue }) => {
+                info!("Skipped (duplicate): {} -> {}", title, issue);
+                self.skipped += 1;
+                Some(issue)
             }
-            Ok(IssueResult::Commented { issue_number }) => {
-                info!("Commented: {} -> #{}", finding.title, issue_number);
-                created += 1;
+            Ok(IssueResult::Commented { issue }) => {
+                info!("Commented: {} -> {}", title, issue);
+                self.created += 1;
+                Some(issue)
             }
-            Ok(IssueResult::Reopened { issue_number }) => {
-                info!("Reopened: {} -> #{}", finding.title, issue_number);
-                created += 1;
+            Ok(IssueResult::Reopened { issue }) => {
+                info!("Reopened: {} -> {}", title, issue);
+                self.created += 1;
+                Some(issue)
             }
             Err(e) => {
-                warn!("Failed to create issue for {}: {}", finding.title, e);
-                errors += 1;
+                warn!("Failed to create issue for {}: {}", title, e);
+                self.errors += 1;
+                None
             }
         }
     }
+}
 
-    if agents_triggered > 0 {
-        info!(
-            "Done: {} created, {} skipped, {} errors, {} agents triggered",
-            created, skipped, errors, agents_triggered
+/// Close issues whose findings a full run no longer reports (`--sync`)
+async fn sync_issues(
+    args: &IssueArgs,
+    config: &Config,
+    tracker: Arc<dyn IssueTracker>,
+    findings: &[(String, crate::parser::Finding)],
+) -> anyhow::Result<()> {
+    let summary_path = args.report_dir.join("summary.json");
+    let content = std::fs::read_to_string(&summary_path)
+        .with_context(|| format!("--sync needs {:?} from a polyrev run", summary_path))?;
+    let summary: SummaryReport = serde_json::from_str(&content)?;
+
+    if let Some(diff_base) = &summary.diff_base {
+        warn!(
+            "Skipping sync: this was a --diff-base {} run, which only reviewed changed files",
+            diff_base
         );
+        return Ok(());
+    }
+
+    // Only reviewers that completed a full run can vouch for a finding being gone;
+    // reduced findings merge all reviewers, so every reviewer must have completed
+    let completed: Vec<String> = summary
+        .reviewers
+        .iter()
+        .filter(|r| r.status == "completed")
+        .map(|r| r.id.clone())
+        .collect();
+    let reviewers = if args.report_dir.join("reduced.json").exists() {
+        if completed.is_empty() || completed.len() < summary.reviewers.len() {
+            warn!("Skipping sync: not every reviewer completed, so reduced findings are partial");
+            return Ok(());
+        }
+        vec!["reduced".to_string()]
     } else {
-        info!(
-            "Done: {} created, {} skipped, {} errors",
-            created, skipped, errors
+        completed
+    };
+
+    let mut reported: HashSet<String> = findings
+        .iter()
+        .flat_map(|(reviewer_id, f)| {
+            [
+                f.fingerprint(reviewer_id),
+                f.legacy_fingerprint(reviewer_id),
+            ]
+        })
+        .collect();
+    // Cluster issues stay open while their cluster is still reported
+    if args.files.is_empty() && args.report_dir.join("reduced.json").exists() {
+        reported.extend(
+            load_cluster_groups(&args.report_dir)?
+                .into_iter()
+                .map(|c| c.fingerprint),
         );
     }
+    let commit = git(&config.target, &["rev-parse", "--short", "HEAD"])
+        .unwrap_or_else(|_| format!("the run of {}", summary.timestamp));
+    let run = SyncRun {
+        run_id: &summary.timestamp,
+        commit: &commit,
+        grace_runs: args.grace_runs,
+        dry_run: args.dry_run,
+    };
 
-    if errors > 0 {
-        std::process::exit(1);
+    let mut state = SyncState::load(&config.target);
+    let mut closed = 0;
+    let mut pending = 0;
+    for reviewer in &reviewers {
+        let outcome =
+            sync_reviewer(tracker.as_ref(), &mut state, reviewer, &reported, &run).await?;
+        for issue in &outcome.to_close {
+            if args.dry_run {
+                println!(
+                    "  Would close {} (fingerprint {})",
+                    issue.id, issue.fingerprint
+                );
+            }
+        }
+        for (issue, count) in &outcome.pending {
+            info!(
+                "{} not detected ({}/{} clean runs)",
+                issue.id, count, args.grace_runs
+            );
+        }
+        closed += outcome.to_close.len();
+        pending += outcome.pending.len();
     }
 
+    if !args.dry_run {
+        if let Err(e) = state.save(&config.target) {
+            warn!("Failed to save sync state: {}", e);
+        }
+    }
+
+    info!(
+        "Sync: {} closed, {} within grace period ({} reviewers)",
+        closed,
+        pending,
+        reviewers.len()
+    );
     Ok(())
 }
 
+/// Load findings from a report directory, preferring postprocessed `reduced.json`
+///
+/// Returns `(reviewer_id, finding)` pairs; reduced findings use the reviewer id "reduced".
+pub fn load_report_findings(
+    report_dir: &Path,
+) -> anyhow::Result<Vec<(String, crate::parser::Finding)>> {
+    // Check for reduced.json first (postprocessed findings)
+    let reduced_path = report_dir.join("reduced.json");
+    if reduced_path.exists() {
+        info!("Loading reduced findings from {:?}", reduced_path);
+        load_reduced_findings(&reduced_path)
+    } else {
+        // Fall back to scanning for raw .findings.json files
+        info!("Scanning {:?} for findings", report_dir);
+        scan_findings_dir(report_dir)
+    }
+}
+
+/// The commit findings were produced against, for source permalinks
+///
+/// Prefers the commit recorded in the run's `summary.json`; finding files
+/// given on the command line are assumed to match the target's `HEAD`.
+fn reviewed_revision(args: &IssueArgs, config: &Config) -> Option<SourceRevision> {
+    if args.files.is_empty() {
+        SourceRevision::of_report(&args.report_dir, &config.target)
+    } else {
+        SourceRevision::capture(&config.target)
+    }
+}
+
+/// Resolve the clusters in `reduced.json` against its findings
+///
+/// Member indexes line up with the findings returned by `load_report_findings`.
+fn load_cluster_groups(report_dir: &Path) -> anyhow::Result<Vec<ClusterGroup>> {
+    #[derive(serde::Deserialize)]
+    struct ReducedClusters {
+        #[serde(default)]
+        clusters: Vec<FindingCluster>,
+        #[serde(default)]
+        findings: Vec<ClusterKey>,
+    }
+
+    let path = report_dir.join("reduced.json");
+    let reduced: ReducedClusters = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    let groups = group_findings(&reduced.clusters, &reduced.findings);
+    info!(
+        "Loaded {} clusters ({} with several findings)",
+        reduced.clusters.len(),
+        groups.len()
+    );
+    Ok(groups)
+}
+
 /// Load findings from specific files
 fn load_findings_from_files(
     files: &[std::path::PathBuf],
@@ -245,6 +666,10 @@ fn load_reduced_findings(
 
     #[derive(serde::Deserialize)]
     struct ReducedFinding {
+        #[serde(default)]
+        merged_from: Vec<String>,
+        #[serde(default)]
+        reviewers: Vec<String>,
         #[serde(default)]
         id: String,
         #[serde(default, alias = "type")]
@@ -258,17 +683,41 @@ fn load_reduced_findings(
         #[serde(default)]
         line: u32,
         #[serde(default)]
+        end_line: Option<u32>,
+        #[serde(default)]
+        column: Option<u32>,
+        #[serde(default)]
+        end_column: Option<u32>,
+        #[serde(default)]
         snippet: Option<String>,
         #[serde(default)]
         description: String,
         #[serde(default)]
         remediation: String,
         #[serde(default)]
+        suggested_patch: Option<String>,
+        #[serde(default)]
         acceptance_criteria: Vec<String>,
         #[serde(default)]
         references: Vec<String>,
         #[serde(default)]
+        related_locations: Vec<crate::parser::RelatedLocation>,
+        #[serde(default)]
+        confidence: Option<crate::parser::Confidence>,
+        #[serde(default)]
+        cwe: Vec<String>,
+        #[serde(default)]
+        owasp: Vec<String>,
+        #[serde(default)]
         model: Option<String>,
+        #[serde(default)]
+        anchor: Option<String>,
+        #[serde(default)]
+        votes: Option<crate::parser::Votes>,
+        #[serde(default)]
+        calibration: Vec<crate::parser::PriorityAdjustment>,
+        #[serde(default)]
+        verification: Option<crate::parser::Verification>,
     }
 
     let content = std::fs::read_to_string(path)?;
@@ -286,12 +735,28 @@ fn load_reduced_findings(
                 priority,
                 file: rf.file,
                 line: rf.line,
+                end_line: rf.end_line,
+                column: rf.column,
+                end_column: rf.end_column,
                 snippet: rf.snippet,
                 description: rf.description,
                 remediation: rf.remediation,
+                suggested_patch: rf.suggested_patch,
                 acceptance_criteria: rf.acceptance_criteria,
                 references: rf.references,
+                related_locations: rf.related_locations,
+                confidence: rf.confidence,
+                cwe: rf.cwe,
+                owasp: rf.owasp,
                 model: rf.model,
+                votes: rf.votes,
+                calibration: rf.calibration,
+                anchor: rf.anchor,
+                verified: None,
+                patch_applies: None,
+                verification: rf.verification,
+                merged_from: rf.merged_from,
+                reviewers: rf.reviewers,
             };
             // Use "reduced" as the reviewer_id for postprocessed findings
             ("reduced".to_string(), finding)
@@ -301,3 +766,45 @@ fn load_reduced_findings(
     info!("Loaded {} reduced findings from {:?}", findings.len(), path);
     Ok(findings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cluster(members: &[usize]) -> ClusterGroup {
+        ClusterGroup {
+            name: "Input validation".to_string(),
+            rationale: String::new(),
+            fingerprint: "polyrev-cluster-abc".to_string(),
+            members: members.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_filing_queue_places_clusters_at_their_most_urgent_member() {
+        // Findings 0 and 3 are p0, 1 and 4 p1, 2 p2; the cluster holds 4 and 2
+        let order = [0, 3, 1, 4, 2];
+        let clusters = [cluster(&[2, 4])];
+
+        assert_eq!(
+            filing_queue(&order, &clusters, true),
+            vec![
+                Filing::Finding(0),
+                Filing::Finding(3),
+                Filing::Finding(1),
+                Filing::Cluster(0),
+            ]
+        );
+        assert_eq!(
+            filing_queue(&order, &clusters, false),
+            vec![
+                Filing::Finding(0),
+                Filing::Finding(3),
+                Filing::Finding(1),
+                Filing::Finding(2),
+                Filing::Finding(4),
+                Filing::Cluster(0),
+            ]
+        );
+    }
+}
@@ -1,11 +1,15 @@
+pub mod apply;
+pub mod check;
 pub mod enqueue;
 pub mod init;
 pub mod issue;
 pub mod plan;
 pub mod postprocess;
+pub mod review_pr;
 pub mod run;
 pub mod schema;
 pub mod tui;
+pub mod verify;
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
@@ -40,12 +44,24 @@ pub enum Commands {
     /// Run postprocessing on existing findings (dedupe/cluster)
     Postprocess(PostprocessArgs),
 
+    /// Have a second model confirm, downgrade or refute existing findings
+    Verify(VerifyArgs),
+
     /// Generate task DAG from a spec using parallel planning perspectives
     Plan(PlanArgs),
 
     /// Enqueue tasks from a plan to tandem
     Enqueue(EnqueueArgs),
 
+    /// Create a GitHub check run with findings as annotations
+    Check(CheckArgs),
+
+    /// Publish findings as an inline pull request review (or GitLab merge request discussions)
+    ReviewPr(ReviewPrArgs),
+
+    /// Apply suggested-fix patches from findings on a new git branch
+    Apply(ApplyArgs),
+
     /// Launch interactive TUI for viewing findings and plans
     Tui(TuiArgs),
 
@@ -62,6 +78,25 @@ pub struct PostprocessArgs {
     /// Report directory containing findings
     #[arg(long)]
     pub report_dir: Option<PathBuf>,
+
+    /// Previous reduced.json to keep ids and clusters from (default: latest earlier report)
+    #[arg(long, conflicts_with = "fresh")]
+    pub previous: Option<PathBuf>,
+
+    /// Reduce from scratch, ignoring earlier reports
+    #[arg(long)]
+    pub fresh: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct VerifyArgs {
+    /// Config file
+    #[arg(short, long, default_value = "polyrev.yaml")]
+    pub config: PathBuf,
+
+    /// Report directory containing findings (reduced.json or *.findings.json)
+    #[arg(long)]
+    pub report_dir: Option<PathBuf>,
 }
 
 #[derive(Parser, Clone)]
@@ -105,6 +140,10 @@ pub struct RunArgs {
     /// Create GitHub issues after run completes
     #[arg(long)]
     pub create_issues: bool,
+
+    /// Create a GitHub check run with annotations after run completes
+    #[arg(long)]
+    pub create_check: bool,
 }
 
 #[derive(Parser, Clone)]
@@ -129,9 +168,111 @@ pub struct IssueArgs {
     #[arg(long)]
     pub force: bool,
 
+    /// Override repository (owner/repo), or the GitLab project, Jira project key or Linear team key
+    #[arg(long)]
+    pub repo: Option<String>,
+
+    /// After a full run, close open issues whose findings were not reported
+    #[arg(long)]
+    pub sync: bool,
+
+    /// Consecutive clean full runs before --sync closes an issue
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub grace_runs: u32,
+
+    /// File postprocess clusters as one issue each instead of one issue per finding
+    #[arg(long, value_enum)]
+    pub clusters: Option<ClusterMode>,
+}
+
+/// How `polyrev issue` files postprocess clusters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClusterMode {
+    /// A parent issue per cluster with a task list linking its child issues
+    Epic,
+    /// Only the cluster issue, with its member findings inlined
+    Inline,
+}
+
+#[derive(Parser, Clone)]
+pub struct CheckArgs {
+    /// Reports directory containing findings and summary.md
+    #[arg(long, default_value = "reports")]
+    pub report_dir: PathBuf,
+
+    /// Config file (for GitHub settings)
+    #[arg(long, default_value = "polyrev.yaml")]
+    pub config: PathBuf,
+
     /// Override repository (owner/repo)
     #[arg(long)]
     pub repo: Option<String>,
+
+    /// Commit to attach the check to (default: HEAD of the review target)
+    #[arg(long)]
+    pub sha: Option<String>,
+
+    /// Check run name
+    #[arg(long, default_value = "polyrev")]
+    pub name: String,
+
+    /// Show the conclusion and annotations without creating the check
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct ReviewPrArgs {
+    /// Pull request number (merge request iid on GitLab)
+    #[arg(value_name = "NUMBER")]
+    pub number: u64,
+
+    /// Reports directory to scan (default: reports/)
+    #[arg(long, default_value = "reports")]
+    pub report_dir: PathBuf,
+
+    /// Config file (for GitHub/GitLab settings)
+    #[arg(long, default_value = "polyrev.yaml")]
+    pub config: PathBuf,
+
+    /// Override repository (owner/repo, or GitLab project path)
+    #[arg(long)]
+    pub repo: Option<String>,
+
+    /// Show what would be posted without posting
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct ApplyArgs {
+    /// Fingerprints of findings whose patches to apply
+    #[arg(value_name = "FINGERPRINT")]
+    pub fingerprints: Vec<String>,
+
+    /// Apply every available patch (narrow with --priority)
+    #[arg(long, conflicts_with = "fingerprints")]
+    pub all: bool,
+
+    /// Only apply patches for these priorities (comma-separated, with --all)
+    #[arg(long, value_delimiter = ',', requires = "all")]
+    pub priority: Option<Vec<String>>,
+
+    /// Reports directory to scan (default: reports/)
+    #[arg(long, default_value = "reports")]
+    pub report_dir: PathBuf,
+
+    /// Config file (for the review target)
+    #[arg(short, long, default_value = "polyrev.yaml")]
+    pub config: PathBuf,
+
+    /// Branch to create (default: polyrev/fixes-<timestamp>)
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    /// List the selected patches and whether they apply, without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Parser, Clone)]
@@ -174,8 +315,12 @@ pub struct InitArgs {
 }
 
 #[derive(Parser, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct PlanArgs {
-    /// The spec/feature to plan (can be multiple words)
+    #[command(subcommand)]
+    pub command: Option<PlanCommand>,
+
+    /// The spec/feature to plan (can be multiple words; put `--` first if it starts with "validate")
     #[arg(value_name = "SPEC")]
     pub spec: Vec<String>,
 
@@ -183,10 +328,22 @@ pub struct PlanArgs {
     #[arg(long)]
     pub file: Option<PathBuf>,
 
-    /// Fetch spec from GitHub issue number
+    /// Fetch spec from GitHub issue number (title, body, labels and comments)
     #[arg(long)]
     pub issue: Option<u64>,
 
+    /// Repository of --issue (owner/repo; defaults to github.repo)
+    #[arg(long, requires = "issue")]
+    pub repo: Option<String>,
+
+    /// Don't comment the plan on --issue after saving it
+    #[arg(long, requires = "issue")]
+    pub no_comment: bool,
+
+    /// Create a linked sub-issue of --issue for each task
+    #[arg(long, requires = "issue")]
+    pub sub_issues: bool,
+
     /// Config file
     #[arg(short, long, default_value = "polyrev.yaml")]
     pub config: PathBuf,
@@ -224,6 +381,23 @@ pub struct PlanArgs {
     pub no_enqueue: bool,
 }
 
+#[derive(Subcommand, Clone)]
+pub enum PlanCommand {
+    /// Check a saved plan for cycles, unknown dependencies and missing acceptance criteria
+    Validate(PlanValidateArgs),
+}
+
+#[derive(Parser, Clone)]
+pub struct PlanValidateArgs {
+    /// Path to plan.json file
+    #[arg(value_name = "PLAN")]
+    pub plan: PathBuf,
+
+    /// Print the validation (problems, layers, critical path) as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 #[derive(Parser, Clone)]
 pub struct EnqueueArgs {
     /// Path to plan.json file
@@ -2,13 +2,17 @@
 //!
 //! Runs parallel planning perspectives and reduces to a unified task DAG.
 
-use crate::cli::PlanArgs;
+use crate::cli::{PlanArgs, PlanCommand, PlanValidateArgs};
 use crate::config::Config;
+use crate::github::{create_api_client, PlanIssue};
 use crate::planner::{
-    reduce_plan, revise_plan, select_perspectives, write_fragments, write_plan, PlanOptions,
-    PlanOrchestrator, Perspective, PerspectiveStatus,
+    reduce_plan, revise_plan, select_perspectives, validate_and_repair, validate_plan,
+    write_fragments, write_plan, Perspective, PerspectiveStatus, PlanOptions, PlanOrchestrator,
+    PlanValidation, UnifiedPlan,
 };
+use anyhow::Context;
 use chrono::Local;
+use std::collections::HashMap;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use tracing::info;
@@ -71,6 +75,10 @@ fn default_perspectives() -> Vec<Perspective> {
 }
 
 pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
+    if let Some(PlanCommand::Validate(ref validate_args)) = args.command {
+        return validate(validate_args);
+    }
+
     // Load config if it exists, otherwise use defaults
     let config = if args.config.exists() {
         info!("Loading config from {:?}", args.config);
@@ -88,11 +96,15 @@ pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
     };
 
     // Build spec from args
+    let mut issue_target = None;
     let spec = if let Some(ref file) = args.file {
         std::fs::read_to_string(file)?
-    } else if let Some(ref issue) = args.issue {
-        // TODO: Fetch issue from GitHub
-        format!("GitHub Issue #{}", issue)
+    } else if let Some(number) = args.issue {
+        let repo = args.repo.clone().or_else(|| config.github.repo.clone());
+        let target = PlanIssue::new(create_api_client(&config.github, repo)?, number);
+        let spec = target.fetch_spec().await?.to_spec();
+        issue_target = Some(target);
+        spec
     } else {
         args.spec.join(" ")
     };
@@ -251,13 +263,17 @@ pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
         .and_then(|p| p.reducer_prompt.clone())
         .unwrap_or_else(|| PathBuf::from("prompts/plan/reduce.md"));
 
-    let reduction = reduce_plan(&config, &planning_result, &reducer_prompt).await?;
+    let mut reduction = reduce_plan(&config, &planning_result, &reducer_prompt).await?;
 
     println!(
         "{} suggestions → {} tasks",
         reduction.task_count_before, reduction.task_count_after
     );
 
+    let (plan, mut validation) = validate_and_repair(&config, reduction.plan).await;
+    reduction.plan = plan;
+    print_problems(&validation);
+
     // Collect answers to questions
     let mut answers: Vec<String> = Vec::new();
     if !reduction.plan.questions.is_empty() && !args.yes {
@@ -328,6 +344,9 @@ pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
             match revise_plan(&config, &reduction.plan, &qa_pairs).await {
                 Ok(revised) => {
                     println!("done ({} tasks)", revised.tasks.len());
+                    let (revised, revised_validation) = validate_and_repair(&config, revised).await;
+                    validation = revised_validation;
+                    print_problems(&validation);
                     revised
                 }
                 Err(e) => {
@@ -425,6 +444,27 @@ pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
 
     println!("\n✓ Plan saved to {}", plan_path.display());
     println!("  {} ready, {} blocked", ready, blocked);
+    if validation.critical_path.len() > 1 {
+        println!("  Critical path: {}", validation.critical_path.join(" → "));
+    }
+
+    if let Some(ref target) = issue_target {
+        let sub_issues = if args.sub_issues {
+            let sub_issues = target.create_sub_issues(&final_plan).await?;
+            println!(
+                "  {} tasks tracked as sub-issues of #{}",
+                sub_issues.len(),
+                target.number()
+            );
+            sub_issues
+        } else {
+            HashMap::new()
+        };
+        if !args.no_comment {
+            target.comment_plan(&final_plan, &sub_issues).await?;
+            println!("  Commented plan on #{}", target.number());
+        }
+    }
 
     if !args.no_enqueue {
         println!("\n  To enqueue: polyrev enqueue --plan {}", plan_path.display());
@@ -433,6 +473,50 @@ pub async fn execute(args: PlanArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `polyrev plan validate <plan.json>`
+fn validate(args: &PlanValidateArgs) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(&args.plan)
+        .with_context(|| format!("Failed to read plan: {}", args.plan.display()))?;
+    let plan: UnifiedPlan = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse plan: {}", args.plan.display()))?;
+    let validation = validate_plan(&plan);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&validation)?);
+    } else {
+        println!(
+            "{} tasks in {} layers",
+            plan.tasks.len(),
+            validation.layers.len()
+        );
+        for (i, layer) in validation.layers.iter().enumerate() {
+            println!("  {}. {}", i + 1, layer.join(", "));
+        }
+        if !validation.critical_path.is_empty() {
+            println!("Critical path: {}", validation.critical_path.join(" → "));
+        }
+        print_problems(&validation);
+    }
+
+    if !validation.is_valid() {
+        anyhow::bail!("Plan has {} structural problems", validation.problems.len());
+    }
+    if !args.json {
+        println!("✓ Plan is valid");
+    }
+    Ok(())
+}
+
+fn print_problems(validation: &PlanValidation) {
+    if validation.is_valid() {
+        return;
+    }
+    println!("\n⚠ {} plan problems:", validation.problems.len());
+    for problem in &validation.problems {
+        println!("  - {}", problem);
+    }
+}
+
 /// Sanitize spec into a valid directory name
 fn sanitize_plan_name(spec: &str) -> String {
     let first_line = spec.lines().next().unwrap_or(spec);
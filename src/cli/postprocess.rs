@@ -6,7 +6,13 @@ use tracing::{error, info};
 
 pub async fn execute(args: PostprocessArgs) -> anyhow::Result<()> {
     // Load config
-    let config = Config::load(&args.config)?;
+    let mut config = Config::load(&args.config)?;
+    if args.previous.is_some() {
+        config.postprocess.previous = args.previous;
+    }
+    if args.fresh {
+        config.postprocess.incremental = false;
+    }
 
     // Determine report directory
     let report_dir = if let Some(dir) = args.report_dir {
@@ -30,6 +36,13 @@ pub async fn execute(args: PostprocessArgs) -> anyhow::Result<()> {
                 "Postprocess complete: {} -> {} findings",
                 result.original_count, result.reduced_count
             );
+            if let Some(changelog) = &result.changelog {
+                info!(
+                    "Since {}: {}",
+                    changelog.previous.display(),
+                    changelog.tally()
+                );
+            }
             if !result.clusters.is_empty() {
                 info!("Identified {} clusters", result.clusters.len());
             }
@@ -0,0 +1,92 @@
+//! CLI handler for the `review-pr` subcommand
+//!
+//! Publishes findings from a report directory as a pull request review, or
+//! as merge request discussions when `tracker: gitlab`.
+
+use crate::cli::issue::load_report_findings;
+use crate::cli::ReviewPrArgs;
+use crate::config::{Config, TrackerKind};
+use crate::github::{create_api_client, PullRequestReviewer};
+use crate::gitlab::{GitLabClient, MergeRequestReviewer};
+use crate::output::SourceRevision;
+use crate::tracker::review::{ReviewPlan, ReviewSummary};
+use tracing::info;
+
+pub async fn execute(args: ReviewPrArgs) -> anyhow::Result<()> {
+    // Load config if exists (for GitHub/GitLab settings)
+    let config = if args.config.exists() {
+        Config::load(&args.config)?
+    } else {
+        Config::default()
+    };
+
+    let findings = load_report_findings(&args.report_dir)?;
+    info!("Loaded {} findings", findings.len());
+
+    // Findings are relative to the target, diff paths to the repository root
+    let prefix = SourceRevision::of_report(&args.report_dir, &config.target)
+        .map(|revision| revision.prefix)
+        .unwrap_or_default();
+
+    match config.tracker {
+        // Code reviews stay on GitHub when issues go to Jira or Linear
+        TrackerKind::Github | TrackerKind::Jira | TrackerKind::Linear => {
+            let repo = args.repo.clone().or_else(|| config.github.repo.clone());
+            let client = create_api_client(&config.github, repo)?;
+            let reviewer = PullRequestReviewer::new(client, args.number);
+            let plan = reviewer.plan(&findings, &prefix).await?;
+            if finish_early(&args, "PR #", &plan) {
+                return Ok(());
+            }
+            let summary = reviewer.publish(&plan).await?;
+            print_summary("PR #", args.number, &summary);
+        }
+        TrackerKind::Gitlab => {
+            let project = args.repo.clone().or_else(|| config.gitlab.project.clone());
+            let client = GitLabClient::from_config(&config.gitlab, project)?;
+            let reviewer = MergeRequestReviewer::new(client, args.number);
+            let plan = reviewer.plan(&findings, &prefix).await?;
+            if finish_early(&args, "MR !", &plan.plan) {
+                return Ok(());
+            }
+            let summary = reviewer.publish(&plan).await?;
+            print_summary("MR !", args.number, &summary);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the dry-run preview or "nothing to post"; true if done
+fn finish_early(args: &ReviewPrArgs, prefix: &str, plan: &ReviewPlan) -> bool {
+    if args.dry_run {
+        println!("DRY RUN - review for {}{}:", prefix, args.number);
+        for comment in &plan.new_comments {
+            let title = comment.body.lines().nth(1).unwrap_or_default();
+            println!("  inline {}:{} {}", comment.path, comment.line, title);
+        }
+        for line in &plan.body_findings {
+            println!("  body   {}", line.trim_start_matches("- "));
+        }
+        println!(
+            "  {} existing comments to update, {} to mark resolved",
+            plan.updated.len(),
+            plan.resolved.len()
+        );
+        return true;
+    }
+
+    if plan.is_empty() {
+        println!("Nothing to post for {}{}", prefix, args.number);
+        return true;
+    }
+
+    false
+}
+
+fn print_summary(prefix: &str, number: u64, summary: &ReviewSummary) {
+    println!(
+        "{}{}: {} inline comments, {} in review body, {} updated, {} resolved",
+        prefix, number, summary.inline, summary.in_body, summary.updated, summary.resolved
+    );
+}
@@ -1,10 +1,12 @@
 use crate::cli::RunArgs;
-use crate::config::Config;
+use crate::config::{Config, Priority};
 use crate::output::write_summary;
 use crate::postprocess::run_postprocess;
-use crate::runner::{Orchestrator, RunOptions};
+use crate::runner::{FindingCounts, Orchestrator, RunOptions};
 use crate::state::State;
+use crate::verify::run_verify;
 use chrono::Local;
+use std::path::Path;
 use tracing::{error, info, warn};
 
 pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
@@ -85,7 +87,12 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
     }
 
     // Write summary artifacts
-    if let Err(e) = write_summary(&report_dir, &report, &config.target) {
+    if let Err(e) = write_summary(
+        &report_dir,
+        &report,
+        &config.target,
+        options.diff_base.as_deref(),
+    ) {
         warn!("Failed to write summary: {}", e);
     }
 
@@ -96,6 +103,9 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
                 "Postprocess: {} -> {} findings",
                 result.original_count, result.reduced_count
             );
+            if let Some(changelog) = &result.changelog {
+                info!("Postprocess changes: {}", changelog.tally());
+            }
         }
         Ok(None) => {
             // Postprocess disabled or skipped
@@ -105,15 +115,42 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
         }
     }
 
+    // Optional verify step (second opinion on high-priority findings)
+    let mut totals = report.totals();
+    match run_verify(&config, &report_dir).await {
+        Ok(Some(summary)) => {
+            info!(
+                "Verify: {} confirmed, {} downgraded, {} refuted",
+                summary.confirmed, summary.downgraded, summary.refuted
+            );
+            // Count what verify left: downgraded findings at their new priority, refuted ones not at all
+            match verified_totals(&report_dir) {
+                Ok(verified) => {
+                    totals = FindingCounts {
+                        suppressed: totals.suppressed,
+                        ..verified
+                    }
+                }
+                Err(e) => warn!("Failed to recount verified findings: {}", e),
+            }
+        }
+        Ok(None) => {
+            // Verify disabled
+        }
+        Err(e) => {
+            warn!("Verify step failed: {}", e);
+        }
+    }
+
     // Summary
-    let totals = report.totals();
     info!(
-        "Completed in {:.1}s: {} p0, {} p1, {} p2 findings across {} reviewers",
+        "Completed in {:.1}s: {} p0, {} p1, {} p2 findings across {} reviewers ({} suppressed)",
         report.total_duration.as_secs_f64(),
         totals.p0,
         totals.p1,
         totals.p2,
-        report.reviewer_results.len()
+        report.reviewer_results.len(),
+        totals.suppressed
     );
 
     // Create GitHub issues if requested
@@ -128,6 +165,9 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
                 dry_run: false,
                 force: false,
                 repo: None,
+                sync: false,
+                grace_runs: 2,
+                clusters: None,
             };
             if let Err(e) = crate::cli::issue::execute(issue_args).await {
                 error!("Failed to create issues: {}", e);
@@ -137,6 +177,22 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
         }
     }
 
+    // Create a check run if requested (gates the PR without failing this job)
+    if args.create_check {
+        info!("Creating GitHub check run...");
+        let check_args = crate::cli::CheckArgs {
+            report_dir: report_dir.clone(),
+            config: args.config.clone(),
+            repo: None,
+            sha: None,
+            name: "polyrev".to_string(),
+            dry_run: false,
+        };
+        if let Err(e) = crate::cli::check::execute(check_args).await {
+            error!("Failed to create check run: {}", e);
+        }
+    }
+
     // Exit with error if critical findings and flag set
     if args.fail_on_critical && totals.p0 > 0 {
         error!("Exiting with error: {} critical (p0) findings", totals.p0);
@@ -146,6 +202,22 @@ pub async fn execute(args: RunArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Findings per priority in the verified report, leaving out refuted ones
+fn verified_totals(report_dir: &Path) -> anyhow::Result<FindingCounts> {
+    let mut counts = FindingCounts::default();
+    for (_, finding) in crate::cli::issue::load_report_findings(report_dir)? {
+        if finding.is_refuted() {
+            continue;
+        }
+        match finding.priority {
+            Priority::P0 => counts.p0 += 1,
+            Priority::P1 => counts.p1 += 1,
+            Priority::P2 => counts.p2 += 1,
+        }
+    }
+    Ok(counts)
+}
+
 fn print_execution_plan(config: &Config, options: &RunOptions, state: &State, force: bool) {
     println!("\n=== Execution Plan ===\n");
     println!("Target: {:?}", config.target);
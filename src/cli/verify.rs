@@ -0,0 +1,40 @@
+use crate::cli::VerifyArgs;
+use crate::config::Config;
+use crate::verify::run_verify;
+use chrono::Local;
+use tracing::{error, info};
+
+pub async fn execute(args: VerifyArgs) -> anyhow::Result<()> {
+    let mut config = Config::load(&args.config)?;
+    // Running the command is the opt-in
+    config.verify.enabled = true;
+
+    let report_dir = if let Some(dir) = args.report_dir {
+        dir
+    } else {
+        let date_str = Local::now().format("%Y-%m-%d").to_string();
+        config.report_dir.join(&date_str)
+    };
+
+    if !report_dir.exists() {
+        anyhow::bail!("Report directory not found: {:?}", report_dir);
+    }
+
+    info!("Verifying findings in {:?}", report_dir);
+
+    match run_verify(&config, &report_dir).await {
+        Ok(Some(summary)) => {
+            println!(
+                "Verified findings: {} confirmed, {} downgraded, {} refuted, {} could not be verified",
+                summary.confirmed, summary.downgraded, summary.refuted, summary.failed
+            );
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("Verify failed: {}", e);
+            anyhow::bail!("Verify failed: {}", e);
+        }
+    }
+
+    Ok(())
+}
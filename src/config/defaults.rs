@@ -68,6 +68,14 @@ pub fn default_backoff_base_ms() -> u64 {
     1000
 }
 
+pub fn default_repair_attempts() -> u32 {
+    1
+}
+
+pub fn default_jira_issue_type() -> String {
+    "Bug".to_string()
+}
+
 pub fn default_true() -> bool {
     true
 }
@@ -88,6 +96,34 @@ pub fn default_postprocess_min_findings() -> usize {
     2 // Only reduce if there are at least 2 findings
 }
 
+pub fn default_postprocess_max_per_call() -> usize {
+    150 // Larger sets are reduced hierarchically
+}
+
+pub fn default_dedupe_line_window() -> u32 {
+    3
+}
+
+pub fn default_dedupe_similarity() -> f64 {
+    0.6
+}
+
+pub fn default_verify_priorities() -> Vec<crate::config::Priority> {
+    vec![crate::config::Priority::P0, crate::config::Priority::P1]
+}
+
+pub fn default_verify_context_lines() -> u32 {
+    20
+}
+
+pub fn default_verify_prompt() -> std::path::PathBuf {
+    std::path::PathBuf::from("prompts/verify.md")
+}
+
+pub fn default_verify_timeout() -> u64 {
+    180
+}
+
 pub fn default_false() -> bool {
     false
 }
@@ -3,6 +3,7 @@ mod types;
 
 pub use types::*;
 
+use crate::calibration::Calibration;
 use crate::error::ConfigError;
 use defaults::*;
 use std::collections::HashMap;
@@ -17,14 +18,23 @@ impl Default for Config {
             report_dir: default_report_dir(),
             dry_run: false,
             diff_base: None,
+            tracker: TrackerKind::default(),
             github: GithubConfig::default(),
+            gitlab: GitlabConfig::default(),
+            jira: JiraConfig::default(),
+            linear: LinearConfig::default(),
+            issue_policy: IssuePolicyConfig::default(),
             providers: ProvidersConfig::default(),
             retry: RetryConfig::default(),
             postprocess: PostProcessConfig::default(),
+            verify: VerifyConfig::default(),
+            calibration: CalibrationConfig::default(),
             planning: None,
             timeout_sec: default_timeout_sec(),
             max_files: default_max_files(),
             launch_delay_ms: default_launch_delay_ms(),
+            repair_attempts: default_repair_attempts(),
+            location_check: LocationCheck::default(),
             scopes: HashMap::new(),
             reviewers: Vec::new(),
         }
@@ -60,6 +70,9 @@ impl Config {
             return Err(ConfigError::NoReviewersEnabled);
         }
 
+        // Check calibration regexes and globs compile
+        Calibration::from_config(&self.calibration)?;
+
         Ok(())
     }
 }
@@ -26,9 +26,26 @@ pub struct Config {
     #[serde(default)]
     pub diff_base: Option<String>,
 
+    /// Issue tracker used by `issue` and `review-pr`
+    #[serde(default)]
+    pub tracker: TrackerKind,
+
     #[serde(default)]
     pub github: GithubConfig,
 
+    #[serde(default)]
+    pub gitlab: GitlabConfig,
+
+    #[serde(default)]
+    pub jira: JiraConfig,
+
+    #[serde(default)]
+    pub linear: LinearConfig,
+
+    /// Which findings `issue` files, and where they are routed (any tracker)
+    #[serde(default)]
+    pub issue_policy: IssuePolicyConfig,
+
     #[serde(default)]
     pub providers: ProvidersConfig,
 
@@ -38,6 +55,13 @@ pub struct Config {
     #[serde(default)]
     pub postprocess: PostProcessConfig,
 
+    #[serde(default)]
+    pub verify: VerifyConfig,
+
+    /// Priority rewrites and caps applied to reviewer findings after parsing
+    #[serde(default)]
+    pub calibration: CalibrationConfig,
+
     #[serde(default)]
     pub planning: Option<PlanningConfig>,
 
@@ -50,6 +74,14 @@ pub struct Config {
     #[serde(default = "default_launch_delay_ms")]
     pub launch_delay_ms: u64,
 
+    /// Follow-up prompts asking a reviewer to fix malformed output (0 disables)
+    #[serde(default = "default_repair_attempts")]
+    pub repair_attempts: u32,
+
+    /// How to handle findings whose file/snippet can't be found in the source
+    #[serde(default)]
+    pub location_check: LocationCheck,
+
     #[serde(default)]
     pub scopes: HashMap<String, Scope>,
 
@@ -101,9 +133,279 @@ pub struct GithubConfig {
     #[serde(default)]
     pub dedupe_action: DedupeAction,
 
+    /// How to talk to GitHub: REST API, `gh` CLI, or auto-detect
+    #[serde(default)]
+    pub backend: GithubBackendKind,
+
+    /// REST API base URL (for GitHub Enterprise Server, e.g. https://ghe.example.com/api/v3).
+    /// Defaults to $GITHUB_API_URL, then https://api.github.com
+    #[serde(default)]
+    pub api_url: Option<String>,
+
     /// Auto-trigger an AI agent to fix created issues
     #[serde(default)]
     pub auto_fix: AutoFixConfig,
+
+    /// Custom issue title and body templates
+    #[serde(default)]
+    pub template: IssueTemplateConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct IssueTemplateConfig {
+    /// Inline title template, e.g. "[{{ finding.priority }}] {{ finding.title }}"
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Body template file (relative to target)
+    #[serde(default)]
+    pub body: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct IssuePolicyConfig {
+    /// Lowest priority that gets an issue (default: file everything)
+    #[serde(default)]
+    pub min_priority: Option<Priority>,
+
+    /// Most new issues to open per run; higher priority findings are filed first
+    #[serde(default)]
+    pub max_new_issues: Option<usize>,
+
+    /// Overrides keyed by reviewer id
+    #[serde(default)]
+    pub reviewers: HashMap<String, ReviewerIssuePolicy>,
+
+    /// File globs mapped to assignees; every matching route adds its assignees
+    #[serde(default)]
+    pub routes: Vec<IssueRoute>,
+
+    /// Also assign the users that CODEOWNERS lists for the finding's file
+    #[serde(default)]
+    pub codeowners: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct CalibrationConfig {
+    /// Priority rewrites; the first matching rule applies
+    #[serde(default)]
+    pub rules: Vec<CalibrationRule>,
+
+    /// Most findings of each priority per reviewer (e.g. `{p0: 3}`), keyed by
+    /// reviewer id; the least confident extras drop to the next priority
+    #[serde(default)]
+    pub caps: HashMap<String, HashMap<Priority, usize>>,
+}
+
+/// Sets `priority` on findings matching every field that is set
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CalibrationRule {
+    /// Reviewer id
+    #[serde(default)]
+    pub reviewer: Option<String>,
+
+    /// Regex matched against the finding type
+    #[serde(default, rename = "type", alias = "finding_type")]
+    pub finding_type: Option<String>,
+
+    /// Glob matched against the finding's file path
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Model that reported the finding
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Only rewrite findings at these priorities (default: any)
+    #[serde(default)]
+    pub from: Vec<Priority>,
+
+    pub priority: Priority,
+
+    /// Recorded on adjusted findings (default: describes the rule)
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ReviewerIssuePolicy {
+    /// Labels added to this reviewer's issues
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Replaces the tracker's default assignees
+    #[serde(default)]
+    pub assignees: Option<Vec<String>>,
+
+    /// File this reviewer's issues in another repository (owner/repo)
+    #[serde(default)]
+    pub repo: Option<String>,
+
+    /// Milestone title (GitHub only)
+    #[serde(default)]
+    pub milestone: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct IssueRoute {
+    /// Globs relative to the target, e.g. "src/api/**"
+    pub paths: Vec<String>,
+
+    pub assignees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    #[default]
+    Github,
+    Gitlab,
+    Jira,
+    Linear,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GitlabConfig {
+    /// Instance URL (defaults to $CI_SERVER_URL, then https://gitlab.com)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Project id or path, e.g. "group/app" (defaults to $CI_PROJECT_ID)
+    #[serde(default)]
+    pub project: Option<String>,
+
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Usernames to assign
+    #[serde(default)]
+    pub assignees: Vec<String>,
+
+    #[serde(default = "default_true")]
+    pub dedupe: bool,
+
+    #[serde(default)]
+    pub dedupe_action: DedupeAction,
+}
+
+impl Default for GitlabConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            project: None,
+            labels: Vec::new(),
+            assignees: Vec::new(),
+            dedupe: true,
+            dedupe_action: DedupeAction::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct JiraConfig {
+    /// Site URL, e.g. https://acme.atlassian.net (defaults to $JIRA_URL)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Project key, e.g. "SEC"
+    #[serde(default)]
+    pub project: Option<String>,
+
+    #[serde(default = "default_jira_issue_type")]
+    pub issue_type: String,
+
+    /// Jira priority name per finding priority (default p0: Highest, p1: High, p2: Medium)
+    #[serde(default)]
+    pub priorities: HashMap<Priority, String>,
+
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Custom field holding the fingerprint, e.g. "customfield_10050".
+    /// Without one, the fingerprint is stored as a `polyrev-fp-<fingerprint>` label
+    #[serde(default)]
+    pub fingerprint_field: Option<String>,
+
+    #[serde(default = "default_true")]
+    pub dedupe: bool,
+
+    #[serde(default)]
+    pub dedupe_action: DedupeAction,
+}
+
+impl Default for JiraConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            project: None,
+            issue_type: default_jira_issue_type(),
+            priorities: HashMap::new(),
+            labels: Vec::new(),
+            fingerprint_field: None,
+            dedupe: true,
+            dedupe_action: DedupeAction::default(),
+        }
+    }
+}
+
+impl JiraConfig {
+    pub fn priority_name(&self, priority: Priority) -> String {
+        self.priorities.get(&priority).cloned().unwrap_or_else(|| {
+            match priority {
+                Priority::P0 => "Highest",
+                Priority::P1 => "High",
+                Priority::P2 => "Medium",
+            }
+            .to_string()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct LinearConfig {
+    /// Team key, e.g. "ENG"
+    #[serde(default)]
+    pub team: Option<String>,
+
+    /// Linear priority per finding priority: 1 urgent, 2 high, 3 medium, 4 low
+    /// (default p0: 1, p1: 2, p2: 3)
+    #[serde(default)]
+    pub priorities: HashMap<Priority, u8>,
+
+    /// Label names (must already exist in the workspace or team)
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    #[serde(default = "default_true")]
+    pub dedupe: bool,
+
+    #[serde(default)]
+    pub dedupe_action: DedupeAction,
+}
+
+impl Default for LinearConfig {
+    fn default() -> Self {
+        Self {
+            team: None,
+            priorities: HashMap::new(),
+            labels: Vec::new(),
+            dedupe: true,
+            dedupe_action: DedupeAction::default(),
+        }
+    }
+}
+
+impl LinearConfig {
+    pub fn priority_value(&self, priority: Priority) -> u8 {
+        self.priorities
+            .get(&priority)
+            .copied()
+            .unwrap_or(match priority {
+                Priority::P0 => 1,
+                Priority::P1 => 2,
+                Priority::P2 => 3,
+            })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
@@ -140,6 +442,30 @@ pub enum DedupeAction {
     Reopen,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GithubBackendKind {
+    /// REST API when $GITHUB_TOKEN (or $GH_TOKEN) and a repo are set, otherwise `gh`
+    #[default]
+    Auto,
+    /// REST API using $GITHUB_TOKEN
+    Api,
+    /// Shell out to the `gh` CLI
+    Gh,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationCheck {
+    /// Don't check finding locations
+    Off,
+    /// Check and correct locations, mark unlocated findings in `verified`
+    #[default]
+    Flag,
+    /// Like flag, but drop findings whose file or snippet can't be found
+    Drop,
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct ProvidersConfig {
     #[serde(default)]
@@ -149,6 +475,16 @@ pub struct ProvidersConfig {
     pub codex_cli: CodexCliConfig,
 }
 
+impl ProvidersConfig {
+    /// Configured model for a provider
+    pub fn model(&self, provider: Provider) -> &str {
+        match provider {
+            Provider::ClaudeCli => &self.claude_cli.model,
+            Provider::CodexCli => &self.codex_cli.model,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ClaudeCliConfig {
     #[serde(default = "default_claude_binary")]
@@ -231,6 +567,31 @@ pub struct PostProcessConfig {
     /// Minimum number of findings required to run postprocessing
     #[serde(default = "default_postprocess_min_findings")]
     pub min_findings: usize,
+
+    /// Run the AI reducer; with `false` only the local dedupe pass runs
+    #[serde(default = "default_true")]
+    pub ai: bool,
+
+    /// Deterministic duplicate merging before the AI reducer
+    #[serde(default)]
+    pub local_dedupe: LocalDedupeConfig,
+
+    /// Most findings sent to the reducer in one call; larger sets are partitioned,
+    /// reduced in parallel, then merged (0 = no limit)
+    #[serde(default = "default_postprocess_max_per_call")]
+    pub max_findings_per_call: usize,
+
+    /// How findings are partitioned when they don't fit in one call
+    #[serde(default)]
+    pub partition_by: PartitionBy,
+
+    /// Build on the previous run's `reduced.json` so reduced ids and clusters stay stable
+    #[serde(default = "default_true")]
+    pub incremental: bool,
+
+    /// `reduced.json` to build on; defaults to the latest earlier report directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<PathBuf>,
 }
 
 impl Default for PostProcessConfig {
@@ -241,6 +602,92 @@ impl Default for PostProcessConfig {
             prompt_file: default_postprocess_prompt(),
             timeout_sec: default_postprocess_timeout(),
             min_findings: default_postprocess_min_findings(),
+            ai: true,
+            local_dedupe: LocalDedupeConfig::default(),
+            max_findings_per_call: default_postprocess_max_per_call(),
+            partition_by: PartitionBy::default(),
+            incremental: true,
+            previous: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PartitionBy {
+    /// One partition per file
+    File,
+    /// One partition per directory
+    #[default]
+    Directory,
+    /// One partition per finding type
+    Type,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct VerifyConfig {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Provider that checks the findings; defaults to the one `postprocess.tool` doesn't use
+    #[serde(default)]
+    pub provider: Option<Provider>,
+
+    /// Model override for the verifier (defaults to the provider's model)
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Priorities to verify
+    #[serde(default = "default_verify_priorities")]
+    pub priorities: Vec<Priority>,
+
+    /// Lines of code shown above and below each finding
+    #[serde(default = "default_verify_context_lines")]
+    pub context_lines: u32,
+
+    /// Path to the verification prompt
+    #[serde(default = "default_verify_prompt")]
+    pub prompt_file: PathBuf,
+
+    /// Timeout in seconds per finding
+    #[serde(default = "default_verify_timeout")]
+    pub timeout_sec: u64,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: None,
+            model: None,
+            priorities: default_verify_priorities(),
+            context_lines: default_verify_context_lines(),
+            prompt_file: default_verify_prompt(),
+            timeout_sec: default_verify_timeout(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct LocalDedupeConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Lines of slack allowed between two findings' ranges in the same file
+    #[serde(default = "default_dedupe_line_window")]
+    pub line_window: u32,
+
+    /// Minimum token-set similarity (0.0-1.0) of titles or snippets to merge
+    #[serde(default = "default_dedupe_similarity")]
+    pub similarity: f64,
+}
+
+impl Default for LocalDedupeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            line_window: default_dedupe_line_window(),
+            similarity: default_dedupe_similarity(),
         }
     }
 }
@@ -282,6 +729,25 @@ pub struct Reviewer {
 
     #[serde(default)]
     pub command_override: Option<String>,
+
+    /// Run the review through each of these and merge findings by consensus
+    /// (replaces `provider`)
+    #[serde(default)]
+    pub models: Vec<ReviewerModel>,
+
+    /// With `models`, drop findings reported by fewer than this many of them
+    #[serde(default)]
+    pub min_votes: Option<usize>,
+}
+
+/// One voter in a consensus review
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ReviewerModel {
+    pub provider: Provider,
+
+    /// Defaults to the provider's configured model
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
@@ -309,6 +775,17 @@ pub enum Priority {
     P2,
 }
 
+impl Priority {
+    /// Sort key, most urgent first
+    pub fn rank(self) -> u8 {
+        match self {
+            Priority::P0 => 0,
+            Priority::P1 => 1,
+            Priority::P2 => 2,
+        }
+    }
+}
+
 impl std::fmt::Display for Priority {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -25,9 +25,15 @@ pub enum PolyrevError {
     #[error("GitHub error: {0}")]
     GitHub(#[from] GitHubError),
 
+    #[error("Tracker error: {0}")]
+    Tracker(#[from] TrackerError),
+
     #[error("Postprocess error: {0}")]
     Postprocess(#[from] PostprocessError),
 
+    #[error("Verify error: {0}")]
+    Verify(#[from] VerifyError),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -48,6 +54,15 @@ pub enum ConfigError {
 
     #[error("No reviewers enabled")]
     NoReviewersEnabled,
+
+    #[error("Invalid suppression: {0}")]
+    InvalidSuppression(String),
+
+    #[error("Invalid issue policy: {0}")]
+    InvalidIssuePolicy(String),
+
+    #[error("Invalid calibration: {0}")]
+    InvalidCalibration(String),
 }
 
 #[derive(Error, Debug)]
@@ -134,6 +149,94 @@ pub enum GitHubError {
 
     #[error("Issue creation failed: {0}")]
     CreateFailed(String),
+
+    #[error("GitHub API returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("GitHub backend misconfigured: {0}")]
+    Config(String),
+}
+
+#[derive(Error, Debug)]
+pub enum GitLabError {
+    #[error("GitLab API returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Failed to parse GitLab response: {0}")]
+    ParseOutput(String),
+
+    #[error("GitLab backend misconfigured: {0}")]
+    Config(String),
+}
+
+#[derive(Error, Debug)]
+pub enum JiraError {
+    #[error("Jira API returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Failed to parse Jira response: {0}")]
+    ParseOutput(String),
+
+    #[error("Jira backend misconfigured: {0}")]
+    Config(String),
+
+    #[error("No Jira transition to '{0}' available")]
+    NoTransition(String),
+}
+
+#[derive(Error, Debug)]
+pub enum LinearError {
+    #[error("Linear API returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("Linear GraphQL error: {0}")]
+    GraphQl(String),
+
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Linear backend misconfigured: {0}")]
+    Config(String),
+}
+
+/// Errors from any issue tracker backend
+#[derive(Error, Debug)]
+pub enum TrackerError {
+    #[error("GitHub error: {0}")]
+    GitHub(#[from] GitHubError),
+
+    #[error("GitLab error: {0}")]
+    GitLab(#[from] GitLabError),
+
+    #[error("Jira error: {0}")]
+    Jira(#[from] JiraError),
+
+    #[error("Linear error: {0}")]
+    Linear(#[from] LinearError),
+
+    #[error("Invalid issue id '{0}'")]
+    InvalidId(String),
+
+    #[error("Issue template error: {0}")]
+    Template(String),
+}
+
+#[derive(Error, Debug)]
+pub enum PatchError {
+    #[error("git {command} failed: {stderr}")]
+    Git { command: String, stderr: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Error, Debug)]
@@ -144,6 +247,12 @@ pub enum PostprocessError {
     #[error("Failed to parse reduced output: {0}")]
     ParseOutput(String),
 
+    #[error("Could not parse reduced findings from CLI output (raw output saved to {})", raw_output.display())]
+    Unparsable { raw_output: PathBuf },
+
+    #[error("{error} (raw output saved to {})", raw_output.display())]
+    CliFailed { error: String, raw_output: PathBuf },
+
     #[error("Execution timed out after {0:?}")]
     Timeout(std::time::Duration),
 
@@ -154,6 +263,21 @@ pub enum PostprocessError {
     Serialize(#[from] serde_json::Error),
 }
 
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Failed to parse verdict: {0}")]
+    ParseOutput(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
 #[derive(Error, Debug)]
 pub enum PlannerError {
     #[error("No perspectives matched filters")]
@@ -0,0 +1,310 @@
+//! GitHub check runs with finding annotations
+//!
+//! The check's conclusion is derived from finding priorities (p0 → failure,
+//! p1 → neutral, otherwise success), so a PR gets a pass/fail gate without
+//! failing the CI job that ran polyrev.
+
+use super::GitHubClient;
+use crate::config::Priority;
+use crate::error::GitHubError;
+use crate::output::repo_path;
+use crate::parser::Finding;
+use serde::Serialize;
+use tracing::info;
+
+/// The API accepts at most 50 annotations per request
+const ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// Maximum length of the check output summary
+const MAX_SUMMARY_LEN: usize = 65_535;
+
+/// Check run conclusion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckConclusion {
+    Success,
+    Neutral,
+    Failure,
+}
+
+impl CheckConclusion {
+    /// failure on any p0, neutral on any p1, success otherwise
+    pub fn from_findings<'a>(findings: impl IntoIterator<Item = &'a Finding>) -> Self {
+        findings
+            .into_iter()
+            .map(|f| match f.priority {
+                Priority::P0 => CheckConclusion::Failure,
+                Priority::P1 => CheckConclusion::Neutral,
+                Priority::P2 => CheckConclusion::Success,
+            })
+            .max_by_key(|c| match c {
+                CheckConclusion::Success => 0,
+                CheckConclusion::Neutral => 1,
+                CheckConclusion::Failure => 2,
+            })
+            .unwrap_or(CheckConclusion::Success)
+    }
+}
+
+impl std::fmt::Display for CheckConclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckConclusion::Success => write!(f, "success"),
+            CheckConclusion::Neutral => write!(f, "neutral"),
+            CheckConclusion::Failure => write!(f, "failure"),
+        }
+    }
+}
+
+/// A check run annotation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Annotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub annotation_level: &'static str,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_details: Option<String>,
+}
+
+impl Annotation {
+    /// `prefix` is the target's directory within the repository; annotation
+    /// paths are relative to the repository root
+    pub fn from_finding(finding: &Finding, prefix: &str) -> Self {
+        // Annotations need a line; file-level findings go on the first line
+        let start_line = finding.line.max(1);
+        let end_line = finding
+            .end_line
+            .filter(|end| *end >= start_line)
+            .unwrap_or(start_line);
+        let annotation_level = match finding.priority {
+            Priority::P0 => "failure",
+            Priority::P1 => "warning",
+            Priority::P2 => "notice",
+        };
+
+        Self {
+            path: repo_path(prefix, &finding.file),
+            start_line,
+            end_line,
+            annotation_level,
+            title: format!("[{}] {}", finding.priority, finding.title),
+            message: if finding.remediation.is_empty() {
+                finding.description.clone()
+            } else {
+                format!(
+                    "{}\n\nRemediation: {}",
+                    finding.description, finding.remediation
+                )
+            },
+            raw_details: finding.snippet.clone(),
+        }
+    }
+}
+
+/// Check run output (title, summary, annotations)
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutput {
+    pub title: String,
+    pub summary: String,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Everything needed to create a completed check run
+#[derive(Debug, Clone)]
+pub struct CheckRun {
+    pub name: String,
+    pub head_sha: String,
+    pub conclusion: CheckConclusion,
+    pub title: String,
+    pub summary: String,
+    pub annotations: Vec<Annotation>,
+}
+
+impl CheckRun {
+    /// Build a check run from findings and the run's `summary.md`
+    ///
+    /// `prefix` is the target's directory within the repository.
+    pub fn new(
+        name: &str,
+        head_sha: &str,
+        findings: &[Finding],
+        summary_md: &str,
+        prefix: &str,
+    ) -> Self {
+        let count = |p: Priority| findings.iter().filter(|f| f.priority == p).count();
+        let title = format!(
+            "{} p0, {} p1, {} p2 findings",
+            count(Priority::P0),
+            count(Priority::P1),
+            count(Priority::P2)
+        );
+
+        let mut summary = if summary_md.trim().is_empty() {
+            format!("## polyrev\n\n{}\n", title)
+        } else {
+            summary_md.to_string()
+        };
+        if summary.len() > MAX_SUMMARY_LEN {
+            let mut cut = MAX_SUMMARY_LEN - 32;
+            while !summary.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            summary.truncate(cut);
+            summary.push_str("\n\n_(summary truncated)_\n");
+        }
+
+        Self {
+            name: name.to_string(),
+            head_sha: head_sha.to_string(),
+            conclusion: CheckConclusion::from_findings(findings),
+            title,
+            summary,
+            annotations: findings
+                .iter()
+                .map(|finding| Annotation::from_finding(finding, prefix))
+                .collect(),
+        }
+    }
+
+    /// Create the check run, sending annotations in batches of 50
+    ///
+    /// Returns the check run's HTML URL.
+    pub async fn publish(&self, client: &GitHubClient) -> Result<String, GitHubError> {
+        let mut batches = self.annotations.chunks(ANNOTATIONS_PER_REQUEST);
+        let output = |annotations: &[Annotation]| CheckOutput {
+            title: self.title.clone(),
+            summary: self.summary.clone(),
+            annotations: annotations.to_vec(),
+        };
+
+        let first = batches.next().unwrap_or_default();
+        let (id, url) = client
+            .create_check_run(&self.name, &self.head_sha, self.conclusion, &output(first))
+            .await?;
+
+        // Later batches are appended to the existing annotations
+        for batch in batches {
+            client.update_check_run(id, &output(batch)).await?;
+        }
+
+        info!(
+            "Created check run '{}' ({}) with {} annotations",
+            self.name,
+            self.conclusion,
+            self.annotations.len()
+        );
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn finding(priority: Priority, line: u32) -> Finding {
+        Finding {
+            id: "T-1".to_string(),
+            title: "Test".to_string(),
+            priority,
+            file: PathBuf::from("src/a.rs"),
+            line,
+            description: "desc".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conclusion_from_priorities() {
+        assert_eq!(
+            CheckConclusion::from_findings(&[]),
+            CheckConclusion::Success
+        );
+        assert_eq!(
+            CheckConclusion::from_findings(&[finding(Priority::P2, 1)]),
+            CheckConclusion::Success
+        );
+        assert_eq!(
+            CheckConclusion::from_findings(&[finding(Priority::P2, 1), finding(Priority::P1, 1)]),
+            CheckConclusion::Neutral
+        );
+        assert_eq!(
+            CheckConclusion::from_findings(&[finding(Priority::P1, 1), finding(Priority::P0, 1)]),
+            CheckConclusion::Failure
+        );
+    }
+
+    #[test]
+    fn test_annotation_lines_and_level() {
+        let mut f = finding(Priority::P0, 0);
+        let a = Annotation::from_finding(&f, "");
+        assert_eq!((a.start_line, a.end_line), (1, 1));
+        assert_eq!(a.annotation_level, "failure");
+
+        f.line = 10;
+        f.end_line = Some(14);
+        f.priority = Priority::P2;
+        let a = Annotation::from_finding(&f, "");
+        assert_eq!((a.start_line, a.end_line), (10, 14));
+        assert_eq!(a.annotation_level, "notice");
+        assert_eq!(a.path, "src/a.rs");
+    }
+
+    #[test]
+    fn test_annotation_path_from_repository_root() {
+        let mut f = finding(Priority::P1, 3);
+        f.file = PathBuf::from("./src/a.rs");
+        assert_eq!(Annotation::from_finding(&f, "").path, "src/a.rs");
+        assert_eq!(
+            Annotation::from_finding(&f, "services/api/").path,
+            "services/api/src/a.rs"
+        );
+    }
+
+    #[test]
+    fn test_check_run_summary_fallback() {
+        let run = CheckRun::new("polyrev", "abc", &[finding(Priority::P1, 3)], "", "");
+        assert_eq!(run.title, "0 p0, 1 p1, 0 p2 findings");
+        assert!(run.summary.contains(&run.title));
+        assert_eq!(run.conclusion, CheckConclusion::Neutral);
+    }
+
+    #[tokio::test]
+    async fn test_publish_batches_annotations() {
+        use serde_json::json;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/app/check-runs"))
+            .and(body_partial_json(json!({
+                "head_sha": "abc123",
+                "status": "completed",
+                "conclusion": "failure"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": 77,
+                "html_url": "https://github.com/acme/app/runs/77"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/acme/app/check-runs/77"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 77})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let findings: Vec<Finding> = (1..=60).map(|line| finding(Priority::P0, line)).collect();
+        let run = CheckRun::new("polyrev", "abc123", &findings, "# Summary", "");
+        let client = GitHubClient::new(&server.uri(), "t0ken", "acme/app").unwrap();
+
+        let url = run.publish(&client).await.unwrap();
+        assert_eq!(url, "https://github.com/acme/app/runs/77");
+    }
+}
@@ -0,0 +1,752 @@
+use super::checks::{CheckConclusion, CheckOutput};
+use super::plan_issue::{IssueComment, IssueDetails};
+use super::review::{PullRequestFile, Review};
+use crate::error::{GitHubError, TrackerError};
+use crate::tracker::review::{NewReviewComment, ReviewComment};
+use crate::tracker::{
+    fingerprint_in, fingerprint_marker, next_link, numeric_id, ExistingIssue, IssueState,
+    IssueTracker, NewIssue, TrackedIssue,
+};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+/// Retries after hitting a primary or secondary rate limit
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest we'll sleep for a rate limit before giving up
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+
+/// Wait used for secondary limits that don't say how long to back off
+const DEFAULT_SECONDARY_WAIT: Duration = Duration::from_secs(60);
+
+/// Safety cap on followed pagination links
+const MAX_PAGES: usize = 50;
+
+/// Minimal GitHub REST client authenticated with a token
+///
+/// Works against github.com and GitHub Enterprise Server (`https://<host>/api/v3`).
+pub struct GitHubClient {
+    http: reqwest::Client,
+    api_url: String,
+    repo: String,
+    /// Open milestones as (title, number), fetched on first use
+    milestones: OnceCell<Vec<(String, u64)>>,
+}
+
+#[derive(Deserialize)]
+struct MilestoneInfo {
+    number: u64,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct IssueInfo {
+    /// Database id (sub-issue links use this, not the number)
+    #[serde(default)]
+    id: u64,
+    number: u64,
+    state: String,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    /// Present when the "issue" is a pull request
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
+
+impl GitHubClient {
+    pub fn new(api_url: &str, token: &str, repo: &str) -> Result<Self, GitHubError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+        headers.insert(USER_AGENT, HeaderValue::from_static("polyrev"));
+        let mut auth = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| GitHubError::Config(format!("invalid token: {}", e)))?;
+        auth.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth);
+
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            http,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            repo: repo.to_string(),
+            milestones: OnceCell::new(),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.api_url, path)
+    }
+
+    /// Send a request, waiting out rate limits, and fail on non-2xx responses
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, GitHubError> {
+        let mut attempt = 0;
+        loop {
+            let mut request: RequestBuilder = self.http.request(method.clone(), url);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let headers = response.headers().clone();
+            let message = response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|v| v.get("message").and_then(Value::as_str).map(String::from))
+                .unwrap_or_else(|| status.to_string());
+
+            if attempt < MAX_RATE_LIMIT_RETRIES {
+                if let Some(wait) = rate_limit_wait(status, &headers, &message) {
+                    if wait > MAX_RATE_LIMIT_WAIT {
+                        return Err(GitHubError::Api {
+                            status: status.as_u16(),
+                            message: format!("rate limited for {}s", wait.as_secs()),
+                        });
+                    }
+                    attempt += 1;
+                    warn!(
+                        "GitHub rate limit hit, retrying in {}s (attempt {}/{})",
+                        wait.as_secs(),
+                        attempt,
+                        MAX_RATE_LIMIT_RETRIES
+                    );
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+            }
+
+            return Err(GitHubError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+    }
+
+    /// GET every page of a list endpoint, following `Link: rel="next"`
+    ///
+    /// Accepts both plain arrays and search-style `{"items": [...]}` pages.
+    async fn get_paginated<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, GitHubError> {
+        let mut items = Vec::new();
+        let mut next = Some(url.to_string());
+        let mut pages = 0;
+
+        while let Some(url) = next.take() {
+            let response = self.send(Method::GET, &url, None).await?;
+            next = next_link(response.headers());
+
+            let page: Value = response.json().await?;
+            let page_items = match page {
+                Value::Array(values) => values,
+                Value::Object(mut obj) => match obj.remove("items") {
+                    Some(Value::Array(values)) => values,
+                    _ => Vec::new(),
+                },
+                _ => Vec::new(),
+            };
+            for item in page_items {
+                items.push(
+                    serde_json::from_value(item)
+                        .map_err(|e| GitHubError::ParseOutput(e.to_string()))?,
+                );
+            }
+
+            pages += 1;
+            if pages >= MAX_PAGES {
+                warn!("Stopped after {} pages of {}", MAX_PAGES, url);
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// Pull request endpoints (REST only; used by `polyrev review-pr`)
+impl GitHubClient {
+    /// Head commit SHA of a pull request
+    pub async fn pull_request_head(&self, number: u64) -> Result<String, GitHubError> {
+        let url = self.url(&format!("/repos/{}/pulls/{}", self.repo, number));
+        let pr: Value = self.send(Method::GET, &url, None).await?.json().await?;
+        pr.pointer("/head/sha")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| GitHubError::ParseOutput("pull request has no head sha".to_string()))
+    }
+
+    pub async fn pull_request_files(
+        &self,
+        number: u64,
+    ) -> Result<Vec<PullRequestFile>, GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/pulls/{}/files?per_page=100",
+            self.repo, number
+        ));
+        self.get_paginated(&url).await
+    }
+
+    pub async fn review_comments(&self, number: u64) -> Result<Vec<ReviewComment>, GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/pulls/{}/comments?per_page=100",
+            self.repo, number
+        ));
+        self.get_paginated(&url).await
+    }
+
+    pub async fn reviews(&self, number: u64) -> Result<Vec<Review>, GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/pulls/{}/reviews?per_page=100",
+            self.repo, number
+        ));
+        self.get_paginated(&url).await
+    }
+
+    /// Submit a COMMENT review with inline comments
+    pub async fn create_review(
+        &self,
+        number: u64,
+        commit_id: &str,
+        body: &str,
+        comments: &[NewReviewComment],
+    ) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/pulls/{}/reviews", self.repo, number));
+        let payload = json!({
+            "commit_id": commit_id,
+            "body": body,
+            "event": "COMMENT",
+            "comments": comments,
+        });
+        self.send(Method::POST, &url, Some(&payload)).await?;
+        Ok(())
+    }
+
+    pub async fn update_review(
+        &self,
+        number: u64,
+        review_id: u64,
+        body: &str,
+    ) -> Result<(), GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/pulls/{}/reviews/{}",
+            self.repo, number, review_id
+        ));
+        self.send(Method::PUT, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_review_comment(&self, id: u64, body: &str) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/pulls/comments/{}", self.repo, id));
+        self.send(Method::PATCH, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Check run endpoints (REST only; used by `polyrev check`)
+impl GitHubClient {
+    /// Create a completed check run, returning its id and HTML URL
+    pub async fn create_check_run(
+        &self,
+        name: &str,
+        head_sha: &str,
+        conclusion: CheckConclusion,
+        output: &CheckOutput,
+    ) -> Result<(u64, String), GitHubError> {
+        let url = self.url(&format!("/repos/{}/check-runs", self.repo));
+        let payload = json!({
+            "name": name,
+            "head_sha": head_sha,
+            "status": "completed",
+            "conclusion": conclusion,
+            "output": output,
+        });
+        let created: Value = self
+            .send(Method::POST, &url, Some(&payload))
+            .await?
+            .json()
+            .await?;
+
+        let id = created
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| GitHubError::ParseOutput("check run has no id".to_string()))?;
+        let html_url = created
+            .get("html_url")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        Ok((id, html_url))
+    }
+
+    /// Update a check run's output; annotations are appended to existing ones
+    pub async fn update_check_run(&self, id: u64, output: &CheckOutput) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/check-runs/{}", self.repo, id));
+        self.send(Method::PATCH, &url, Some(&json!({ "output": output })))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Issue endpoints
+impl GitHubClient {
+    /// Find issues (open or closed) whose body contains `text`
+    pub async fn search_issues(&self, text: &str) -> Result<Vec<ExistingIssue>, GitHubError> {
+        let query = format!("repo:{} is:issue \"{}\" in:body", self.repo, text);
+        let mut url = reqwest::Url::parse(&self.url("/search/issues"))
+            .map_err(|e| GitHubError::Config(format!("invalid api_url: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("q", &query)
+            .append_pair("per_page", "100");
+
+        let issues: Vec<IssueInfo> = self.get_paginated(url.as_str()).await?;
+        debug!("Search '{}' matched {} issues", query, issues.len());
+
+        Ok(issues
+            .into_iter()
+            .map(|info| ExistingIssue {
+                id: info.number.to_string(),
+                state: IssueState::from_api(&info.state),
+            })
+            .collect())
+    }
+
+    pub async fn create_issue(&self, issue: &NewIssue) -> Result<String, GitHubError> {
+        Ok(self.post_issue(issue).await?.html_url)
+    }
+
+    /// Create an issue and link it as a sub-issue of `parent`
+    ///
+    /// Returns the new issue's number and URL. A failed link (e.g. sub-issues
+    /// aren't available on this server) is logged; the issue is kept.
+    pub async fn create_sub_issue(
+        &self,
+        parent: u64,
+        issue: &NewIssue,
+    ) -> Result<(u64, String), GitHubError> {
+        let created = self.post_issue(issue).await?;
+        let url = self.url(&format!(
+            "/repos/{}/issues/{}/sub_issues",
+            self.repo, parent
+        ));
+        let link = json!({ "sub_issue_id": created.id });
+        if let Err(e) = self.send(Method::POST, &url, Some(&link)).await {
+            warn!(
+                "Could not link #{} as a sub-issue of #{}: {}",
+                created.number, parent, e
+            );
+        }
+        Ok((created.number, created.html_url))
+    }
+
+    async fn post_issue(&self, issue: &NewIssue) -> Result<IssueInfo, GitHubError> {
+        let url = self.url(&format!("/repos/{}/issues", self.repo));
+        let mut body = json!({
+            "title": issue.title,
+            "body": issue.body,
+            "labels": issue.labels,
+            "assignees": issue.assignees,
+        });
+        if let Some(title) = &issue.milestone {
+            body["milestone"] = json!(self.milestone_number(title).await?);
+        }
+
+        let response = self
+            .send(Method::POST, &url, Some(&body))
+            .await
+            .map_err(|e| match e {
+                GitHubError::Api { message, .. } => GitHubError::CreateFailed(message),
+                e => e,
+            })?;
+        Ok(response.json().await?)
+    }
+
+    /// Number of the open milestone with this title
+    async fn milestone_number(&self, title: &str) -> Result<u64, GitHubError> {
+        let milestones = self
+            .milestones
+            .get_or_try_init(|| async {
+                let url = self.url(&format!(
+                    "/repos/{}/milestones?state=open&per_page=100",
+                    self.repo
+                ));
+                let infos: Vec<MilestoneInfo> = self.get_paginated(&url).await?;
+                Ok::<_, GitHubError>(infos.into_iter().map(|m| (m.title, m.number)).collect())
+            })
+            .await?;
+        milestones
+            .iter()
+            .find(|(t, _)| t == title)
+            .map(|(_, number)| *number)
+            .ok_or_else(|| {
+                GitHubError::CreateFailed(format!("no open milestone '{}' in {}", title, self.repo))
+            })
+    }
+
+    pub async fn get_issue(&self, number: u64) -> Result<IssueDetails, GitHubError> {
+        let url = self.url(&format!("/repos/{}/issues/{}", self.repo, number));
+        Ok(self.send(Method::GET, &url, None).await?.json().await?)
+    }
+
+    pub async fn issue_comments(&self, number: u64) -> Result<Vec<IssueComment>, GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/issues/{}/comments?per_page=100",
+            self.repo, number
+        ));
+        self.get_paginated(&url).await
+    }
+
+    pub async fn comment_issue(&self, number: u64, body: &str) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/issues/{}/comments", self.repo, number));
+        self.send(Method::POST, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_issue_comment(&self, id: u64, body: &str) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/issues/comments/{}", self.repo, id));
+        self.send(Method::PATCH, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    /// Issues linked as sub-issues of `parent`
+    pub async fn sub_issues(&self, parent: u64) -> Result<Vec<IssueDetails>, GitHubError> {
+        let url = self.url(&format!(
+            "/repos/{}/issues/{}/sub_issues?per_page=100",
+            self.repo, parent
+        ));
+        self.get_paginated(&url).await
+    }
+
+    /// Open issues with `label` that carry a fingerprint marker
+    pub async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, GitHubError> {
+        let mut url = reqwest::Url::parse(&self.url(&format!("/repos/{}/issues", self.repo)))
+            .map_err(|e| GitHubError::Config(format!("invalid api_url: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("state", "open")
+            .append_pair("labels", label)
+            .append_pair("per_page", "100");
+
+        let issues: Vec<IssueInfo> = self.get_paginated(url.as_str()).await?;
+        Ok(issues
+            .into_iter()
+            .filter(|info| info.pull_request.is_none())
+            .filter_map(|info| {
+                Some(TrackedIssue {
+                    id: info.number.to_string(),
+                    fingerprint: fingerprint_in(info.body.as_deref()?)?,
+                })
+            })
+            .collect())
+    }
+
+    /// Set an issue's state ("open" or "closed")
+    pub async fn set_issue_state(&self, number: u64, state: &str) -> Result<(), GitHubError> {
+        let url = self.url(&format!("/repos/{}/issues/{}", self.repo, number));
+        self.send(Method::PATCH, &url, Some(&json!({ "state": state })))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for GitHubClient {
+    async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Vec<ExistingIssue>, TrackerError> {
+        Ok(self.search_issues(&fingerprint_marker(fingerprint)).await?)
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> Result<String, TrackerError> {
+        Ok(GitHubClient::create_issue(self, issue).await?)
+    }
+
+    async fn comment_issue(&self, id: &str, body: &str) -> Result<(), TrackerError> {
+        Ok(GitHubClient::comment_issue(self, numeric_id(id)?, body).await?)
+    }
+
+    async fn reopen_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_issue_state(numeric_id(id)?, "open").await?)
+    }
+
+    async fn close_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_issue_state(numeric_id(id)?, "closed").await?)
+    }
+
+    async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, TrackerError> {
+        Ok(GitHubClient::list_open_issues(self, label).await?)
+    }
+}
+
+/// How long to wait before retrying, if this response is a rate limit
+///
+/// Primary limits set `x-ratelimit-remaining: 0` with a reset timestamp;
+/// secondary limits send `retry-after`, or nothing but a 429 or a 403 whose
+/// `message` mentions the secondary rate limit.
+fn rate_limit_wait(status: StatusCode, headers: &HeaderMap, message: &str) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    if let Some(secs) = header("retry-after") {
+        return Some(Duration::from_secs(secs));
+    }
+
+    if header("x-ratelimit-remaining") == Some(0) {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        let reset = header("x-ratelimit-reset").unwrap_or(now);
+        return Some(Duration::from_secs(reset.saturating_sub(now) + 1));
+    }
+
+    let secondary = message
+        .to_ascii_lowercase()
+        .contains("secondary rate limit");
+    (status == StatusCode::TOO_MANY_REQUESTS || secondary).then_some(DEFAULT_SECONDARY_WAIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(server: &MockServer) -> GitHubClient {
+        GitHubClient::new(&format!("{}/api/v3", server.uri()), "t0ken", "acme/app").unwrap()
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        assert_eq!(
+            rate_limit_wait(StatusCode::FORBIDDEN, &headers, ""),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(rate_limit_wait(StatusCode::NOT_FOUND, &headers, ""), None);
+
+        // Plain 403 without rate limit headers is a permission error
+        assert_eq!(
+            rate_limit_wait(
+                StatusCode::FORBIDDEN,
+                &HeaderMap::new(),
+                "Resource not accessible by integration"
+            ),
+            None
+        );
+        assert_eq!(
+            rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), ""),
+            Some(DEFAULT_SECONDARY_WAIT)
+        );
+
+        // A secondary limit may only say so in the body
+        assert_eq!(
+            rate_limit_wait(
+                StatusCode::FORBIDDEN,
+                &HeaderMap::new(),
+                "You have exceeded a secondary rate limit. Please wait a few minutes before you try again."
+            ),
+            Some(DEFAULT_SECONDARY_WAIT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_follows_pagination() {
+        let server = MockServer::start().await;
+        let page2 = format!("{}/api/v3/search/issues?page=2", server.uri());
+
+        Mock::given(method("GET"))
+            .and(path("/api/v3/search/issues"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [{"number": 2, "state": "open"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/search/issues"))
+            .and(header("authorization", "Bearer t0ken"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", format!("<{}>; rel=\"next\"", page2).as_str())
+                    .set_body_json(json!({
+                        "items": [{"number": 1, "state": "closed"}]
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let issues = client(&server)
+            .search_issues("polyrev:fp:abc")
+            .await
+            .unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ExistingIssue {
+                    id: "1".to_string(),
+                    state: IssueState::Closed
+                },
+                ExistingIssue {
+                    id: "2".to_string(),
+                    state: IssueState::Open
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_open_issues_skips_pull_requests_and_unmarked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/acme/app/issues"))
+            .and(query_param("state", "open"))
+            .and(query_param("labels", "security"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"number": 4, "state": "open", "body": "<!-- polyrev:fp:0a1b2c -->\nbody"},
+                {"number": 5, "state": "open", "body": "filed by hand"},
+                {"number": 6, "state": "open", "body": "<!-- polyrev:fp:ffff -->", "pull_request": {}}
+            ])))
+            .mount(&server)
+            .await;
+
+        let issues = client(&server).list_open_issues("security").await.unwrap();
+        assert_eq!(
+            issues,
+            vec![TrackedIssue {
+                id: "4".to_string(),
+                fingerprint: "0a1b2c".to_string()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_resolves_milestone_title() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/acme/app/milestones"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"number": 3, "title": "Q2"},
+                {"number": 7, "title": "Q3 hardening"}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/repos/acme/app/issues"))
+            .and(body_partial_json(json!({"milestone": 7})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "number": 10,
+                "state": "open",
+                "html_url": "https://github.com/acme/app/issues/10"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let issue = NewIssue {
+            title: "Bug".to_string(),
+            milestone: Some("Q3 hardening".to_string()),
+            ..Default::default()
+        };
+        assert!(client.create_issue(&issue).await.is_ok());
+        // Milestones are fetched once
+        assert!(client.create_issue(&issue).await.is_ok());
+
+        let missing = NewIssue {
+            milestone: Some("Q4".to_string()),
+            ..issue
+        };
+        assert!(matches!(
+            client.create_issue(&missing).await,
+            Err(GitHubError::CreateFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_retries_after_secondary_rate_limit() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/v3/repos/acme/app/issues"))
+            .respond_with(ResponseTemplate::new(403).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/repos/acme/app/issues"))
+            .and(body_partial_json(json!({"title": "Bug", "labels": ["p0"]})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "number": 9,
+                "state": "open",
+                "html_url": "https://github.com/acme/app/issues/9"
+            })))
+            .mount(&server)
+            .await;
+
+        let url = client(&server)
+            .create_issue(&NewIssue {
+                title: "Bug".to_string(),
+                body: "body".to_string(),
+                labels: vec!["p0".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(url, "https://github.com/acme/app/issues/9");
+    }
+
+    #[tokio::test]
+    async fn test_api_error_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/v3/repos/acme/app/issues/3"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "Not Found"})))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .set_issue_state(3, "open")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, GitHubError::Api { status: 404, ref message } if message == "Not Found")
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{GitHubError, TrackerError};
+use crate::tracker::{
+    fingerprint_in, fingerprint_marker, numeric_id, ExistingIssue, IssueState, IssueTracker,
+    NewIssue, TrackedIssue,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::process::Command;
+
+/// Backend that shells out to the `gh` CLI
+pub struct GhCli {
+    repo: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IssueInfo {
+    number: u64,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    body: String,
+}
+
+impl GhCli {
+    pub fn new(repo: Option<String>) -> Self {
+        Self { repo }
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::new("gh");
+        cmd.args(args);
+        if let Some(repo) = &self.repo {
+            cmd.arg("--repo").arg(repo);
+        }
+        cmd
+    }
+
+    async fn run(&self, mut cmd: Command) -> Result<String, GitHubError> {
+        let output = cmd.output().await.map_err(GitHubError::Io)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitHubError::GhCli(stderr.to_string()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for GhCli {
+    async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Vec<ExistingIssue>, TrackerError> {
+        let search = format!("{} in:body", fingerprint_marker(fingerprint));
+        let cmd = self.command(&[
+            "issue",
+            "list",
+            "--state",
+            "all", // include closed
+            "--search",
+            &search,
+            "--json",
+            "number,state",
+            "--limit",
+            "100",
+        ]);
+        let stdout = self.run(cmd).await?;
+
+        let issues: Vec<IssueInfo> =
+            serde_json::from_str(&stdout).map_err(|e| GitHubError::ParseOutput(e.to_string()))?;
+
+        Ok(issues
+            .into_iter()
+            .map(|info| ExistingIssue {
+                id: info.number.to_string(),
+                state: IssueState::from_api(&info.state),
+            })
+            .collect())
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> Result<String, TrackerError> {
+        let mut cmd = self.command(&[
+            "issue",
+            "create",
+            "--title",
+            &issue.title,
+            "--body",
+            &issue.body,
+        ]);
+        for label in &issue.labels {
+            cmd.arg("--label").arg(label);
+        }
+        for assignee in &issue.assignees {
+            cmd.arg("--assignee").arg(assignee);
+        }
+        if let Some(milestone) = &issue.milestone {
+            cmd.arg("--milestone").arg(milestone);
+        }
+
+        let result = match self.run(cmd).await {
+            Ok(url) => Ok(url),
+            // Check for label errors and provide helpful message
+            Err(GitHubError::GhCli(stderr))
+                if stderr.contains("label") && stderr.contains("not found") =>
+            {
+                Err(GitHubError::CreateFailed(format!(
+                    "{}. Run `polyrev init --repo <owner/repo>` to create required labels",
+                    stderr.trim()
+                )))
+            }
+            Err(GitHubError::GhCli(stderr)) => Err(GitHubError::CreateFailed(stderr)),
+            Err(e) => Err(e),
+        };
+        Ok(result?)
+    }
+
+    async fn comment_issue(&self, id: &str, body: &str) -> Result<(), TrackerError> {
+        let number = numeric_id(id)?.to_string();
+        let cmd = self.command(&["issue", "comment", &number, "--body", body]);
+        self.run(cmd).await?;
+        Ok(())
+    }
+
+    async fn reopen_issue(&self, id: &str) -> Result<(), TrackerError> {
+        let number = numeric_id(id)?.to_string();
+        let cmd = self.command(&["issue", "reopen", &number]);
+        self.run(cmd).await?;
+        Ok(())
+    }
+
+    async fn close_issue(&self, id: &str) -> Result<(), TrackerError> {
+        let number = numeric_id(id)?.to_string();
+        let cmd = self.command(&["issue", "close", &number]);
+        self.run(cmd).await?;
+        Ok(())
+    }
+
+    async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, TrackerError> {
+        let cmd = self.command(&[
+            "issue",
+            "list",
+            "--state",
+            "open",
+            "--label",
+            label,
+            "--json",
+            "number,body",
+            "--limit",
+            "1000",
+        ]);
+        let stdout = self.run(cmd).await?;
+
+        let issues: Vec<IssueInfo> =
+            serde_json::from_str(&stdout).map_err(|e| GitHubError::ParseOutput(e.to_string()))?;
+
+        Ok(issues
+            .into_iter()
+            .filter_map(|info| {
+                Some(TrackedIssue {
+                    id: info.number.to_string(),
+                    fingerprint: fingerprint_in(&info.body)?,
+                })
+            })
+            .collect())
+    }
+}
@@ -1,4 +1,94 @@
-mod dedupe;
-mod issue;
+mod checks;
+mod client;
+mod gh;
+mod plan_issue;
+mod review;
 
-pub use issue::{IssueCreator, IssueResult};
+pub use checks::CheckRun;
+pub use client::GitHubClient;
+pub use gh::GhCli;
+pub use plan_issue::PlanIssue;
+pub use review::PullRequestReviewer;
+
+use crate::config::{GithubBackendKind, GithubConfig};
+use crate::error::GitHubError;
+use crate::tracker::IssueTracker;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Default REST API base URL (github.com)
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Pick the GitHub backend from config and environment
+///
+/// `auto` uses the REST API when a token ($GITHUB_TOKEN, then $GH_TOKEN) and
+/// a repository are available, and falls back to the `gh` CLI otherwise.
+pub fn create_backend(
+    config: &GithubConfig,
+    repo: Option<String>,
+) -> Result<Arc<dyn IssueTracker>, GitHubError> {
+    let token = env_token();
+    let api_url = api_url(config);
+
+    match (config.backend, token, repo) {
+        (GithubBackendKind::Gh, _, repo) | (GithubBackendKind::Auto, None, repo) => {
+            debug!("Using gh CLI backend");
+            Ok(Arc::new(GhCli::new(repo)))
+        }
+        (GithubBackendKind::Auto, Some(_), None) => {
+            debug!("No repository configured, using gh CLI backend");
+            Ok(Arc::new(GhCli::new(None)))
+        }
+        (_, Some(token), Some(repo)) => {
+            debug!("Using GitHub REST backend at {}", api_url);
+            Ok(Arc::new(GitHubClient::new(&api_url, &token, &repo)?))
+        }
+        (GithubBackendKind::Api, None, _) => Err(GitHubError::Config(
+            "backend 'api' requires GITHUB_TOKEN (or GH_TOKEN)".to_string(),
+        )),
+        (GithubBackendKind::Api, Some(_), None) => Err(GitHubError::Config(
+            "backend 'api' requires a repository (--repo or github.repo)".to_string(),
+        )),
+    }
+}
+
+/// Build a REST client, for features the `gh` backend can't provide
+///
+/// Uses $GITHUB_TOKEN / $GH_TOKEN, falling back to `gh auth token`.
+pub fn create_api_client(
+    config: &GithubConfig,
+    repo: Option<String>,
+) -> Result<GitHubClient, GitHubError> {
+    let repo = repo.ok_or_else(|| {
+        GitHubError::Config("a repository is required (--repo or github.repo)".to_string())
+    })?;
+    let token = env_token().or_else(gh_auth_token).ok_or_else(|| {
+        GitHubError::Config("set GITHUB_TOKEN or log in with `gh auth login`".to_string())
+    })?;
+
+    GitHubClient::new(&api_url(config), &token, &repo)
+}
+
+fn env_token() -> Option<String> {
+    std::env::var("GITHUB_TOKEN")
+        .or_else(|_| std::env::var("GH_TOKEN"))
+        .ok()
+        .filter(|t| !t.is_empty())
+}
+
+fn gh_auth_token() -> Option<String> {
+    let output = std::process::Command::new("gh")
+        .args(["auth", "token"])
+        .output()
+        .ok()?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
+
+fn api_url(config: &GithubConfig) -> String {
+    config
+        .api_url
+        .clone()
+        .or_else(|| std::env::var("GITHUB_API_URL").ok())
+        .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+}
@@ -0,0 +1,443 @@
+//! Plan from a GitHub issue and report the plan back to it
+//!
+//! `polyrev plan --issue N` uses the issue's title, body, labels and comments
+//! as the spec. Once the plan is saved, its summary and task list are posted
+//! as a comment, optionally with one linked sub-issue per task. Planning the
+//! same issue again edits that comment and only files sub-issues for new tasks.
+
+use super::GitHubClient;
+use crate::error::GitHubError;
+use crate::planner::{UnifiedPlan, UnifiedTask};
+use crate::tracker::NewIssue;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Hidden marker on plan comments, so re-planning doesn't read them back
+pub const PLAN_MARKER: &str = "<!-- polyrev:plan -->";
+
+/// Prefix of the hidden marker naming a sub-issue's task
+const TASK_MARKER: &str = "<!-- polyrev:task ";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub login: String,
+}
+
+/// An issue as returned by the REST API
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueDetails {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub html_url: String,
+}
+
+/// A comment on an issue
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueComment {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// An issue and its discussion, ready to plan from
+#[derive(Debug, Clone)]
+pub struct IssueSpec {
+    pub issue: IssueDetails,
+    pub comments: Vec<IssueComment>,
+}
+
+impl IssueSpec {
+    /// Render the issue as a markdown spec, title first
+    ///
+    /// Previous polyrev plan comments are left out.
+    pub fn to_spec(&self) -> String {
+        let mut spec = format!("# {}\n\n", self.issue.title);
+        spec.push_str(&format!("GitHub issue #{}", self.issue.number));
+        if !self.issue.html_url.is_empty() {
+            spec.push_str(&format!(" ({})", self.issue.html_url));
+        }
+        spec.push('\n');
+        if !self.issue.labels.is_empty() {
+            let labels: Vec<&str> = self.issue.labels.iter().map(|l| l.name.as_str()).collect();
+            spec.push_str(&format!("Labels: {}\n", labels.join(", ")));
+        }
+
+        let body = self.issue.body.as_deref().unwrap_or_default().trim();
+        if !body.is_empty() {
+            spec.push_str(&format!("\n{}\n", body));
+        }
+
+        let comments: Vec<(&str, &str)> = self
+            .comments
+            .iter()
+            .filter_map(|c| {
+                let body = c.body.as_deref()?.trim();
+                let author = c.user.as_ref().map_or("unknown", |u| u.login.as_str());
+                (!body.is_empty() && !body.contains(PLAN_MARKER)).then_some((author, body))
+            })
+            .collect();
+        if !comments.is_empty() {
+            spec.push_str("\n## Comments\n");
+            for (author, body) in comments {
+                spec.push_str(&format!("\n**@{}:**\n\n{}\n", author, body));
+            }
+        }
+
+        spec
+    }
+}
+
+/// Reads a spec from an issue and publishes the resulting plan to it
+pub struct PlanIssue {
+    client: GitHubClient,
+    number: u64,
+}
+
+impl PlanIssue {
+    pub fn new(client: GitHubClient, number: u64) -> Self {
+        Self { client, number }
+    }
+
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    pub async fn fetch_spec(&self) -> Result<IssueSpec, GitHubError> {
+        let issue = self.client.get_issue(self.number).await?;
+        let comments = self.client.issue_comments(self.number).await?;
+        Ok(IssueSpec { issue, comments })
+    }
+
+    /// Make sure every task has a sub-issue, returning task id → issue number
+    ///
+    /// Tasks whose sub-issue exists from an earlier run keep it. New ones are
+    /// created in plan order, so dependencies are referenced by issue number.
+    pub async fn create_sub_issues(
+        &self,
+        plan: &UnifiedPlan,
+    ) -> Result<HashMap<String, u64>, GitHubError> {
+        let mut sub_issues: HashMap<String, u64> = self
+            .client
+            .sub_issues(self.number)
+            .await?
+            .into_iter()
+            .filter_map(|issue| {
+                let task = task_in(issue.body.as_deref()?)?;
+                Some((task.to_string(), issue.number))
+            })
+            .collect();
+        for task in &plan.tasks {
+            if let Some(number) = sub_issues.get(&task.id) {
+                info!("Task {} already has sub-issue #{}", task.id, number);
+                continue;
+            }
+            let issue = task_issue(task, self.number, &sub_issues);
+            let (number, url) = self.client.create_sub_issue(self.number, &issue).await?;
+            info!(
+                "Created sub-issue #{} for task {}: {}",
+                number, task.id, url
+            );
+            sub_issues.insert(task.id.clone(), number);
+        }
+        sub_issues.retain(|id, _| plan.tasks.iter().any(|t| &t.id == id));
+        Ok(sub_issues)
+    }
+
+    /// Post the plan summary and task list as a comment, replacing an earlier plan comment
+    pub async fn comment_plan(
+        &self,
+        plan: &UnifiedPlan,
+        sub_issues: &HashMap<String, u64>,
+    ) -> Result<(), GitHubError> {
+        let body = plan_comment(plan, sub_issues);
+        let comments = self.client.issue_comments(self.number).await?;
+        let earlier = comments
+            .iter()
+            .rev()
+            .find(|c| c.body.as_deref().is_some_and(|b| b.contains(PLAN_MARKER)));
+        match earlier {
+            Some(comment) => {
+                debug!("Updating plan comment {} on #{}", comment.id, self.number);
+                self.client.update_issue_comment(comment.id, &body).await
+            }
+            None => self.client.comment_issue(self.number, &body).await,
+        }
+    }
+}
+
+/// Markdown comment with the plan summary, tasks, risks and questions
+pub fn plan_comment(plan: &UnifiedPlan, sub_issues: &HashMap<String, u64>) -> String {
+    let mut body = format!("{}\n## polyrev plan\n\n", PLAN_MARKER);
+    if let Some(summary) = plan.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        body.push_str(&format!("{}\n\n", summary.trim()));
+    }
+
+    body.push_str(&format!("### Tasks ({})\n\n", plan.tasks.len()));
+    for task in &plan.tasks {
+        let reference = sub_issues
+            .get(&task.id)
+            .map(|n| format!(" (#{})", n))
+            .unwrap_or_default();
+        body.push_str(&format!("- [ ] `{}` {}{}", task.id, task.title, reference));
+        if !task.depends_on.is_empty() {
+            body.push_str(&format!(
+                " — after {}",
+                task_refs(&task.depends_on, sub_issues)
+            ));
+        }
+        body.push('\n');
+    }
+
+    if !plan.risks.is_empty() {
+        body.push_str("\n### Risks\n\n");
+        for risk in &plan.risks {
+            match &risk.mitigation {
+                Some(mitigation) => {
+                    body.push_str(&format!("- {} → {}\n", risk.description, mitigation))
+                }
+                None => body.push_str(&format!("- {}\n", risk.description)),
+            }
+        }
+    }
+
+    if !plan.questions.is_empty() {
+        body.push_str("\n### Questions\n\n");
+        for q in &plan.questions {
+            match &q.answer {
+                Some(answer) => body.push_str(&format!("- {} **{}**\n", q.question, answer)),
+                None => body.push_str(&format!("- {} _(open)_\n", q.question)),
+            }
+        }
+    }
+
+    body
+}
+
+/// Sub-issue for one task, pointing back at the parent issue
+pub fn task_issue(task: &UnifiedTask, parent: u64, sub_issues: &HashMap<String, u64>) -> NewIssue {
+    let mut body = format!(
+        "{}{} -->\nPart of #{} (task `{}`)\n",
+        TASK_MARKER, task.id, parent, task.id
+    );
+    if !task.description.trim().is_empty() {
+        body.push_str(&format!("\n{}\n", task.description.trim()));
+    }
+    if !task.files.target.is_empty() {
+        body.push_str("\n### Files\n\n");
+        for file in &task.files.target {
+            body.push_str(&format!("- `{}`\n", file.display()));
+        }
+    }
+    if !task.acceptance_criteria.is_empty() {
+        body.push_str("\n### Acceptance criteria\n\n");
+        for criterion in &task.acceptance_criteria {
+            if criterion.verification.is_empty() {
+                body.push_str(&format!("- [ ] {}\n", criterion.criterion));
+            } else {
+                body.push_str(&format!(
+                    "- [ ] {} (verify: {})\n",
+                    criterion.criterion, criterion.verification
+                ));
+            }
+        }
+    }
+    if !task.depends_on.is_empty() {
+        body.push_str(&format!(
+            "\nDepends on: {}\n",
+            task_refs(&task.depends_on, sub_issues)
+        ));
+    }
+
+    NewIssue {
+        title: task.title.clone(),
+        body,
+        ..Default::default()
+    }
+}
+
+/// Task id from a sub-issue's marker
+fn task_in(body: &str) -> Option<&str> {
+    let rest = &body[body.find(TASK_MARKER)? + TASK_MARKER.len()..];
+    let id = rest[..rest.find("-->")?].trim();
+    (!id.is_empty()).then_some(id)
+}
+
+/// Task ids as issue references where a sub-issue exists
+fn task_refs(ids: &[String], sub_issues: &HashMap<String, u64>) -> String {
+    ids.iter()
+        .map(|id| match sub_issues.get(id) {
+            Some(n) => format!("#{}", n),
+            None => format!("`{}`", id),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn plan() -> UnifiedPlan {
+        serde_json::from_value(json!({
+            "summary": "Add OAuth login.",
+            "tasks": [
+                {"id": "oauth-001", "title": "Add provider config",
+                 "files": {"target": ["src/auth/config.rs"]},
+                 "acceptance_criteria": [{"criterion": "Config parses", "verification": "cargo test"}]},
+                {"id": "oauth-002", "title": "Login flow", "depends_on": ["oauth-001"]}
+            ],
+            "risks": [{"description": "Token leakage", "mitigation": "Redact logs"}],
+            "questions": [{"question": "Which providers?", "answer": "GitHub"}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_spec_skips_plan_comments() {
+        let spec = IssueSpec {
+            issue: serde_json::from_value(json!({
+                "number": 12,
+                "title": "Support OAuth",
+                "body": "Users want SSO.\n",
+                "labels": [{"name": "feature"}, {"name": "auth"}],
+                "html_url": "https://github.com/acme/app/issues/12"
+            }))
+            .unwrap(),
+            comments: serde_json::from_value(json!([
+                {"user": {"login": "dana"}, "body": "Start with GitHub."},
+                {"user": {"login": "polyrev-bot"}, "body": format!("{}\nold plan", PLAN_MARKER)},
+                {"user": {"login": "lee"}, "body": "  "}
+            ]))
+            .unwrap(),
+        };
+
+        let text = spec.to_spec();
+        assert!(text.starts_with("# Support OAuth\n"));
+        assert!(text.contains("GitHub issue #12 (https://github.com/acme/app/issues/12)"));
+        assert!(text.contains("Labels: feature, auth"));
+        assert!(text.contains("Users want SSO."));
+        assert!(text.contains("**@dana:**\n\nStart with GitHub."));
+        assert!(!text.contains("old plan"));
+        assert!(!text.contains("@lee"));
+    }
+
+    #[test]
+    fn test_plan_comment_references_sub_issues() {
+        let comment = plan_comment(&plan(), &HashMap::from([("oauth-001".to_string(), 31)]));
+        assert!(comment.starts_with(PLAN_MARKER));
+        assert!(comment.contains("Add OAuth login."));
+        assert!(comment.contains("### Tasks (2)"));
+        assert!(comment.contains("- [ ] `oauth-001` Add provider config (#31)\n"));
+        assert!(comment.contains("- [ ] `oauth-002` Login flow — after #31\n"));
+        assert!(comment.contains("- Token leakage → Redact logs"));
+        assert!(comment.contains("- Which providers? **GitHub**"));
+
+        let without = plan_comment(&plan(), &HashMap::new());
+        assert!(without.contains("Login flow — after `oauth-001`"));
+    }
+
+    #[test]
+    fn test_task_issue_body() {
+        let plan = plan();
+        let issue = task_issue(&plan.tasks[0], 12, &HashMap::new());
+        assert_eq!(issue.title, "Add provider config");
+        assert!(issue.body.contains("Part of #12 (task `oauth-001`)"));
+        assert_eq!(task_in(&issue.body), Some("oauth-001"));
+        assert_eq!(task_in("Part of #12 (task `oauth-001`)"), None);
+        assert!(issue.body.contains("- `src/auth/config.rs`"));
+        assert!(issue
+            .body
+            .contains("- [ ] Config parses (verify: cargo test)"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_spec_and_publish() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/app/issues/12"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "number": 12,
+                "title": "Support OAuth",
+                "body": "Users want SSO.",
+                "labels": [{"name": "feature"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/app/issues/12/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 70, "user": {"login": "dana"}, "body": "Start with GitHub."},
+                {"id": 77, "user": {"login": "polyrev-bot"}, "body": format!("{}\nold plan", PLAN_MARKER)}
+            ])))
+            .mount(&server)
+            .await;
+        // An earlier run already filed the first task
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/app/issues/12/sub_issues"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"number": 31, "title": "Add provider config",
+                 "body": "<!-- polyrev:task oauth-001 -->\nPart of #12 (task `oauth-001`)\n"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/app/issues"))
+            .and(body_string_contains("Depends on: #31"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": 9002, "number": 32, "state": "open",
+                "html_url": "https://github.com/acme/app/issues/32"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/app/issues/12/sub_issues"))
+            .and(body_partial_json(json!({"sub_issue_id": 9002})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({"number": 12})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/acme/app/issues/comments/77"))
+            .and(body_string_contains("Login flow (#32) — after #31"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 77})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/app/issues/12/comments"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": 1})))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::new(&server.uri(), "t0ken", "acme/app").unwrap();
+        let target = PlanIssue::new(client, 12);
+
+        let spec = target.fetch_spec().await.unwrap().to_spec();
+        assert!(spec.contains("Start with GitHub."));
+
+        let plan = plan();
+        let sub_issues = target.create_sub_issues(&plan).await.unwrap();
+        assert_eq!(sub_issues["oauth-001"], 31);
+        assert_eq!(sub_issues["oauth-002"], 32);
+        target.comment_plan(&plan, &sub_issues).await.unwrap();
+    }
+}
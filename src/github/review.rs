@@ -0,0 +1,126 @@
+//! Publish findings as a GitHub pull request review
+//!
+//! Builds on the shared plan in `tracker::review`: new inline comments are
+//! posted as a single review, existing comments are edited in place.
+
+use super::GitHubClient;
+use crate::error::GitHubError;
+use crate::parser::Finding;
+use crate::tracker::review::{
+    commentable_lines, plan_review, ReviewPlan, ReviewSummary, REVIEW_MARKER,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+/// A file changed in the pull request
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestFile {
+    pub filename: String,
+    /// Unified diff hunks (absent for binary or very large files)
+    #[serde(default)]
+    pub patch: Option<String>,
+}
+
+/// A submitted pull request review
+#[derive(Debug, Clone, Deserialize)]
+pub struct Review {
+    pub id: u64,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// Publishes findings to a pull request via the REST API
+pub struct PullRequestReviewer {
+    client: GitHubClient,
+    number: u64,
+}
+
+impl PullRequestReviewer {
+    pub fn new(client: GitHubClient, number: u64) -> Self {
+        Self { client, number }
+    }
+
+    /// Work out which comments to add, update and resolve
+    ///
+    /// `prefix` is the target's directory within the repository.
+    pub async fn plan(
+        &self,
+        findings: &[(String, Finding)],
+        prefix: &str,
+    ) -> Result<ReviewPlan, GitHubError> {
+        let files = self.client.pull_request_files(self.number).await?;
+        let diff: HashMap<String, HashSet<u32>> = files
+            .into_iter()
+            .map(|f| {
+                let lines = f
+                    .patch
+                    .as_deref()
+                    .map(commentable_lines)
+                    .unwrap_or_default();
+                (f.filename, lines)
+            })
+            .collect();
+
+        let existing = self.client.review_comments(self.number).await?;
+        debug!(
+            "PR #{}: {} changed files, {} existing review comments",
+            self.number,
+            diff.len(),
+            existing.len()
+        );
+
+        Ok(plan_review(findings, &diff, &existing, prefix))
+    }
+
+    /// Apply the plan: post one review, then edit existing comments
+    pub async fn publish(&self, plan: &ReviewPlan) -> Result<ReviewSummary, GitHubError> {
+        let summary = ReviewSummary::from_plan(plan);
+
+        let reviews = self.client.reviews(self.number).await?;
+        let latest = reviews
+            .iter()
+            .rev()
+            .find(|r| r.body.as_deref().is_some_and(|b| b.contains(REVIEW_MARKER)));
+
+        let inline_total = plan.new_comments.len() + plan.updated.len();
+        let body = plan.body(inline_total);
+
+        match latest {
+            // Nothing new to anchor; refresh the previous review's body in place
+            Some(review) if plan.new_comments.is_empty() => {
+                if review.body.as_deref() != Some(body.as_str()) {
+                    self.client
+                        .update_review(self.number, review.id, &body)
+                        .await?;
+                }
+            }
+            _ if plan.new_comments.is_empty() && plan.body_findings.is_empty() => {}
+            _ => {
+                let head_sha = self.client.pull_request_head(self.number).await?;
+                self.client
+                    .create_review(self.number, &head_sha, &body, &plan.new_comments)
+                    .await?;
+                if let Some(review) = latest {
+                    let superseded = format!(
+                        "{}\n_Superseded by a newer polyrev review._\n",
+                        REVIEW_MARKER
+                    );
+                    self.client
+                        .update_review(self.number, review.id, &superseded)
+                        .await?;
+                }
+            }
+        }
+
+        for (id, body) in plan.updated.iter().chain(plan.resolved.iter()) {
+            self.client.update_review_comment(*id, body).await?;
+        }
+
+        info!(
+            "PR #{}: {} new inline, {} updated, {} resolved, {} in review body",
+            self.number, summary.inline, summary.updated, summary.resolved, summary.in_body
+        );
+        Ok(summary)
+    }
+}
@@ -0,0 +1,682 @@
+use super::merge_request::{DiffRefs, Discussion, MergeRequestDiff, Note};
+use crate::config::GitlabConfig;
+use crate::error::{GitLabError, TrackerError};
+use crate::tracker::{
+    fingerprint_in, fingerprint_marker, next_link, numeric_id, ExistingIssue, IssueState,
+    IssueTracker, NewIssue, TrackedIssue,
+};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+/// Default instance when neither config nor $CI_SERVER_URL set one
+pub const DEFAULT_URL: &str = "https://gitlab.com";
+
+/// Retries after a 429
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest we'll sleep for a rate limit before giving up
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+
+/// Wait used when a 429 doesn't say how long to back off
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Safety cap on followed pagination links
+const MAX_PAGES: usize = 50;
+
+/// How the client authenticates
+#[derive(Debug, Clone)]
+pub enum GitLabToken {
+    /// Personal/project access token ($GITLAB_TOKEN)
+    Private(String),
+    /// CI job token ($CI_JOB_TOKEN)
+    Job(String),
+}
+
+/// Minimal GitLab REST (v4) client for one project
+pub struct GitLabClient {
+    http: reqwest::Client,
+    api_url: String,
+    /// URL-encoded project id or path
+    project: String,
+    /// Username -> user id lookups (None when the user doesn't exist)
+    user_ids: Mutex<HashMap<String, Option<u64>>>,
+    /// Active milestones as (title, id), fetched on first use
+    milestones: OnceCell<Vec<(String, u64)>>,
+}
+
+#[derive(Deserialize)]
+struct IssueInfo {
+    iid: u64,
+    state: String,
+    #[serde(default)]
+    web_url: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct MilestoneInfo {
+    id: u64,
+    title: String,
+}
+
+impl GitLabClient {
+    /// `url` is the instance URL, e.g. `https://gitlab.example.com`
+    pub fn new(url: &str, token: GitLabToken, project: &str) -> Result<Self, GitLabError> {
+        let (name, token) = match token {
+            GitLabToken::Private(t) => ("PRIVATE-TOKEN", t),
+            GitLabToken::Job(t) => ("JOB-TOKEN", t),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("polyrev"));
+        let mut auth = HeaderValue::from_str(&token)
+            .map_err(|e| GitLabError::Config(format!("invalid token: {}", e)))?;
+        auth.set_sensitive(true);
+        headers.insert(name, auth);
+
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            http,
+            api_url: format!("{}/api/v4", url.trim_end_matches('/')),
+            project: encode_project(project),
+            user_ids: Mutex::new(HashMap::new()),
+            milestones: OnceCell::new(),
+        })
+    }
+
+    /// Build a client from config and environment
+    ///
+    /// The instance URL comes from `gitlab.url`, then $CI_SERVER_URL; the
+    /// project from `project` (--repo or `gitlab.project`), then $CI_PROJECT_ID.
+    /// Authenticates with $GITLAB_TOKEN, falling back to $CI_JOB_TOKEN.
+    pub fn from_config(
+        config: &GitlabConfig,
+        project: Option<String>,
+    ) -> Result<Self, GitLabError> {
+        let url = config
+            .url
+            .clone()
+            .or_else(|| env_var("CI_SERVER_URL"))
+            .unwrap_or_else(|| DEFAULT_URL.to_string());
+        let project = project
+            .or_else(|| env_var("CI_PROJECT_ID"))
+            .ok_or_else(|| {
+                GitLabError::Config("a project is required (--repo or gitlab.project)".to_string())
+            })?;
+        let token = env_var("GITLAB_TOKEN")
+            .map(GitLabToken::Private)
+            .or_else(|| env_var("CI_JOB_TOKEN").map(GitLabToken::Job))
+            .ok_or_else(|| GitLabError::Config("set GITLAB_TOKEN (or CI_JOB_TOKEN)".to_string()))?;
+
+        debug!("Using GitLab backend at {} for project {}", url, project);
+        Self::new(&url, token, &project)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/projects/{}{}", self.api_url, self.project, path)
+    }
+
+    /// Send a request, waiting out rate limits, and fail on non-2xx responses
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, GitLabError> {
+        let mut attempt = 0;
+        loop {
+            let mut request = self.http.request(method.clone(), url);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RATE_LIMIT_RETRIES {
+                let wait = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_RATE_LIMIT_WAIT);
+                if wait <= MAX_RATE_LIMIT_WAIT {
+                    attempt += 1;
+                    warn!(
+                        "GitLab rate limit hit, retrying in {}s (attempt {}/{})",
+                        wait.as_secs(),
+                        attempt,
+                        MAX_RATE_LIMIT_RETRIES
+                    );
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+            }
+
+            // Errors come as {"message": ...} or {"error": ...}; message may be an object
+            let message = response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|v| {
+                    v.get("message").or_else(|| v.get("error")).map(|m| {
+                        m.as_str()
+                            .map(String::from)
+                            .unwrap_or_else(|| m.to_string())
+                    })
+                })
+                .unwrap_or_else(|| status.to_string());
+            return Err(GitLabError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+    ) -> Result<T, GitLabError> {
+        let value: Value = self.send(method, url, body).await?.json().await?;
+        serde_json::from_value(value).map_err(|e| GitLabError::ParseOutput(e.to_string()))
+    }
+
+    /// GET every page of a list endpoint, following `Link: rel="next"`
+    async fn get_paginated<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, GitLabError> {
+        let mut items = Vec::new();
+        let mut next = Some(url.to_string());
+        let mut pages = 0;
+
+        while let Some(url) = next.take() {
+            let response = self.send(Method::GET, &url, None).await?;
+            next = next_link(response.headers());
+
+            let page: Vec<Value> = response.json().await?;
+            for item in page {
+                items.push(
+                    serde_json::from_value(item)
+                        .map_err(|e| GitLabError::ParseOutput(e.to_string()))?,
+                );
+            }
+
+            pages += 1;
+            if pages >= MAX_PAGES {
+                warn!("Stopped after {} pages of {}", MAX_PAGES, url);
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Resolve assignee usernames to user ids, looking each one up once per client
+    async fn user_ids(&self, usernames: &[&str]) -> Result<Vec<u64>, GitLabError> {
+        let mut ids = Vec::new();
+        for &username in usernames {
+            let cached = self.user_ids.lock().unwrap().get(username).copied();
+            let id = match cached {
+                Some(id) => id,
+                None => {
+                    let mut url = reqwest::Url::parse(&format!("{}/users", self.api_url))
+                        .map_err(|e| GitLabError::Config(format!("invalid url: {}", e)))?;
+                    url.query_pairs_mut().append_pair("username", username);
+                    let users: Vec<UserInfo> =
+                        self.send_json(Method::GET, url.as_str(), None).await?;
+                    let id = users.first().map(|user| user.id);
+                    if id.is_none() {
+                        warn!("GitLab user '{}' not found, not assigning", username);
+                    }
+                    self.user_ids
+                        .lock()
+                        .unwrap()
+                        .insert(username.to_string(), id);
+                    id
+                }
+            };
+            if let Some(id) = id.filter(|id| !ids.contains(id)) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Id of the active project milestone with this title
+    async fn milestone_id(&self, title: &str) -> Result<u64, GitLabError> {
+        let milestones = self
+            .milestones
+            .get_or_try_init(|| async {
+                let url = self.url("/milestones?state=active&per_page=100");
+                let infos: Vec<MilestoneInfo> = self.get_paginated(&url).await?;
+                Ok::<_, GitLabError>(infos.into_iter().map(|m| (m.title, m.id)).collect())
+            })
+            .await?;
+        milestones
+            .iter()
+            .find(|(t, _)| t == title)
+            .map(|(_, id)| *id)
+            .ok_or_else(|| GitLabError::Config(format!("no active milestone '{}'", title)))
+    }
+}
+
+/// Issue endpoints
+impl GitLabClient {
+    /// Find issues (open or closed) whose description contains `text`
+    pub async fn search_issues(&self, text: &str) -> Result<Vec<ExistingIssue>, GitLabError> {
+        let mut url = reqwest::Url::parse(&self.url("/issues"))
+            .map_err(|e| GitLabError::Config(format!("invalid url: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("search", text)
+            .append_pair("in", "description")
+            .append_pair("state", "all")
+            .append_pair("per_page", "100");
+
+        let issues: Vec<IssueInfo> = self.get_paginated(url.as_str()).await?;
+        debug!("Search '{}' matched {} issues", text, issues.len());
+
+        Ok(issues
+            .into_iter()
+            .map(|info| ExistingIssue {
+                id: info.iid.to_string(),
+                state: IssueState::from_api(&info.state),
+            })
+            .collect())
+    }
+
+    pub async fn create_issue(&self, issue: &NewIssue) -> Result<String, GitLabError> {
+        debug!(
+            "Creating GitLab issue for fingerprint {}",
+            issue.fingerprint
+        );
+        let mut body = json!({
+            "title": issue.title,
+            "description": issue.body,
+            "labels": issue.labels.join(","),
+        });
+        // Already the configured defaults, or a reviewer override that replaces them
+        let usernames: Vec<&str> = issue.assignees.iter().map(String::as_str).collect();
+        let assignee_ids = self.user_ids(&usernames).await?;
+        if !assignee_ids.is_empty() {
+            body["assignee_ids"] = json!(assignee_ids);
+        }
+        if let Some(title) = &issue.milestone {
+            body["milestone_id"] = json!(self.milestone_id(title).await?);
+        }
+
+        let created: IssueInfo = self
+            .send_json(Method::POST, &self.url("/issues"), Some(&body))
+            .await?;
+        Ok(created.web_url)
+    }
+
+    pub async fn comment_issue(&self, iid: u64, body: &str) -> Result<(), GitLabError> {
+        let url = self.url(&format!("/issues/{}/notes", iid));
+        self.send(Method::POST, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    /// Open issues with `label` that carry a fingerprint marker
+    pub async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, GitLabError> {
+        let mut url = reqwest::Url::parse(&self.url("/issues"))
+            .map_err(|e| GitLabError::Config(format!("invalid url: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("state", "opened")
+            .append_pair("labels", label)
+            .append_pair("per_page", "100");
+
+        let issues: Vec<IssueInfo> = self.get_paginated(url.as_str()).await?;
+        Ok(issues
+            .into_iter()
+            .filter_map(|info| {
+                Some(TrackedIssue {
+                    id: info.iid.to_string(),
+                    fingerprint: fingerprint_in(info.description.as_deref()?)?,
+                })
+            })
+            .collect())
+    }
+
+    /// Apply a state event ("reopen" or "close")
+    pub async fn set_issue_state(&self, iid: u64, state_event: &str) -> Result<(), GitLabError> {
+        let url = self.url(&format!("/issues/{}", iid));
+        self.send(
+            Method::PUT,
+            &url,
+            Some(&json!({ "state_event": state_event })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Merge request endpoints (used by `polyrev review-pr`)
+impl GitLabClient {
+    pub async fn merge_request_diff_refs(&self, iid: u64) -> Result<DiffRefs, GitLabError> {
+        let mr: Value = self
+            .send_json(
+                Method::GET,
+                &self.url(&format!("/merge_requests/{}", iid)),
+                None,
+            )
+            .await?;
+        let refs = mr
+            .get("diff_refs")
+            .filter(|r| !r.is_null())
+            .cloned()
+            .ok_or_else(|| {
+                GitLabError::ParseOutput("merge request has no diff_refs".to_string())
+            })?;
+        serde_json::from_value(refs).map_err(|e| GitLabError::ParseOutput(e.to_string()))
+    }
+
+    pub async fn merge_request_diffs(
+        &self,
+        iid: u64,
+    ) -> Result<Vec<MergeRequestDiff>, GitLabError> {
+        let url = self.url(&format!("/merge_requests/{}/diffs?per_page=100", iid));
+        self.get_paginated(&url).await
+    }
+
+    pub async fn discussions(&self, iid: u64) -> Result<Vec<Discussion>, GitLabError> {
+        let url = self.url(&format!("/merge_requests/{}/discussions?per_page=100", iid));
+        self.get_paginated(&url).await
+    }
+
+    /// Start a diff discussion; `position` is a GitLab text position object
+    pub async fn create_discussion(
+        &self,
+        iid: u64,
+        body: &str,
+        position: &Value,
+    ) -> Result<(), GitLabError> {
+        let url = self.url(&format!("/merge_requests/{}/discussions", iid));
+        self.send(
+            Method::POST,
+            &url,
+            Some(&json!({ "body": body, "position": position })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_discussion_note(
+        &self,
+        iid: u64,
+        discussion_id: &str,
+        note_id: u64,
+        body: &str,
+    ) -> Result<(), GitLabError> {
+        let url = self.url(&format!(
+            "/merge_requests/{}/discussions/{}/notes/{}",
+            iid, discussion_id, note_id
+        ));
+        self.send(Method::PUT, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn resolve_discussion(
+        &self,
+        iid: u64,
+        discussion_id: &str,
+    ) -> Result<(), GitLabError> {
+        let url = self.url(&format!(
+            "/merge_requests/{}/discussions/{}?resolved=true",
+            iid, discussion_id
+        ));
+        self.send(Method::PUT, &url, None).await?;
+        Ok(())
+    }
+
+    /// Top-level (non-diff) notes on the merge request
+    pub async fn merge_request_notes(&self, iid: u64) -> Result<Vec<Note>, GitLabError> {
+        let url = self.url(&format!(
+            "/merge_requests/{}/notes?per_page=100&sort=asc",
+            iid
+        ));
+        self.get_paginated(&url).await
+    }
+
+    pub async fn create_merge_request_note(&self, iid: u64, body: &str) -> Result<(), GitLabError> {
+        let url = self.url(&format!("/merge_requests/{}/notes", iid));
+        self.send(Method::POST, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_merge_request_note(
+        &self,
+        iid: u64,
+        note_id: u64,
+        body: &str,
+    ) -> Result<(), GitLabError> {
+        let url = self.url(&format!("/merge_requests/{}/notes/{}", iid, note_id));
+        self.send(Method::PUT, &url, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for GitLabClient {
+    async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Vec<ExistingIssue>, TrackerError> {
+        Ok(self.search_issues(&fingerprint_marker(fingerprint)).await?)
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> Result<String, TrackerError> {
+        Ok(GitLabClient::create_issue(self, issue).await?)
+    }
+
+    async fn comment_issue(&self, id: &str, body: &str) -> Result<(), TrackerError> {
+        Ok(GitLabClient::comment_issue(self, numeric_id(id)?, body).await?)
+    }
+
+    async fn reopen_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_issue_state(numeric_id(id)?, "reopen").await?)
+    }
+
+    async fn close_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_issue_state(numeric_id(id)?, "close").await?)
+    }
+
+    async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, TrackerError> {
+        Ok(GitLabClient::list_open_issues(self, label).await?)
+    }
+}
+
+/// Project ids are used as-is; paths like "group/app" are URL-encoded
+fn encode_project(project: &str) -> String {
+    project.trim_matches('/').replace('/', "%2F")
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(server: &MockServer) -> GitLabClient {
+        GitLabClient::new(
+            &server.uri(),
+            GitLabToken::Private("t0ken".to_string()),
+            "group/app",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_encode_project() {
+        assert_eq!(encode_project("group/sub/app"), "group%2Fsub%2Fapp");
+        assert_eq!(encode_project("42"), "42");
+    }
+
+    #[tokio::test]
+    async fn test_search_issues_by_description() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/group%2Fapp/issues"))
+            .and(query_param("search", "polyrev:fp:abc"))
+            .and(query_param("in", "description"))
+            .and(query_param("state", "all"))
+            .and(header("private-token", "t0ken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"iid": 3, "state": "opened"},
+                {"iid": 1, "state": "closed"}
+            ])))
+            .mount(&server)
+            .await;
+
+        let issues = client(&server).find_by_fingerprint("abc").await.unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ExistingIssue {
+                    id: "3".to_string(),
+                    state: IssueState::Open
+                },
+                ExistingIssue {
+                    id: "1".to_string(),
+                    state: IssueState::Closed
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_resolves_assignees() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .and(query_param("username", "alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 17}])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/group%2Fapp/issues"))
+            .and(body_partial_json(json!({
+                "title": "Bug",
+                "labels": "polyrev,p0",
+                "assignee_ids": [17]
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "iid": 5,
+                "state": "opened",
+                "web_url": "https://gitlab.example.com/group/app/-/issues/5"
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let issue = NewIssue {
+            title: "Bug".to_string(),
+            body: "body".to_string(),
+            labels: vec!["polyrev".to_string(), "p0".to_string()],
+            assignees: vec!["alice".to_string()],
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            let url = IssueTracker::create_issue(&client, &issue).await.unwrap();
+            assert_eq!(url, "https://gitlab.example.com/group/app/-/issues/5");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_with_routed_assignees_and_milestone() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/users"))
+            .and(query_param("username", "bob"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 23}])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/group%2Fapp/milestones"))
+            .and(query_param("state", "active"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"id": 4, "title": "Q2"},
+                {"id": 9, "title": "Q3 hardening"}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/group%2Fapp/issues"))
+            .and(body_partial_json(json!({
+                "assignee_ids": [23],
+                "milestone_id": 9
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "iid": 6,
+                "state": "opened",
+                "web_url": "https://gitlab.example.com/group/app/-/issues/6"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // A reviewer override replaced the configured assignees before the issue got here
+        let client = client(&server);
+        let issue = NewIssue {
+            title: "Bug".to_string(),
+            assignees: vec!["bob".to_string()],
+            milestone: Some("Q3 hardening".to_string()),
+            ..Default::default()
+        };
+        let url = IssueTracker::create_issue(&client, &issue).await.unwrap();
+        assert_eq!(url, "https://gitlab.example.com/group/app/-/issues/6");
+    }
+
+    #[tokio::test]
+    async fn test_reopen_retries_after_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/group%2Fapp/issues/4"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/group%2Fapp/issues/4"))
+            .and(body_partial_json(json!({"state_event": "reopen"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"iid": 4})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(&server).reopen_issue("4").await.unwrap();
+    }
+}
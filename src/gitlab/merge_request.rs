@@ -0,0 +1,278 @@
+//! Publish findings as GitLab merge request discussions
+//!
+//! Uses the shared plan in `tracker::review`: each inline finding becomes a
+//! diff discussion thread, and the review body is a single summary note that
+//! is edited in place on later runs.
+
+use super::GitLabClient;
+use crate::error::GitLabError;
+use crate::parser::Finding;
+use crate::tracker::review::{
+    commentable_lines, plan_review, ReviewComment, ReviewPlan, ReviewSummary, REVIEW_MARKER,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+/// Commits a merge request diff is based on (needed to position comments)
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiffRefs {
+    pub base_sha: String,
+    pub start_sha: String,
+    pub head_sha: String,
+}
+
+/// A file changed in the merge request
+#[derive(Debug, Clone, Deserialize)]
+pub struct MergeRequestDiff {
+    pub new_path: String,
+    pub old_path: String,
+    /// Unified diff hunks (empty for binary or collapsed files)
+    #[serde(default)]
+    pub diff: String,
+}
+
+/// A note (comment) on a merge request
+#[derive(Debug, Clone, Deserialize)]
+pub struct Note {
+    pub id: u64,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// A discussion thread; diff comments start a thread of their own
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discussion {
+    pub id: String,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+}
+
+/// Publishes findings to a merge request via the REST API
+pub struct MergeRequestReviewer {
+    client: GitLabClient,
+    iid: u64,
+}
+
+/// Review plan plus the GitLab ids needed to carry it out
+pub struct MergeRequestPlan {
+    pub plan: ReviewPlan,
+    /// new path → old path, for diff positions
+    old_paths: HashMap<String, String>,
+    /// note id → discussion id
+    discussions: HashMap<u64, String>,
+}
+
+impl MergeRequestReviewer {
+    pub fn new(client: GitLabClient, iid: u64) -> Self {
+        Self { client, iid }
+    }
+
+    /// Work out which threads to start, update and resolve
+    ///
+    /// `prefix` is the target's directory within the repository.
+    pub async fn plan(
+        &self,
+        findings: &[(String, Finding)],
+        prefix: &str,
+    ) -> Result<MergeRequestPlan, GitLabError> {
+        let diffs = self.client.merge_request_diffs(self.iid).await?;
+        let mut old_paths = HashMap::new();
+        let diff: HashMap<String, HashSet<u32>> = diffs
+            .into_iter()
+            .map(|d| {
+                old_paths.insert(d.new_path.clone(), d.old_path);
+                (d.new_path, commentable_lines(&d.diff))
+            })
+            .collect();
+
+        // Only the first note of a thread carries polyrev's marker
+        let mut discussions = HashMap::new();
+        let existing: Vec<ReviewComment> = self
+            .client
+            .discussions(self.iid)
+            .await?
+            .into_iter()
+            .filter_map(|d| {
+                let note = d.notes.into_iter().next()?;
+                discussions.insert(note.id, d.id);
+                Some(ReviewComment {
+                    id: note.id,
+                    body: note.body,
+                })
+            })
+            .collect();
+        debug!(
+            "MR !{}: {} changed files, {} existing discussions",
+            self.iid,
+            diff.len(),
+            existing.len()
+        );
+
+        Ok(MergeRequestPlan {
+            plan: plan_review(findings, &diff, &existing, prefix),
+            old_paths,
+            discussions,
+        })
+    }
+
+    /// Apply the plan: start new threads, edit and resolve existing ones, then
+    /// create or refresh the summary note
+    pub async fn publish(&self, mr_plan: &MergeRequestPlan) -> Result<ReviewSummary, GitLabError> {
+        let plan = &mr_plan.plan;
+        let summary = ReviewSummary::from_plan(plan);
+
+        if !plan.new_comments.is_empty() {
+            let refs = self.client.merge_request_diff_refs(self.iid).await?;
+            for comment in &plan.new_comments {
+                let old_path = mr_plan
+                    .old_paths
+                    .get(&comment.path)
+                    .unwrap_or(&comment.path);
+                let position = json!({
+                    "position_type": "text",
+                    "base_sha": refs.base_sha,
+                    "start_sha": refs.start_sha,
+                    "head_sha": refs.head_sha,
+                    "new_path": comment.path,
+                    "old_path": old_path,
+                    "new_line": comment.line,
+                });
+                self.client
+                    .create_discussion(self.iid, &comment.body, &position)
+                    .await?;
+            }
+        }
+
+        for (note_id, body) in &plan.updated {
+            if let Some(discussion) = mr_plan.discussions.get(note_id) {
+                self.client
+                    .update_discussion_note(self.iid, discussion, *note_id, body)
+                    .await?;
+            }
+        }
+        for (note_id, body) in &plan.resolved {
+            if let Some(discussion) = mr_plan.discussions.get(note_id) {
+                self.client
+                    .update_discussion_note(self.iid, discussion, *note_id, body)
+                    .await?;
+                self.client.resolve_discussion(self.iid, discussion).await?;
+            }
+        }
+
+        let inline_total = plan.new_comments.len() + plan.updated.len();
+        let body = plan.body(inline_total);
+        let notes = self.client.merge_request_notes(self.iid).await?;
+        match notes.iter().rev().find(|n| n.body.contains(REVIEW_MARKER)) {
+            Some(note) if note.body != body => {
+                self.client
+                    .update_merge_request_note(self.iid, note.id, &body)
+                    .await?;
+            }
+            Some(_) => {}
+            None if plan.new_comments.is_empty() && plan.body_findings.is_empty() => {}
+            None => {
+                self.client
+                    .create_merge_request_note(self.iid, &body)
+                    .await?;
+            }
+        }
+
+        info!(
+            "MR !{}: {} new threads, {} updated, {} resolved, {} in summary note",
+            self.iid, summary.inline, summary.updated, summary.resolved, summary.in_body
+        );
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+    use crate::gitlab::client::GitLabToken;
+    use std::path::PathBuf;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn finding(line: u32) -> Finding {
+        Finding {
+            id: "T-1".to_string(),
+            title: "Unchecked input".to_string(),
+            priority: Priority::P1,
+            file: PathBuf::from("src/new.rs"),
+            line,
+            description: "desc".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_starts_diff_discussions() {
+        let server = MockServer::start().await;
+        let mr = "/api/v4/projects/7/merge_requests/12";
+
+        Mock::given(method("GET"))
+            .and(path(format!("{}/diffs", mr)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "new_path": "src/new.rs",
+                "old_path": "src/old.rs",
+                "diff": "@@ -1,2 +1,3 @@\n a\n+b\n c\n"
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/discussions", mr)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(mr))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "iid": 12,
+                "diff_refs": {"base_sha": "b", "start_sha": "s", "head_sha": "h"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/discussions", mr)))
+            .and(body_partial_json(json!({
+                "position": {
+                    "position_type": "text",
+                    "head_sha": "h",
+                    "new_path": "src/new.rs",
+                    "old_path": "src/old.rs",
+                    "new_line": 2
+                }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": "d1"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/notes", mr)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/notes", mr)))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": 1})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client =
+            GitLabClient::new(&server.uri(), GitLabToken::Private("t".to_string()), "7").unwrap();
+        let reviewer = MergeRequestReviewer::new(client, 12);
+        // Line 2 is in the diff; line 40 goes to the summary note
+        let findings = vec![
+            ("r".to_string(), finding(2)),
+            ("r".to_string(), finding(40)),
+        ];
+
+        let plan = reviewer.plan(&findings, "").await.unwrap();
+        let summary = reviewer.publish(&plan).await.unwrap();
+        assert_eq!((summary.inline, summary.in_body), (1, 1));
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod merge_request;
+
+pub use client::{GitLabClient, DEFAULT_URL};
+pub use merge_request::MergeRequestReviewer;
@@ -1,13 +1,19 @@
+pub mod calibration;
 pub mod cli;
 pub mod config;
 pub mod discovery;
 pub mod error;
 pub mod github;
+pub mod gitlab;
 pub mod output;
 pub mod parser;
+pub mod patch;
 pub mod planner;
 pub mod postprocess;
 pub mod provider;
 pub mod runner;
 pub mod state;
+pub mod suppression;
+pub mod tracker;
 pub mod tui;
+pub mod verify;
@@ -1,19 +1,25 @@
 use clap::Parser;
 use tracing_subscriber::{fmt, EnvFilter};
 
+mod calibration;
 mod cli;
 mod config;
 mod discovery;
 mod error;
 mod github;
+mod gitlab;
 mod output;
 mod parser;
+mod patch;
 mod planner;
 mod postprocess;
 mod provider;
 mod runner;
 mod state;
+mod suppression;
+mod tracker;
 mod tui;
+mod verify;
 
 use cli::{Cli, Commands};
 
@@ -35,8 +41,12 @@ async fn main() -> anyhow::Result<()> {
         Commands::Issue(args) => cli::issue::execute(args).await,
         Commands::Init(args) => cli::init::execute(args),
         Commands::Postprocess(args) => cli::postprocess::execute(args).await,
+        Commands::Verify(args) => cli::verify::execute(args).await,
         Commands::Plan(args) => cli::plan::execute(args).await,
         Commands::Enqueue(args) => cli::enqueue::execute(args),
+        Commands::Check(args) => cli::check::execute(args).await,
+        Commands::ReviewPr(args) => cli::review_pr::execute(args).await,
+        Commands::Apply(args) => cli::apply::execute(args),
         Commands::Tui(args) => cli::tui::execute(args),
         Commands::Schema => cli::schema::execute(),
     }
@@ -1,5 +1,7 @@
+mod permalink;
 mod report;
 mod summary;
 
+pub use permalink::{markdown_location, repo_path, Permalinks, SourceRevision};
 pub use report::write_reviewer_report;
-pub use summary::write_summary;
+pub use summary::{write_summary, SummaryReport};
@@ -0,0 +1,337 @@
+//! Source permalinks pinned to the reviewed commit
+//!
+//! `polyrev run` records the target's `HEAD` when it starts; reports and
+//! issue bodies link `file:line` to that commit on GitHub or GitLab so the
+//! lines don't drift as the default branch moves. When the target is a
+//! subdirectory of its repository, links are rooted at that subdirectory.
+
+use super::summary::SummaryReport;
+use crate::config::{Config, TrackerKind};
+use crate::patch::git;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The commit a run reviewed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRevision {
+    pub sha: String,
+    /// The working tree had uncommitted changes, so lines may differ from `sha`
+    #[serde(default)]
+    pub dirty: bool,
+    /// Path of the target within its repository, e.g. `services/api/` (empty at the root)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+}
+
+impl SourceRevision {
+    /// `HEAD` of the target, or `None` outside a git repository
+    pub fn capture(target: &Path) -> Option<Self> {
+        let sha = git(target, &["rev-parse", "HEAD"]).ok()?;
+        let dirty = git(target, &["status", "--porcelain", "--untracked-files=no"])
+            .map(|status| !status.is_empty())
+            .unwrap_or(false);
+        let prefix = git(target, &["rev-parse", "--show-prefix"]).unwrap_or_default();
+        Some(Self { sha, dirty, prefix })
+    }
+
+    /// The commit recorded in a run's `summary.json`, else the target's `HEAD`
+    pub fn of_report(report_dir: &Path, target: &Path) -> Option<Self> {
+        std::fs::read_to_string(report_dir.join("summary.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<SummaryReport>(&content).ok())
+            .and_then(|summary| summary.commit)
+            .or_else(|| Self::capture(target))
+    }
+}
+
+/// A finding's path relative to the repository root rather than the target
+///
+/// Strips `./`, uses forward slashes and prepends `prefix` (the target's
+/// directory within the repository, see [`SourceRevision::prefix`]).
+pub fn repo_path(prefix: &str, file: &Path) -> String {
+    let file = file.to_string_lossy().replace('\\', "/");
+    prefix
+        .split('/')
+        .chain(file.split('/'))
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Blob links into one repository at one commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permalinks {
+    /// e.g. `https://github.com/acme/app/blob/<sha>`
+    base: String,
+    forge: Forge,
+    /// Target directory within the repository, prepended to finding paths
+    prefix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Forge {
+    GitHub,
+    GitLab,
+}
+
+impl Permalinks {
+    /// `web_url` is the instance root, e.g. `https://github.com`
+    pub fn github(web_url: &str, repo: &str, sha: &str) -> Self {
+        Self {
+            base: format!("{}/{}/blob/{}", web_url.trim_end_matches('/'), repo, sha),
+            forge: Forge::GitHub,
+            prefix: String::new(),
+        }
+    }
+
+    /// `project` is the full path, e.g. `group/app`
+    pub fn gitlab(web_url: &str, project: &str, sha: &str) -> Self {
+        Self {
+            base: format!(
+                "{}/{}/-/blob/{}",
+                web_url.trim_end_matches('/'),
+                project,
+                sha
+            ),
+            forge: Forge::GitLab,
+            prefix: String::new(),
+        }
+    }
+
+    /// Root links at `prefix`, the target's directory within the repository
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_matches('/').to_string();
+        self
+    }
+
+    /// Links to `revision` on the configured forge; `repo` overrides the configured repo/project
+    ///
+    /// GitLab projects must be given by path (numeric ids don't work in web URLs);
+    /// inside GitLab CI `$CI_PROJECT_URL` is used. Jira and Linear link to
+    /// `github.repo` when it is set.
+    pub fn from_config(
+        config: &Config,
+        repo: Option<&str>,
+        revision: &SourceRevision,
+    ) -> Option<Self> {
+        let sha = &revision.sha;
+        let links = match config.tracker {
+            TrackerKind::Gitlab => {
+                let project = repo
+                    .map(String::from)
+                    .or_else(|| config.gitlab.project.clone())
+                    .filter(|p| p.parse::<u64>().is_err());
+                match project {
+                    Some(project) => {
+                        let web_url = config
+                            .gitlab
+                            .url
+                            .clone()
+                            .or_else(|| env_var("CI_SERVER_URL"))
+                            .unwrap_or_else(|| crate::gitlab::DEFAULT_URL.to_string());
+                        Some(Self::gitlab(&web_url, &project, sha))
+                    }
+                    None => {
+                        let project_url = env_var("CI_PROJECT_URL")?;
+                        Some(Self {
+                            base: format!("{}/-/blob/{}", project_url.trim_end_matches('/'), sha),
+                            forge: Forge::GitLab,
+                            prefix: String::new(),
+                        })
+                    }
+                }
+            }
+            TrackerKind::Github => {
+                let repo = repo
+                    .map(String::from)
+                    .or_else(|| config.github.repo.clone())?;
+                Some(Self::github(&github_web_url(config), &repo, sha))
+            }
+            TrackerKind::Jira | TrackerKind::Linear => {
+                let repo = config.github.repo.as_deref()?;
+                Some(Self::github(&github_web_url(config), repo, sha))
+            }
+        };
+        links.map(|links| links.with_prefix(&revision.prefix))
+    }
+
+    /// Link to `file` at the pinned commit, highlighting `line..=end_line` (line 0: whole file)
+    pub fn link(&self, file: &Path, line: u32, end_line: Option<u32>) -> String {
+        let file = repo_path(&self.prefix, file)
+            .split('/')
+            .map(encode_segment)
+            .collect::<Vec<_>>()
+            .join("/");
+        match (line, end_line) {
+            (0, _) => format!("{}/{}", self.base, file),
+            (line, Some(end)) if end > line => match self.forge {
+                Forge::GitHub => format!("{}/{}#L{}-L{}", self.base, file, line, end),
+                Forge::GitLab => format!("{}/{}#L{}-{}", self.base, file, line, end),
+            },
+            (line, _) => format!("{}/{}#L{}", self.base, file, line),
+        }
+    }
+}
+
+/// `` [`label`](permalink) `` when links are available, otherwise `` `label` ``
+pub fn markdown_location(
+    permalinks: Option<&Permalinks>,
+    label: &str,
+    file: &Path,
+    line: u32,
+    end_line: Option<u32>,
+) -> String {
+    match permalinks {
+        Some(p) => format!("[`{}`]({})", label, p.link(file, line, end_line)),
+        None => format!("`{}`", label),
+    }
+}
+
+/// Web root for GitHub; Enterprise Server serves the API under `<host>/api/v3`
+fn github_web_url(config: &Config) -> String {
+    match config
+        .github
+        .api_url
+        .as_deref()
+        .map(|u| u.trim_end_matches('/'))
+    {
+        Some(api_url) if api_url.ends_with("/api/v3") => {
+            api_url.trim_end_matches("/api/v3").to_string()
+        }
+        _ => "https://github.com".to_string(),
+    }
+}
+
+/// Percent-encode a path segment, leaving RFC 3986 unreserved characters alone
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_and_gitlab_links() {
+        let github = Permalinks::github("https://github.com/", "acme/app", "abc123");
+        assert_eq!(
+            github.link(Path::new("./src/db.py"), 10, Some(14)),
+            "https://github.com/acme/app/blob/abc123/src/db.py#L10-L14"
+        );
+        assert_eq!(
+            github.link(Path::new("src/db.py"), 10, Some(10)),
+            "https://github.com/acme/app/blob/abc123/src/db.py#L10"
+        );
+        assert_eq!(
+            github.link(Path::new("README.md"), 0, None),
+            "https://github.com/acme/app/blob/abc123/README.md"
+        );
+
+        let gitlab = Permalinks::gitlab("https://gitlab.example.com", "group/app", "abc123");
+        assert_eq!(
+            markdown_location(Some(&gitlab), "a.py:3-7", Path::new("a.py"), 3, Some(7)),
+            "[`a.py:3-7`](https://gitlab.example.com/group/app/-/blob/abc123/a.py#L3-7)"
+        );
+        assert_eq!(
+            markdown_location(None, "a.py:3", Path::new("a.py"), 3, None),
+            "`a.py:3`"
+        );
+    }
+
+    #[test]
+    fn test_from_config() {
+        let revision = SourceRevision {
+            sha: "abc".to_string(),
+            dirty: false,
+            prefix: String::new(),
+        };
+        let mut config = Config::default();
+        config.github.repo = Some("acme/app".to_string());
+        config.github.api_url = Some("https://ghe.acme.com/api/v3/".to_string());
+        assert_eq!(
+            Permalinks::from_config(&config, None, &revision)
+                .unwrap()
+                .link(Path::new("a.rs"), 1, None),
+            "https://ghe.acme.com/acme/app/blob/abc/a.rs#L1"
+        );
+
+        config.tracker = TrackerKind::Jira;
+        assert_eq!(
+            Permalinks::from_config(&config, Some("SEC"), &revision)
+                .unwrap()
+                .base,
+            "https://ghe.acme.com/acme/app/blob/abc"
+        );
+
+        config.tracker = TrackerKind::Gitlab;
+        config.gitlab.url = Some("https://gitlab.acme.com".to_string());
+        config.gitlab.project = Some("group/app".to_string());
+        assert_eq!(
+            Permalinks::from_config(&config, None, &revision)
+                .unwrap()
+                .base,
+            "https://gitlab.acme.com/group/app/-/blob/abc"
+        );
+
+        // A target in a subdirectory of the repository
+        let revision = SourceRevision {
+            prefix: "services/api/".to_string(),
+            ..revision
+        };
+        assert_eq!(
+            Permalinks::from_config(&config, None, &revision)
+                .unwrap()
+                .link(Path::new("./src/main.rs"), 3, None),
+            "https://gitlab.acme.com/group/app/-/blob/abc/services/api/src/main.rs#L3"
+        );
+    }
+
+    #[test]
+    fn test_link_encodes_path_segments() {
+        let github =
+            Permalinks::github("https://github.com", "acme/app", "abc123").with_prefix("my docs/");
+        assert_eq!(
+            github.link(Path::new("notes #1/a+b.md"), 2, None),
+            "https://github.com/acme/app/blob/abc123/my%20docs/notes%20%231/a%2Bb.md#L2"
+        );
+    }
+
+    #[test]
+    fn test_capture_subdirectory_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("services/api");
+        std::fs::create_dir_all(&sub).unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        ] {
+            git(dir.path(), args).unwrap();
+        }
+        let revision = SourceRevision::capture(&sub).unwrap();
+        assert_eq!(revision.prefix, "services/api/");
+        assert_eq!(SourceRevision::capture(dir.path()).unwrap().prefix, "");
+    }
+}
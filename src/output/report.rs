@@ -1,3 +1,4 @@
+use super::permalink::{markdown_location, Permalinks};
 use crate::error::OutputError;
 use crate::runner::{ReviewerResult, ReviewerStatus};
 use std::fs;
@@ -7,6 +8,7 @@ use std::path::Path;
 pub fn write_reviewer_report(
     report_dir: &Path,
     result: &ReviewerResult,
+    permalinks: Option<&Permalinks>,
 ) -> Result<(), OutputError> {
     // Ensure directory exists
     fs::create_dir_all(report_dir).map_err(OutputError::CreateDir)?;
@@ -45,8 +47,19 @@ pub fn write_reviewer_report(
     content.push_str(&format!("| p0 (Critical) | {} |\n", p0_count));
     content.push_str(&format!("| p1 (High) | {} |\n", p1_count));
     content.push_str(&format!("| p2 (Medium) | {} |\n", p2_count));
+    if result.suppressed > 0 {
+        content.push_str(&format!("| Suppressed | {} |\n", result.suppressed));
+    }
     content.push_str("\n---\n\n");
 
+    if !result.parse_errors.is_empty() {
+        content.push_str("## Parse Errors\n\n");
+        for error in &result.parse_errors {
+            content.push_str(&format!("- {}\n", error));
+        }
+        content.push_str("\n---\n\n");
+    }
+
     // Findings
     if result.findings.is_empty() {
         content.push_str("*No findings*\n");
@@ -55,18 +68,40 @@ pub fn write_reviewer_report(
 
         for finding in &result.findings {
             content.push_str(&format!("### [{}] {}\n\n", finding.priority, finding.title));
-            if finding.line > 0 {
-                content.push_str(&format!(
-                    "- **File:** `{}:{}`\n",
-                    finding.file.display(),
-                    finding.line
-                ));
-            } else {
-                content.push_str(&format!("- **File:** `{}`\n", finding.file.display()));
-            }
+            content.push_str(&format!(
+                "- **File:** {}\n",
+                markdown_location(
+                    permalinks,
+                    &finding.location(),
+                    &finding.file,
+                    finding.line,
+                    finding.end_line
+                )
+            ));
             if !finding.finding_type.is_empty() {
                 content.push_str(&format!("- **Type:** `{}`\n", finding.finding_type));
             }
+            if let Some(confidence) = finding.confidence {
+                content.push_str(&format!("- **Confidence:** {}\n", confidence));
+            }
+            if let Some(votes) = &finding.votes {
+                content.push_str(&format!("- **Votes:** {}\n", votes));
+            }
+            for adjustment in &finding.calibration {
+                content.push_str(&format!("- **Calibrated:** {}\n", adjustment));
+            }
+            if !finding.cwe.is_empty() || !finding.owasp.is_empty() {
+                let tags: Vec<&str> = finding
+                    .cwe
+                    .iter()
+                    .chain(finding.owasp.iter())
+                    .map(String::as_str)
+                    .collect();
+                content.push_str(&format!("- **Taxonomy:** {}\n", tags.join(", ")));
+            }
+            if let Some(status) = finding.verified.filter(|s| !s.is_located()) {
+                content.push_str(&format!("- **Location:** ⚠️ {}\n", status));
+            }
             content.push('\n');
             content.push_str(&format!("{}\n\n", finding.description));
 
@@ -75,8 +110,40 @@ pub fn write_reviewer_report(
                 content.push_str(&format!("```\n{}\n```\n\n", snippet));
             }
 
+            if !finding.related_locations.is_empty() {
+                content.push_str("**Related Locations:**\n");
+                for related in &finding.related_locations {
+                    let location = markdown_location(
+                        permalinks,
+                        &related.location(),
+                        &related.file,
+                        related.line,
+                        related.end_line,
+                    );
+                    if related.message.is_empty() {
+                        content.push_str(&format!("- {}\n", location));
+                    } else {
+                        content.push_str(&format!("- {} — {}\n", location, related.message));
+                    }
+                }
+                content.push('\n');
+            }
+
             content.push_str(&format!("**Remediation:** {}\n\n", finding.remediation));
 
+            if let Some(patch) = &finding.suggested_patch {
+                let status = match finding.patch_applies {
+                    Some(true) => format!(
+                        "applies cleanly (`polyrev apply {}`)",
+                        finding.fingerprint(&result.reviewer_id)
+                    ),
+                    Some(false) => "⚠️ does not apply to the working tree".to_string(),
+                    None => "not checked".to_string(),
+                };
+                content.push_str(&format!("**Suggested Patch:** {}\n\n", status));
+                content.push_str(&format!("```diff\n{}\n```\n\n", patch.trim_end()));
+            }
+
             if !finding.acceptance_criteria.is_empty() {
                 content.push_str("**Acceptance Criteria:**\n");
                 for criterion in &finding.acceptance_criteria {
@@ -117,5 +184,6 @@ fn format_status(status: &ReviewerStatus) -> String {
         ReviewerStatus::Skipped { reason } => format!("⏭️ Skipped ({})", reason),
         ReviewerStatus::TimedOut => "⏱️ Timed Out".to_string(),
         ReviewerStatus::Failed { error } => format!("❌ Failed ({})", error),
+        ReviewerStatus::InvalidOutput { error } => format!("⚠️ Invalid Output ({})", error),
     }
 }
@@ -1,3 +1,4 @@
+use super::permalink::SourceRevision;
 use crate::config::Priority;
 use crate::error::OutputError;
 use crate::runner::{ReviewerStatus, RunReport};
@@ -11,9 +12,18 @@ use std::path::{Path, PathBuf};
 pub struct SummaryReport {
     pub timestamp: String,
     pub target: String,
+    /// Set for `--diff-base` runs, which only review changed files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_base: Option<String>,
+    /// Commit of the target when the run started (absent outside git)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<SourceRevision>,
     pub duration_sec: f64,
     pub reviewers: Vec<ReviewerSummary>,
     pub totals: HashMap<String, usize>,
+    /// Findings dropped by suppressions (not included in totals)
+    #[serde(default)]
+    pub suppressed: usize,
     pub skipped: Vec<String>,
     pub failed: Vec<String>,
     pub exit_code: i32,
@@ -28,6 +38,11 @@ pub struct ReviewerSummary {
     pub duration_sec: f64,
     pub files_scanned: usize,
     pub findings: HashMap<String, usize>,
+    #[serde(default)]
+    pub suppressed: usize,
+    /// Output items discarded because they failed validation
+    #[serde(default)]
+    pub parse_errors: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
 }
@@ -36,11 +51,13 @@ pub fn write_summary(
     report_dir: &Path,
     run_report: &RunReport,
     target: &Path,
+    diff_base: Option<&str>,
 ) -> Result<(), OutputError> {
     // Ensure report directory exists (needed when all reviewers were skipped)
     fs::create_dir_all(report_dir).map_err(OutputError::CreateDir)?;
 
-    let summary = build_summary(run_report, report_dir.to_path_buf(), target);
+    let mut summary = build_summary(run_report, report_dir.to_path_buf(), target);
+    summary.diff_base = diff_base.map(String::from);
 
     // Write JSON
     let json_path = report_dir.join("summary.json");
@@ -62,6 +79,7 @@ fn build_summary(run_report: &RunReport, report_dir: PathBuf, target: &Path) ->
     let mut total_p0 = 0;
     let mut total_p1 = 0;
     let mut total_p2 = 0;
+    let mut total_suppressed = 0;
 
     for result in &run_report.reviewer_results {
         let p0 = result
@@ -83,6 +101,7 @@ fn build_summary(run_report: &RunReport, report_dir: PathBuf, target: &Path) ->
         total_p0 += p0;
         total_p1 += p1;
         total_p2 += p2;
+        total_suppressed += result.suppressed;
 
         let (status_str, reason) = match &result.status {
             ReviewerStatus::Completed => ("completed".to_string(), None),
@@ -98,6 +117,10 @@ fn build_summary(run_report: &RunReport, report_dir: PathBuf, target: &Path) ->
                 failed.push(result.reviewer_id.clone());
                 ("failed".to_string(), Some(error.clone()))
             }
+            ReviewerStatus::InvalidOutput { error } => {
+                failed.push(result.reviewer_id.clone());
+                ("invalid_output".to_string(), Some(error.clone()))
+            }
         };
 
         let mut findings = HashMap::new();
@@ -112,6 +135,8 @@ fn build_summary(run_report: &RunReport, report_dir: PathBuf, target: &Path) ->
             duration_sec: result.duration.as_secs_f64(),
             files_scanned: result.files_scanned,
             findings,
+            suppressed: result.suppressed,
+            parse_errors: result.parse_errors.len(),
             reason,
         });
     }
@@ -126,9 +151,12 @@ fn build_summary(run_report: &RunReport, report_dir: PathBuf, target: &Path) ->
     SummaryReport {
         timestamp: Utc::now().to_rfc3339(),
         target: target.display().to_string(),
+        diff_base: None,
+        commit: run_report.revision.clone(),
         duration_sec: run_report.total_duration.as_secs_f64(),
         reviewers,
         totals,
+        suppressed: total_suppressed,
         skipped,
         failed,
         exit_code,
@@ -146,6 +174,20 @@ fn build_summary_markdown(summary: &SummaryReport) -> String {
         "**Report Dir:** {}\n",
         summary.report_dir.display()
     ));
+    if let Some(commit) = &summary.commit {
+        md.push_str(&format!(
+            "**Commit:** {}{}\n",
+            commit.sha,
+            if commit.dirty {
+                " (with uncommitted changes)"
+            } else {
+                ""
+            }
+        ));
+    }
+    if let Some(diff_base) = &summary.diff_base {
+        md.push_str(&format!("**Diff Base:** {}\n", diff_base));
+    }
     md.push_str(&format!("**Duration:** {:.1}s\n\n", summary.duration_sec));
 
     // Totals
@@ -164,6 +206,9 @@ fn build_summary_markdown(summary: &SummaryReport) -> String {
         "| p2 (Medium) | {} |\n\n",
         summary.totals.get("p2").unwrap_or(&0)
     ));
+    if summary.suppressed > 0 {
+        md.push_str(&format!("**Suppressed:** {}\n\n", summary.suppressed));
+    }
 
     // Reviewers table
     md.push_str("## Reviewers\n\n");
@@ -176,15 +221,19 @@ fn build_summary_markdown(summary: &SummaryReport) -> String {
             "skipped" => "⏭️",
             "timed_out" => "⏱️",
             "failed" => "❌",
+            "invalid_output" => "⚠️",
             _ => "❓",
         };
 
-        let findings_str = format!(
+        let mut findings_str = format!(
             "{} p0, {} p1, {} p2",
             reviewer.findings.get("p0").unwrap_or(&0),
             reviewer.findings.get("p1").unwrap_or(&0),
             reviewer.findings.get("p2").unwrap_or(&0),
         );
+        if reviewer.suppressed > 0 {
+            findings_str.push_str(&format!(" ({} suppressed)", reviewer.suppressed));
+        }
 
         let status_str = if let Some(reason) = &reviewer.reason {
             format!("{} {} ({})", status_icon, reviewer.status, reason)
@@ -0,0 +1,137 @@
+use super::Finding;
+use crate::runner::is_within_target;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How far above the reported line to look for an enclosing definition
+const MAX_ANCHOR_DISTANCE: usize = 200;
+
+/// Matches lines that open a function, method, type or block definition
+/// in the languages reviewers commonly target (Rust, Python, Go, JS/TS, Swift, Kotlin, Java, Ruby)
+fn anchor_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|public|private|protected|internal|static|final|abstract|override|open|async|unsafe|const|extern|@\w+)\s+)*(?:fn|def|func|fun|function|class|struct|enum|trait|impl|interface|protocol|extension|module|object)\b",
+        )
+        .expect("anchor regex is valid")
+    })
+}
+
+/// Find the nearest enclosing definition line at or above `line` (1-based)
+///
+/// Returns the definition line with whitespace collapsed, or None when the
+/// line is unknown/out of range or no definition is found nearby.
+pub fn find_anchor(source: &str, line: u32) -> Option<String> {
+    if line == 0 {
+        return None;
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let idx = (line as usize).checked_sub(1)?;
+    if idx >= lines.len() {
+        return None;
+    }
+
+    let re = anchor_regex();
+    let lowest = idx.saturating_sub(MAX_ANCHOR_DISTANCE);
+    (lowest..=idx)
+        .rev()
+        .map(|i| lines[i])
+        .find(|l| re.is_match(l))
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Populate `anchor` on findings by reading their files under `target`
+///
+/// Files are read at most once; findings whose file can't be read, or lies
+/// outside the target, keep `anchor: None`.
+pub fn attach_anchors(findings: &mut [Finding], target: &Path) {
+    let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+
+    for finding in findings.iter_mut() {
+        if finding.anchor.is_some() {
+            continue;
+        }
+
+        let source = sources.entry(finding.file.clone()).or_insert_with(|| {
+            is_within_target(&finding.file)
+                .then(|| std::fs::read_to_string(target.join(&finding.file)).ok())
+                .flatten()
+        });
+
+        if let Some(source) = source {
+            finding.anchor = find_anchor(source, finding.line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"import os
+
+def load_user(user_id):
+    conn = db()
+    query = f"SELECT * FROM users WHERE id = {user_id}"
+    return conn.execute(query)
+
+class Cache:
+    pass
+"#;
+
+    #[test]
+    fn test_find_anchor_enclosing_function() {
+        assert_eq!(
+            find_anchor(SOURCE, 5).as_deref(),
+            Some("def load_user(user_id):")
+        );
+    }
+
+    #[test]
+    fn test_find_anchor_stable_across_line_shift() {
+        let shifted = format!("# new header comment\n{}", SOURCE);
+        assert_eq!(find_anchor(SOURCE, 5), find_anchor(&shifted, 6));
+    }
+
+    #[test]
+    fn test_find_anchor_none_outside_definitions() {
+        assert_eq!(find_anchor(SOURCE, 1), None);
+        assert_eq!(find_anchor(SOURCE, 0), None);
+        assert_eq!(find_anchor(SOURCE, 999), None);
+    }
+
+    #[test]
+    fn test_find_anchor_rust_modifiers() {
+        let src = "impl Foo {\n    pub async fn bar(&self) {\n        todo!()\n    }\n}\n";
+        assert_eq!(
+            find_anchor(src, 3).as_deref(),
+            Some("pub async fn bar(&self) {")
+        );
+    }
+
+    #[test]
+    fn test_attach_anchors_stays_inside_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(dir.path().join("outside.py"), SOURCE).unwrap();
+        std::fs::write(target.join("inside.py"), SOURCE).unwrap();
+
+        let finding = |file: &str| Finding {
+            file: PathBuf::from(file),
+            line: 5,
+            ..Default::default()
+        };
+        let mut findings = vec![finding("inside.py"), finding("../outside.py")];
+        attach_anchors(&mut findings, &target);
+        assert_eq!(
+            findings[0].anchor.as_deref(),
+            Some("def load_user(user_id):")
+        );
+        assert_eq!(findings[1].anchor, None);
+    }
+}
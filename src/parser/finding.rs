@@ -1,9 +1,14 @@
 use crate::config::Priority;
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Current fingerprint scheme version (see `Finding::fingerprint`)
+pub const FINGERPRINT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct Finding {
     pub id: String,
 
@@ -20,6 +25,22 @@ pub struct Finding {
     #[serde(default)]
     pub line: u32, // 0 means no specific line
 
+    /// Last line of the flagged range (inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+
+    /// 1-based column where the flagged code starts
+    #[serde(
+        default,
+        alias = "start_column",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub column: Option<u32>,
+
+    /// 1-based column where the flagged code ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<u32>,
+
     #[serde(default)]
     pub snippet: Option<String>,
 
@@ -28,18 +49,312 @@ pub struct Finding {
     #[serde(default, alias = "recommendation")]
     pub remediation: String,
 
+    /// Unified diff (relative to the target root) that fixes the issue
+    #[serde(default, alias = "patch", skip_serializing_if = "Option::is_none")]
+    pub suggested_patch: Option<String>,
+
     #[serde(default)]
     pub acceptance_criteria: Vec<String>,
 
     #[serde(default)]
     pub references: Vec<String>,
 
+    /// Other code locations involved (e.g. taint source and sink)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<RelatedLocation>,
+
+    /// How sure the reviewer is that this is a real issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+
+    /// CWE identifiers, normalized to "CWE-<n>"
+    #[serde(
+        default,
+        deserialize_with = "deserialize_cwe",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub cwe: Vec<String>,
+
+    /// OWASP Top 10 categories (e.g. "A03:2021-Injection")
+    #[serde(
+        default,
+        deserialize_with = "deserialize_one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub owasp: Vec<String>,
+
     /// Model that produced this finding (set after parsing)
     #[serde(default)]
+    #[schemars(skip)]
     pub model: Option<String>,
+
+    /// Agreement between models in a consensus review (set after merging)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub votes: Option<Votes>,
+
+    /// Priority changes made by calibration rules and caps, in order (set after parsing)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(skip)]
+    pub calibration: Vec<PriorityAdjustment>,
+
+    /// Enclosing function/definition line (set after parsing, used for fingerprinting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub anchor: Option<String>,
+
+    /// Result of checking file/line/snippet against the source (set after parsing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub verified: Option<LocationStatus>,
+
+    /// Whether `suggested_patch` applies cleanly to the working tree (set after parsing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub patch_applies: Option<bool>,
+
+    /// Second opinion from the `verify` stage (set after verification)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub verification: Option<Verification>,
+
+    /// Fingerprints of the original findings merged into this one (set when loading `reduced.json`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(skip)]
+    pub merged_from: Vec<String>,
+
+    /// Reviewers of the merged findings (set when loading `reduced.json`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(skip)]
+    pub reviewers: Vec<String>,
+}
+
+/// A secondary code location attached to a finding
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct RelatedLocation {
+    pub file: PathBuf,
+
+    #[serde(default)]
+    pub line: u32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+
+    /// What happens at this location (e.g. "user input enters here")
+    #[serde(default, alias = "label", alias = "description")]
+    pub message: String,
+}
+
+impl RelatedLocation {
+    /// Render as `file:line` / `file:line-end` / `file`
+    pub fn location(&self) -> String {
+        format_location(&self.file, self.line, self.end_line)
+    }
+}
+
+/// Reviewer confidence in a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Confidence::High => write!(f, "high"),
+            Confidence::Medium => write!(f, "medium"),
+            Confidence::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// Accept a single tag or a list, as strings or numbers
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    Many(Vec<serde_json::Value>),
+    One(serde_json::Value),
+}
+
+fn tag_values(v: OneOrMany) -> Vec<String> {
+    let values = match v {
+        OneOrMany::Many(values) => values,
+        OneOrMany::One(value) => vec![value],
+    };
+    values
+        .into_iter()
+        .filter_map(|v| match v {
+            serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn deserialize_one_or_many<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    Ok(Option::<OneOrMany>::deserialize(d)?
+        .map(tag_values)
+        .unwrap_or_default())
+}
+
+fn deserialize_cwe<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    Ok(deserialize_one_or_many(d)?
+        .into_iter()
+        .map(|tag| {
+            let upper = tag.to_uppercase();
+            let id = upper
+                .trim_start_matches("CWE")
+                .trim_start_matches(['-', ':', ' ']);
+            if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+                format!("CWE-{}", id)
+            } else {
+                tag
+            }
+        })
+        .collect())
+}
+
+/// Render a location as `file:line` / `file:line-end` / `file`
+fn format_location(file: &std::path::Path, line: u32, end_line: Option<u32>) -> String {
+    match (line, end_line) {
+        (0, _) => file.display().to_string(),
+        (start, Some(end)) if end > start => format!("{}:{}-{}", file.display(), start, end),
+        (start, _) => format!("{}:{}", file.display(), start),
+    }
+}
+
+/// Outcome of verifying a finding's location against the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationStatus {
+    /// Snippet found at the reported line
+    Verified,
+    /// Snippet found elsewhere in the file; `line` was moved to it
+    LineCorrected,
+    /// File exists but there is no snippet to check; `line` clamped to the file
+    FileOnly,
+    /// File exists but the snippet could not be located
+    SnippetNotFound,
+    /// Reported file does not exist under the target
+    FileNotFound,
+}
+
+impl LocationStatus {
+    /// Whether the finding points at something that actually exists
+    pub fn is_located(&self) -> bool {
+        !matches!(
+            self,
+            LocationStatus::SnippetNotFound | LocationStatus::FileNotFound
+        )
+    }
+}
+
+impl std::fmt::Display for LocationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocationStatus::Verified => write!(f, "verified"),
+            LocationStatus::LineCorrected => write!(f, "line corrected"),
+            LocationStatus::FileOnly => write!(f, "file only"),
+            LocationStatus::SnippetNotFound => write!(f, "snippet not found"),
+            LocationStatus::FileNotFound => write!(f, "file not found"),
+        }
+    }
+}
+
+/// Which of a consensus reviewer's models reported a finding
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Votes {
+    /// Number of models that reported it
+    pub count: usize,
+    /// Number of models that ran
+    pub total: usize,
+    pub models: Vec<String>,
+}
+
+impl std::fmt::Display for Votes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} ({})",
+            self.count,
+            self.total,
+            self.models.join(", ")
+        )
+    }
+}
+
+/// A priority change made by calibration, kept on the finding for auditing
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PriorityAdjustment {
+    pub from: Priority,
+    pub to: Priority,
+    /// The rule or cap that made the change
+    pub reason: String,
+}
+
+impl std::fmt::Display for PriorityAdjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} → {} ({})", self.from, self.to, self.reason)
+    }
+}
+
+/// An independent model's verdict on a finding
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Verification {
+    pub verdict: Verdict,
+
+    #[serde(default)]
+    pub rationale: String,
+
+    /// Priority the reviewer assigned, when the verifier downgraded it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_priority: Option<Priority>,
+
+    /// Provider and model that verified the finding (e.g. "codex_cli/o3")
+    #[serde(default)]
+    pub verifier: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The issue is real at the reported priority
+    #[serde(alias = "confirm")]
+    Confirmed,
+    /// The issue is real but less severe; `priority` was lowered
+    #[serde(alias = "downgrade")]
+    Downgraded,
+    /// The issue does not exist or is not a problem
+    #[serde(alias = "refute")]
+    Refuted,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Confirmed => write!(f, "confirmed"),
+            Verdict::Downgraded => write!(f, "downgraded"),
+            Verdict::Refuted => write!(f, "refuted"),
+        }
+    }
 }
 
 impl Finding {
+    /// Whether the `verify` stage refuted this finding
+    pub fn is_refuted(&self) -> bool {
+        self.verification
+            .as_ref()
+            .is_some_and(|v| v.verdict == Verdict::Refuted)
+    }
+
+    /// Render the primary location as `file:line` / `file:line-end` / `file`
+    pub fn location(&self) -> String {
+        format_location(&self.file, self.line, self.end_line)
+    }
+
     /// Normalize snippet for stable fingerprinting
     /// Removes variable whitespace that might differ between runs
     fn normalize_snippet(&self) -> String {
@@ -51,9 +366,59 @@ impl Finding {
             .join(" ")
     }
 
-    /// Generate deterministic fingerprint for deduplication
-    /// Uses: reviewer_id | relative_file | line | finding_type | normalized_snippet
+    /// Normalize snippet into a token stream for location-tolerant fingerprinting
+    /// Keeps identifiers and punctuation, drops formatting, and masks numeric literals
+    fn snippet_tokens(&self) -> String {
+        static RE: OnceLock<regex::Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| {
+            regex::Regex::new(r"[A-Za-z_][A-Za-z0-9_]*|[0-9][0-9A-Za-z_.]*|\S")
+                .expect("token regex is valid")
+        });
+        re.find_iter(self.snippet.as_deref().unwrap_or(""))
+            .map(|m| {
+                let tok = m.as_str();
+                if tok.starts_with(|c: char| c.is_ascii_digit()) {
+                    "#"
+                } else {
+                    tok
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Generate deterministic fingerprint for deduplication (v2)
+    /// Uses: version | reviewer_id | relative_file | finding_type | anchor | snippet tokens
+    ///
+    /// The raw line number is only mixed in when there is neither an anchor nor a
+    /// snippet, so inserting lines above a finding or reformatting it keeps the key stable.
     pub fn fingerprint(&self, reviewer_id: &str) -> String {
+        let tokens = self.snippet_tokens();
+        let anchor = self.anchor.as_deref().unwrap_or("");
+        let line = if anchor.is_empty() && tokens.is_empty() {
+            self.line.to_string()
+        } else {
+            String::new()
+        };
+        let input = format!(
+            "v{}|{}|{}|{}|{}|{}|{}",
+            FINGERPRINT_VERSION,
+            reviewer_id,
+            self.file.display(),
+            self.finding_type,
+            anchor,
+            tokens,
+            line,
+        );
+        let hash = Sha256::digest(input.as_bytes());
+        format!("{:x}", hash)[..12].to_string()
+    }
+
+    /// Generate the original (v1) line-sensitive fingerprint
+    /// Uses: reviewer_id | relative_file | line | finding_type | normalized_snippet
+    ///
+    /// Kept as a secondary key so issues filed before v2 still dedupe.
+    pub fn legacy_fingerprint(&self, reviewer_id: &str) -> String {
         let normalized_snippet = self.normalize_snippet();
         let input = format!(
             "{}|{}|{}|{}|{}",
@@ -87,6 +452,7 @@ mod tests {
             acceptance_criteria: vec![],
             references: vec![],
             model: None,
+            ..Default::default()
         };
 
         let fp1 = finding.fingerprint("security-python");
@@ -111,6 +477,7 @@ mod tests {
             acceptance_criteria: vec![],
             references: vec![],
             model: None,
+            ..Default::default()
         };
 
         let fp1 = finding.fingerprint("security-python");
@@ -134,8 +501,131 @@ mod tests {
             acceptance_criteria: vec![],
             references: vec![],
             model: None,
+            ..Default::default()
         };
 
         assert_eq!(finding.normalize_snippet(), "foo bar baz");
     }
+
+    fn sample_finding() -> Finding {
+        Finding {
+            id: "TEST-001".to_string(),
+            finding_type: "sql-injection".to_string(),
+            title: "SQL Injection".to_string(),
+            priority: Priority::P0,
+            file: PathBuf::from("src/db.py"),
+            line: 42,
+            snippet: Some("query = f\"SELECT * FROM users WHERE id = {id}\"".to_string()),
+            description: "Bad".to_string(),
+            remediation: "Fix it".to_string(),
+            acceptance_criteria: vec![],
+            references: vec![],
+            model: None,
+            anchor: Some("def load_user(id):".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_survives_line_shift() {
+        let finding = sample_finding();
+        let mut shifted = finding.clone();
+        shifted.line = 43;
+
+        assert_eq!(
+            finding.fingerprint("security-python"),
+            shifted.fingerprint("security-python")
+        );
+        assert_ne!(
+            finding.legacy_fingerprint("security-python"),
+            shifted.legacy_fingerprint("security-python")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_survives_snippet_reformat() {
+        let finding = sample_finding();
+        let mut reformatted = finding.clone();
+        reformatted.snippet =
+            Some("query  =  f\"SELECT * FROM users WHERE id = {id}\"\n".to_string());
+
+        assert_eq!(
+            finding.fingerprint("security-python"),
+            reformatted.fingerprint("security-python")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_keeps_identifiers() {
+        let finding = sample_finding();
+        let mut renamed = finding.clone();
+        renamed.snippet = Some("sql = f\"SELECT * FROM users WHERE id = {id}\"".to_string());
+
+        assert_ne!(
+            finding.fingerprint("security-python"),
+            renamed.fingerprint("security-python")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_falls_back_to_line_without_context() {
+        let mut finding = sample_finding();
+        finding.snippet = None;
+        finding.anchor = None;
+        let mut other = finding.clone();
+        other.line = 99;
+
+        assert_ne!(
+            finding.fingerprint("security-python"),
+            other.fingerprint("security-python")
+        );
+    }
+
+    #[test]
+    fn test_legacy_fingerprint_unchanged() {
+        let mut finding = sample_finding();
+        finding.anchor = None;
+        let input = format!(
+            "security-python|src/db.py|42|sql-injection|{}",
+            finding.normalize_snippet()
+        );
+        let expected = format!("{:x}", Sha256::digest(input.as_bytes()))[..12].to_string();
+
+        assert_eq!(finding.legacy_fingerprint("security-python"), expected);
+    }
+
+    #[test]
+    fn test_extended_fields_backward_compatible() {
+        let old: Finding = serde_json::from_str(
+            r#"{"id": "T1", "title": "t", "file": "a.py", "line": 3, "description": "d"}"#,
+        )
+        .unwrap();
+        assert_eq!(old.end_line, None);
+        assert!(old.related_locations.is_empty());
+        assert!(old.cwe.is_empty());
+
+        let json = serde_json::to_value(&old).unwrap();
+        assert!(json.get("end_line").is_none());
+        assert!(json.get("cwe").is_none());
+    }
+
+    #[test]
+    fn test_extended_fields_parse() {
+        let f: Finding = serde_json::from_str(
+            r#"{
+                "id": "T1", "title": "t", "file": "a.py", "line": 3, "end_line": 7,
+                "start_column": 5, "description": "d", "confidence": "high",
+                "cwe": [89, "cwe-79", "CWE-20"], "owasp": "A03:2021-Injection",
+                "related_locations": [{"file": "b.py", "line": 10, "label": "source"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(f.location(), "a.py:3-7");
+        assert_eq!(f.column, Some(5));
+        assert_eq!(f.confidence, Some(Confidence::High));
+        assert_eq!(f.cwe, vec!["CWE-89", "CWE-79", "CWE-20"]);
+        assert_eq!(f.owasp, vec!["A03:2021-Injection"]);
+        assert_eq!(f.related_locations[0].message, "source");
+        assert_eq!(f.related_locations[0].location(), "b.py:10");
+    }
 }
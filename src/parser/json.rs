@@ -1,8 +1,13 @@
-use super::Finding;
+use super::{Finding, ParseOutcome};
 use serde::Deserialize;
+use serde_json::Value;
 
 /// Try to parse findings from JSON output
-pub fn try_parse_json(raw: &str) -> Option<Vec<Finding>> {
+///
+/// Returns None if no JSON object with a `findings` array is present.
+/// Items are validated individually so one malformed finding doesn't
+/// discard the rest; per-item errors are collected in the outcome.
+pub fn try_parse_json(raw: &str) -> Option<ParseOutcome> {
     // Claude wraps result in {"result": "...", ...} JSON
     #[derive(Deserialize)]
     struct ClaudeOutput {
@@ -20,22 +25,56 @@ pub fn try_parse_json(raw: &str) -> Option<Vec<Finding>> {
     parse_findings_json(raw)
 }
 
-fn parse_findings_json(s: &str) -> Option<Vec<Finding>> {
+fn parse_findings_json(s: &str) -> Option<ParseOutcome> {
     // Try to find JSON in the string (might be wrapped in markdown code blocks)
     let json_str = extract_json(s)?;
+    let value: Value = serde_json::from_str(&json_str).ok()?;
+
+    let items = match value.get("findings") {
+        Some(Value::Array(items)) => items,
+        Some(other) => {
+            tracing::debug!("`findings` is not an array: {}", other);
+            return Some(ParseOutcome {
+                findings: Vec::new(),
+                errors: vec!["`findings` must be an array".to_string()],
+                recognized: false,
+            });
+        }
+        None => return None,
+    };
+
+    let (findings, errors) = validate_items(items);
+    Some(ParseOutcome {
+        findings,
+        errors,
+        recognized: true,
+    })
+}
 
-    #[derive(Deserialize)]
-    struct FindingsWrapper {
-        findings: Vec<Finding>,
-    }
+/// Validate each item of the `findings` array against the Finding schema
+fn validate_items(items: &[Value]) -> (Vec<Finding>, Vec<String>) {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, item) in items.iter().enumerate() {
+        let label = match item.get("id").and_then(|v| v.as_str()) {
+            Some(id) => format!("findings[{}] ({})", idx, id),
+            None => format!("findings[{}]", idx),
+        };
 
-    match serde_json::from_str::<FindingsWrapper>(&json_str) {
-        Ok(wrapper) => Some(wrapper.findings),
-        Err(e) => {
-            tracing::debug!("Failed to parse findings JSON: {}", e);
-            None
+        match serde_json::from_value::<Finding>(item.clone()) {
+            Ok(f) if f.title.trim().is_empty() => {
+                errors.push(format!("{}: `title` must not be empty", label))
+            }
+            Ok(f) if f.file.as_os_str().is_empty() => {
+                errors.push(format!("{}: `file` must not be empty", label))
+            }
+            Ok(f) => findings.push(f),
+            Err(e) => errors.push(format!("{}: {}", label, e)),
         }
     }
+
+    (findings, errors)
 }
 
 /// Extract JSON object from a string that might contain markdown code blocks
@@ -92,7 +131,7 @@ mod tests {
     #[test]
     fn test_parse_direct_json() {
         let json = r#"{"findings": [{"id": "T1", "title": "Test", "priority": "p0", "file": "a.py", "line": 1, "description": "d", "remediation": "r"}]}"#;
-        let findings = try_parse_json(json).unwrap();
+        let findings = try_parse_json(json).unwrap().findings;
         assert_eq!(findings.len(), 1);
         assert_eq!(findings[0].id, "T1");
     }
@@ -100,7 +139,7 @@ mod tests {
     #[test]
     fn test_parse_claude_wrapped() {
         let json = r#"{"result": "{\"findings\": [{\"id\": \"T1\", \"title\": \"Test\", \"priority\": \"p1\", \"file\": \"a.py\", \"line\": 1, \"description\": \"d\", \"remediation\": \"r\"}]}", "session_id": "abc"}"#;
-        let findings = try_parse_json(json).unwrap();
+        let findings = try_parse_json(json).unwrap().findings;
         assert_eq!(findings.len(), 1);
     }
 
@@ -113,7 +152,34 @@ Here are the findings:
 {"findings": [{"id": "T1", "title": "Test", "priority": "p2", "file": "a.py", "line": 1, "description": "d", "remediation": "r"}]}
 ```
 "#;
-        let findings = try_parse_json(md).unwrap();
+        let findings = try_parse_json(md).unwrap().findings;
         assert_eq!(findings.len(), 1);
     }
+
+    #[test]
+    fn test_parse_collects_item_errors() {
+        let json = r#"{"findings": [
+            {"id": "T1", "title": "Ok", "priority": "p0", "file": "a.py", "line": 1, "description": "d"},
+            {"id": "T2", "title": "Bad priority", "priority": "urgent", "file": "a.py", "description": "d"},
+            {"id": "T3", "file": "a.py", "description": "missing title"},
+            {"id": "T4", "title": " ", "file": "a.py", "description": "blank title"}
+        ]}"#;
+        let outcome = try_parse_json(json).unwrap();
+        assert!(outcome.recognized);
+        assert_eq!(outcome.findings.len(), 1);
+        assert_eq!(outcome.errors.len(), 3);
+        assert!(outcome.errors[0].starts_with("findings[1] (T2)"));
+    }
+
+    #[test]
+    fn test_parse_empty_findings_is_clean() {
+        let outcome = try_parse_json(r#"{"findings": []}"#).unwrap();
+        assert!(outcome.is_clean());
+        assert!(outcome.findings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_without_findings_key() {
+        assert!(try_parse_json(r#"{"summary": "looks fine"}"#).is_none());
+    }
 }
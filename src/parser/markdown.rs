@@ -50,6 +50,7 @@ pub fn try_parse_markdown_table(
             acceptance_criteria: Vec::new(),
             references: Vec::new(),
             model: None,
+            ..Default::default()
         });
     }
 
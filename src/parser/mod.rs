@@ -1,27 +1,148 @@
+mod anchor;
 mod finding;
 mod json;
 mod markdown;
 
-pub use finding::Finding;
+pub use anchor::attach_anchors;
+pub use finding::{
+    Confidence, Finding, LocationStatus, PriorityAdjustment, RelatedLocation, Verdict,
+    Verification, Votes,
+};
 
 use crate::config::Priority;
+use schemars::JsonSchema;
+
+/// Result of parsing a reviewer's raw output
+#[derive(Debug, Default)]
+pub struct ParseOutcome {
+    /// Findings that passed validation
+    pub findings: Vec<Finding>,
+    /// Per-item validation errors (e.g. "findings[2] (SEC-003): missing field `file`")
+    pub errors: Vec<String>,
+    /// Whether a findings payload (JSON `findings` array or markdown table) was found
+    pub recognized: bool,
+}
+
+impl ParseOutcome {
+    /// Output was recognized and every item validated
+    pub fn is_clean(&self) -> bool {
+        self.recognized && self.errors.is_empty()
+    }
+
+    /// Whether this outcome is an improvement over `other` (used to accept repairs)
+    pub fn is_better_than(&self, other: &ParseOutcome) -> bool {
+        match (self.recognized, other.recognized) {
+            (true, false) => true,
+            (false, _) => false,
+            (true, true) => self.errors.len() < other.errors.len(),
+        }
+    }
+}
 
 /// Parse findings from provider output
 /// Tries JSON first, then falls back to markdown table parsing
-pub fn parse_findings(raw: &str, reviewer_id: &str, default_priority: Priority) -> Vec<Finding> {
+pub fn parse_findings(raw: &str, reviewer_id: &str, default_priority: Priority) -> ParseOutcome {
     // Try JSON first
-    if let Some(findings) = json::try_parse_json(raw) {
-        return findings;
+    let json_outcome = json::try_parse_json(raw);
+    if let Some(outcome) = json_outcome.as_ref().filter(|o| o.recognized) {
+        if !outcome.errors.is_empty() {
+            tracing::warn!(
+                "Reviewer {} output had {} invalid findings",
+                reviewer_id,
+                outcome.errors.len()
+            );
+        }
+        return json_outcome.unwrap_or_default();
     }
 
     // Fallback: markdown table
     if let Some(findings) = markdown::try_parse_markdown_table(raw, reviewer_id, default_priority) {
-        return findings;
+        return ParseOutcome {
+            findings,
+            errors: Vec::new(),
+            recognized: true,
+        };
     }
 
     tracing::warn!(
         "Could not parse findings from output for reviewer {}",
         reviewer_id
     );
-    Vec::new()
+    json_outcome.unwrap_or_else(|| ParseOutcome {
+        findings: Vec::new(),
+        errors: vec!["no JSON object with a `findings` array found in output".to_string()],
+        recognized: false,
+    })
+}
+
+/// Build a follow-up prompt asking the model to fix its malformed output
+pub fn build_repair_prompt(outcome: &ParseOutcome) -> String {
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct FindingsOutput {
+        findings: Vec<Finding>,
+    }
+
+    let schema =
+        serde_json::to_string_pretty(&schemars::schema_for!(FindingsOutput)).unwrap_or_default();
+    let errors = outcome
+        .errors
+        .iter()
+        .map(|e| format!("- {}", e))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "Your previous response could not be parsed as the required findings JSON.\n\n\
+        Problems:\n{}\n\n\
+        Re-emit ALL of your findings (including the ones that were valid) as a single JSON object \
+        matching this schema. Output ONLY the JSON object, no prose or code fences. \
+        If there are no findings, output {{\"findings\": []}}.\n\n```json\n{}\n```",
+        errors, schema
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unrecognized_output() {
+        let outcome = parse_findings("I reviewed the code and it looks fine.", "r", Priority::P1);
+        assert!(!outcome.recognized);
+        assert!(!outcome.is_clean());
+        assert_eq!(outcome.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_repair_acceptance() {
+        let broken = ParseOutcome::default();
+        let partial = ParseOutcome {
+            findings: vec![],
+            errors: vec!["findings[0]: missing field `file`".to_string()],
+            recognized: true,
+        };
+        let clean = ParseOutcome {
+            recognized: true,
+            ..Default::default()
+        };
+
+        assert!(partial.is_better_than(&broken));
+        assert!(clean.is_better_than(&partial));
+        assert!(!broken.is_better_than(&partial));
+        assert!(!partial.is_better_than(&partial));
+    }
+
+    #[test]
+    fn test_repair_prompt_lists_errors_and_schema() {
+        let outcome = ParseOutcome {
+            findings: vec![],
+            errors: vec!["findings[0]: missing field `file`".to_string()],
+            recognized: true,
+        };
+        let prompt = build_repair_prompt(&outcome);
+        assert!(prompt.contains("- findings[0]: missing field `file`"));
+        assert!(prompt.contains("\"remediation\""));
+        assert!(!prompt.contains("\"anchor\""));
+    }
 }
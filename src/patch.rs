@@ -0,0 +1,180 @@
+//! Suggested-fix patches
+//!
+//! Reviewers may attach a unified diff to a finding (`suggested_patch`).
+//! Patches are checked with `git apply --check` after parsing and can later be
+//! applied onto a fresh branch with `polyrev apply`.
+
+use crate::error::PatchError;
+use crate::parser::Finding;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+/// Set `patch_applies` on every finding that carries a suggested patch
+pub fn check_patches(findings: &mut [Finding], target: &Path) {
+    for finding in findings.iter_mut() {
+        let Some(patch) = finding.suggested_patch.as_deref() else {
+            continue;
+        };
+
+        let applies = match patch_applies(target, patch) {
+            Ok(applies) => applies,
+            Err(e) => {
+                debug!("Could not check patch for {}: {}", finding.id, e);
+                false
+            }
+        };
+        debug!("Patch for {} applies: {}", finding.id, applies);
+        finding.patch_applies = Some(applies);
+    }
+}
+
+/// Whether `patch` applies cleanly to the files under `target`
+pub fn patch_applies(target: &Path, patch: &str) -> Result<bool, PatchError> {
+    let patch = normalize_patch(patch);
+    let output = git_apply(target, &patch, &["--check"])?;
+    Ok(output.status.success())
+}
+
+/// Apply `patch` to the working tree and index under `target`
+pub fn apply_patch(target: &Path, patch: &str) -> Result<(), PatchError> {
+    let patch = normalize_patch(patch);
+    let output = git_apply(target, &patch, &["--index"])?;
+    if !output.status.success() {
+        return Err(PatchError::Git {
+            command: "apply".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Run a git command in `target`, returning trimmed stdout
+pub fn git(target: &Path, args: &[&str]) -> Result<String, PatchError> {
+    let output = Command::new("git")
+        .current_dir(target)
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(PatchError::Git {
+            command: args.first().copied().unwrap_or_default().to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn git_apply(
+    target: &Path,
+    patch: &str,
+    extra: &[&str],
+) -> Result<std::process::Output, PatchError> {
+    // Model-written hunks often have wrong line counts; let git recount them
+    let strip = format!("-p{}", strip_level(patch));
+    let mut child = Command::new("git")
+        .current_dir(target)
+        .args(["apply", "--recount", "--whitespace=nowarn", &strip])
+        .args(extra)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+
+    Ok(child.wait_with_output()?)
+}
+
+/// Strip markdown fences and make sure the patch ends with a newline
+fn normalize_patch(patch: &str) -> String {
+    let mut body: String = patch
+        .trim()
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    body.push('\n');
+    body
+}
+
+/// `1` for git-style `a/` `b/` paths, `0` for paths relative to the target
+fn strip_level(patch: &str) -> u8 {
+    let old_path = patch
+        .lines()
+        .find_map(|l| l.strip_prefix("--- "))
+        .map(str::trim);
+
+    match old_path {
+        Some(p) if p.starts_with("a/") || p == "/dev/null" => 1,
+        Some(_) => 0,
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+    use std::path::PathBuf;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("db.py"),
+            "def load(id):\n    return run(id)\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn finding(patch: &str) -> Finding {
+        Finding {
+            id: "T-1".to_string(),
+            title: "Test".to_string(),
+            priority: Priority::P1,
+            file: PathBuf::from("db.py"),
+            line: 2,
+            suggested_patch: Some(patch.to_string()),
+            ..Default::default()
+        }
+    }
+
+    const GOOD: &str = "```diff\n--- a/db.py\n+++ b/db.py\n@@ -1,2 +1,2 @@\n def load(id):\n-    return run(id)\n+    return run(int(id))\n```";
+
+    #[test]
+    fn test_check_patches() {
+        let dir = fixture();
+        let stale = "--- db.py\n+++ db.py\n@@ -1,2 +1,2 @@\n def load(id):\n-    return exec(id)\n+    return run(int(id))\n";
+
+        let mut findings = vec![finding(GOOD), finding(stale), Finding::default()];
+        check_patches(&mut findings, dir.path());
+
+        assert_eq!(findings[0].patch_applies, Some(true));
+        assert_eq!(findings[1].patch_applies, Some(false));
+        assert_eq!(findings[2].patch_applies, None);
+    }
+
+    #[test]
+    fn test_strip_level() {
+        assert_eq!(strip_level("--- a/src/x.rs\n+++ b/src/x.rs\n"), 1);
+        assert_eq!(strip_level("--- /dev/null\n+++ b/new.rs\n"), 1);
+        assert_eq!(strip_level("--- src/x.rs\n+++ src/x.rs\n"), 0);
+    }
+
+    #[test]
+    fn test_apply_patch_updates_file() {
+        let dir = fixture();
+        git(dir.path(), &["init", "-q"]).unwrap();
+        git(dir.path(), &["add", "db.py"]).unwrap();
+
+        apply_patch(dir.path(), GOOD).unwrap();
+        let content = std::fs::read_to_string(dir.path().join("db.py")).unwrap();
+        assert!(content.contains("run(int(id))"));
+    }
+}
@@ -11,10 +11,12 @@ pub mod orchestrator;
 pub mod parser;
 pub mod reducer;
 pub mod types;
+pub mod validate;
 
 pub use orchestrator::{select_perspectives, PlanOptions, PlanOrchestrator};
-pub use reducer::{reduce_plan, revise_plan, write_fragments, write_plan};
+pub use reducer::{reduce_plan, revise_plan, validate_and_repair, write_fragments, write_plan};
 pub use types::{
     PerspectiveResult, PerspectiveStatus, Perspective, Risk, Severity, UnifiedPlan,
     UnifiedQuestion, UnifiedTask,
 };
+pub use validate::{validate_plan, PlanValidation};
@@ -143,6 +143,7 @@ const DEFAULT_SKEPTIC_PROMPT: &str = include_str!("../../prompts/plan/skeptic.md
 const DEFAULT_GENERALIST_PROMPT: &str = include_str!("../../prompts/plan/generalist.md");
 const DEFAULT_SELECT_PROMPT: &str = include_str!("../../prompts/plan/select.md");
 pub const DEFAULT_REVISE_PROMPT: &str = include_str!("../../prompts/plan/revise.md");
+pub const DEFAULT_REPAIR_PROMPT: &str = include_str!("../../prompts/plan/repair.md");
 
 /// Get embedded prompt for a perspective ID
 fn get_embedded_prompt(perspective_id: &str) -> Option<&'static str> {
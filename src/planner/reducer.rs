@@ -5,6 +5,7 @@
 use crate::config::Config;
 use crate::error::PlannerError;
 use crate::provider::{create_runner_for_provider, ProviderConfig};
+use std::collections::HashSet;
 use std::path::Path;
 use std::time::Duration;
 use tracing::{debug, info, warn};
@@ -13,6 +14,7 @@ use super::types::{
     AcceptanceCriterion, PlanFragment, PlanningResult, PerspectiveStatus, TaskFiles, UnifiedPlan,
     UnifiedTask,
 };
+use super::validate::{validate_plan, PlanValidation};
 
 /// Result of the reduction step
 #[derive(Debug)]
@@ -399,6 +401,86 @@ pub async fn revise_plan(
     }))
 }
 
+const MAX_REPAIR_ATTEMPTS: usize = 2;
+
+/// Validate a plan, asking the reducer to repair structural problems
+///
+/// Returns the plan with the fewest problems seen and its validation; the
+/// caller decides what to do if problems remain after the repair attempts.
+/// Repairs that drop any of the input's tasks are rejected.
+pub async fn validate_and_repair(
+    config: &Config,
+    plan: UnifiedPlan,
+) -> (UnifiedPlan, PlanValidation) {
+    let task_ids: Vec<String> = plan.tasks.iter().map(|t| t.id.clone()).collect();
+    let mut best = (validate_plan(&plan), plan);
+    let timeout = Duration::from_secs(config.timeout_sec);
+
+    for attempt in 1..=MAX_REPAIR_ATTEMPTS {
+        if best.0.is_valid() {
+            break;
+        }
+        warn!(
+            "Plan has {} structural problems, repair attempt {} of {}",
+            best.0.problems.len(),
+            attempt,
+            MAX_REPAIR_ATTEMPTS
+        );
+
+        let prompt = repair_prompt(&best.1, &best.0);
+        let repaired = match invoke_reducer(config, &prompt, timeout).await {
+            Ok(output) => parse_unified_plan(&output),
+            Err(e) => Err(e),
+        };
+        match repaired {
+            Ok(repaired) => {
+                let dropped = dropped_tasks(&task_ids, &repaired);
+                if !dropped.is_empty() {
+                    warn!(
+                        "Rejected plan repair (attempt {}): it drops tasks {}",
+                        attempt,
+                        dropped.join(", ")
+                    );
+                    continue;
+                }
+                let validation = validate_plan(&repaired);
+                if validation.problems.len() < best.0.problems.len() {
+                    best = (validation, repaired);
+                }
+            }
+            Err(e) => warn!("Plan repair failed (attempt {}): {}", attempt, e),
+        }
+    }
+
+    (best.1, best.0)
+}
+
+/// Task ids in `original` that `repaired` no longer has, each once
+fn dropped_tasks(original: &[String], repaired: &UnifiedPlan) -> Vec<String> {
+    let kept: HashSet<&str> = repaired.tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut dropped: Vec<String> = Vec::new();
+    for id in original {
+        if !kept.contains(id.as_str()) && !dropped.contains(id) {
+            dropped.push(id.clone());
+        }
+    }
+    dropped
+}
+
+fn repair_prompt(plan: &UnifiedPlan, validation: &PlanValidation) -> String {
+    let problems: String = validation
+        .problems
+        .iter()
+        .map(|p| format!("- {}\n", p))
+        .collect();
+    format!(
+        "{}\n\n## Problems\n\n{}\n## Plan\n\n```json\n{}\n```",
+        crate::planner::orchestrator::DEFAULT_REPAIR_PROMPT,
+        problems,
+        serde_json::to_string_pretty(plan).unwrap_or_default()
+    )
+}
+
 /// Parse YAML revision output and convert to UnifiedPlan
 fn parse_revision_yaml(raw: &str, original: &UnifiedPlan) -> Result<UnifiedPlan, PlannerError> {
     // Extract YAML from output (may be wrapped in markdown code block or Claude JSON)
@@ -579,4 +661,19 @@ mod tests {
         let plan = try_parse_plan(json).unwrap();
         assert_eq!(plan.questions.len(), 1);
     }
+
+    #[test]
+    fn test_dropped_tasks() {
+        let original: Vec<String> = ["a", "b", "a", "c"].iter().map(|s| s.to_string()).collect();
+        let repaired =
+            try_parse_plan(r#"{"tasks": [{"id": "a", "title": "A"}, {"id": "d", "title": "D"}]}"#)
+                .unwrap();
+        assert_eq!(dropped_tasks(&original, &repaired), vec!["b", "c"]);
+        // Merging duplicate ids into one task drops nothing
+        let repaired = try_parse_plan(
+            r#"{"tasks": [{"id": "a", "title": "A"}, {"id": "b", "title": "B"}, {"id": "c", "title": "C"}]}"#,
+        )
+        .unwrap();
+        assert!(dropped_tasks(&original, &repaired).is_empty());
+    }
 }
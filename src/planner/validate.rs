@@ -0,0 +1,345 @@
+//! Structural validation of unified plans
+//!
+//! Reducer output isn't trusted as-is: task ids must be unique, `depends_on`
+//! must name existing tasks without repeats or cycles, every task needs
+//! acceptance criteria, and question `blocks` must point at real tasks.
+//! Validation also orders the task DAG into topological layers and finds the
+//! critical path (the longest dependency chain).
+
+use super::types::UnifiedPlan;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A structural problem in a plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlanProblem {
+    /// Several tasks share an id
+    DuplicateId { id: String, count: usize },
+    /// `depends_on` names a task that doesn't exist
+    DanglingDependency { task: String, dependency: String },
+    /// `depends_on` lists the same task more than once
+    DuplicateDependency { task: String, dependency: String },
+    /// Tasks that (transitively) depend on themselves, in dependency order
+    Cycle { tasks: Vec<String> },
+    /// A task with no way to verify completion
+    MissingAcceptanceCriteria { task: String },
+    /// A question's `blocks` names a task that doesn't exist
+    DanglingQuestionBlock { question: String, task: String },
+}
+
+impl fmt::Display for PlanProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanProblem::DuplicateId { id, count } => {
+                write!(f, "task id '{}' is used by {} tasks", id, count)
+            }
+            PlanProblem::DanglingDependency { task, dependency } => {
+                write!(f, "{} depends on unknown task '{}'", task, dependency)
+            }
+            PlanProblem::DuplicateDependency { task, dependency } => {
+                write!(
+                    f,
+                    "{} lists dependency '{}' more than once",
+                    task, dependency
+                )
+            }
+            PlanProblem::Cycle { tasks } => {
+                write!(f, "dependency cycle: {} → {}", tasks.join(" → "), tasks[0])
+            }
+            PlanProblem::MissingAcceptanceCriteria { task } => {
+                write!(f, "{} has no acceptance criteria", task)
+            }
+            PlanProblem::DanglingQuestionBlock { question, task } => {
+                write!(f, "question '{}' blocks unknown task '{}'", question, task)
+            }
+        }
+    }
+}
+
+/// Outcome of validating a plan
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlanValidation {
+    pub problems: Vec<PlanProblem>,
+    /// Task ids by topological layer; each layer only depends on earlier ones.
+    /// Tasks on or behind a cycle are left out.
+    pub layers: Vec<Vec<String>>,
+    /// Longest dependency chain, first task to last
+    pub critical_path: Vec<String>,
+}
+
+impl PlanValidation {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check a plan's structure and compute its layers and critical path
+pub fn validate_plan(plan: &UnifiedPlan) -> PlanValidation {
+    let mut problems = Vec::new();
+
+    // Ids in plan order; the first task with a duplicated id wins
+    let mut ids: Vec<&str> = Vec::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for task in &plan.tasks {
+        let count = counts.entry(task.id.as_str()).or_default();
+        if *count == 0 {
+            ids.push(&task.id);
+        }
+        *count += 1;
+    }
+    for id in &ids {
+        if counts[id] > 1 {
+            problems.push(PlanProblem::DuplicateId {
+                id: id.to_string(),
+                count: counts[id],
+            });
+        }
+    }
+
+    let mut deps: HashMap<&str, Vec<&str>> = HashMap::new();
+    for task in &plan.tasks {
+        if deps.contains_key(task.id.as_str()) {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        let mut task_deps = Vec::new();
+        for dep in &task.depends_on {
+            if !counts.contains_key(dep.as_str()) {
+                problems.push(PlanProblem::DanglingDependency {
+                    task: task.id.clone(),
+                    dependency: dep.clone(),
+                });
+            } else if !seen.insert(dep.as_str()) {
+                problems.push(PlanProblem::DuplicateDependency {
+                    task: task.id.clone(),
+                    dependency: dep.clone(),
+                });
+            } else {
+                task_deps.push(dep.as_str());
+            }
+        }
+        deps.insert(&task.id, task_deps);
+    }
+
+    for tasks in find_cycles(&ids, &deps) {
+        problems.push(PlanProblem::Cycle { tasks });
+    }
+
+    for task in &plan.tasks {
+        if task.acceptance_criteria.is_empty() {
+            problems.push(PlanProblem::MissingAcceptanceCriteria {
+                task: task.id.clone(),
+            });
+        }
+    }
+
+    for question in &plan.questions {
+        for task in &question.blocks {
+            if !counts.contains_key(task.as_str()) {
+                problems.push(PlanProblem::DanglingQuestionBlock {
+                    question: question.question.clone(),
+                    task: task.clone(),
+                });
+            }
+        }
+    }
+
+    let layers = topological_layers(&ids, &deps);
+    let critical_path = critical_path(&layers, &deps);
+
+    PlanValidation {
+        problems,
+        layers: layers
+            .into_iter()
+            .map(|layer| layer.into_iter().map(String::from).collect())
+            .collect(),
+        critical_path,
+    }
+}
+
+/// Group tasks into layers (Kahn's algorithm), keeping plan order within a layer
+fn topological_layers<'a>(ids: &[&'a str], deps: &HashMap<&str, Vec<&str>>) -> Vec<Vec<&'a str>> {
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut layers = Vec::new();
+    loop {
+        let layer: Vec<&str> = ids
+            .iter()
+            .copied()
+            .filter(|id| !placed.contains(id))
+            .filter(|id| deps[id].iter().all(|d| placed.contains(d)))
+            .collect();
+        if layer.is_empty() {
+            return layers;
+        }
+        placed.extend(layer.iter().copied());
+        layers.push(layer);
+    }
+}
+
+/// Longest chain through the layered tasks; ties go to the earlier task
+fn critical_path(layers: &[Vec<&str>], deps: &HashMap<&str, Vec<&str>>) -> Vec<String> {
+    // task -> (chain length ending here, previous task on that chain)
+    let mut best: HashMap<&str, (usize, Option<&str>)> = HashMap::new();
+    let mut end: Option<(&str, usize)> = None;
+    for &id in layers.iter().flatten() {
+        let (mut length, mut previous) = (0, None);
+        for &dep in &deps[id] {
+            if best[dep].0 > length {
+                (length, previous) = (best[dep].0, Some(dep));
+            }
+        }
+        best.insert(id, (length + 1, previous));
+        if end.is_none_or(|(_, len)| length + 1 > len) {
+            end = Some((id, length + 1));
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut current = end.map(|(id, _)| id);
+    while let Some(id) = current {
+        path.push(id.to_string());
+        current = best[id].1;
+    }
+    path.reverse();
+    path
+}
+
+/// Each distinct cycle once, as found by depth-first search in plan order
+fn find_cycles(ids: &[&str], deps: &HashMap<&str, Vec<&str>>) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        id: &'a str,
+        deps: &HashMap<&str, Vec<&'a str>>,
+        stack: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+        seen: &mut HashSet<Vec<&'a str>>,
+    ) {
+        stack.push(id);
+        for &dep in &deps[id] {
+            if let Some(start) = stack.iter().position(|&s| s == dep) {
+                let cycle = &stack[start..];
+                let mut key = cycle.to_vec();
+                key.sort_unstable();
+                if seen.insert(key) {
+                    // Report in dependency order: each task depends on the next
+                    cycles.push(cycle.iter().map(|s| s.to_string()).collect());
+                }
+            } else if !done.contains(dep) {
+                visit(dep, deps, stack, done, cycles, seen);
+            }
+        }
+        stack.pop();
+        done.insert(id);
+    }
+
+    let mut done = HashSet::new();
+    let mut cycles = Vec::new();
+    let mut seen = HashSet::new();
+    for &id in ids {
+        if !done.contains(id) {
+            visit(id, deps, &mut Vec::new(), &mut done, &mut cycles, &mut seen);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan(value: serde_json::Value) -> UnifiedPlan {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn task(id: &str, depends_on: &[&str]) -> serde_json::Value {
+        json!({
+            "id": id,
+            "title": id,
+            "depends_on": depends_on,
+            "acceptance_criteria": [{"criterion": "works"}]
+        })
+    }
+
+    #[test]
+    fn test_layers_and_critical_path() {
+        let validation = validate_plan(&plan(json!({
+            "tasks": [
+                task("a", &[]),
+                task("b", &["a"]),
+                task("c", &[]),
+                task("d", &["b", "c"]),
+                task("e", &["c"])
+            ]
+        })));
+        assert!(validation.is_valid(), "{:?}", validation.problems);
+        assert_eq!(
+            validation.layers,
+            vec![vec!["a", "c"], vec!["b", "e"], vec!["d"]]
+        );
+        assert_eq!(validation.critical_path, vec!["a", "b", "d"]);
+    }
+
+    #[test]
+    fn test_structural_problems() {
+        let validation = validate_plan(&plan(json!({
+            "tasks": [
+                task("a", &["missing"]),
+                task("a", &[]),
+                task("b", &["a", "a"]),
+                {"id": "c", "title": "c", "depends_on": ["b"]}
+            ],
+            "questions": [{"question": "Which DB?", "blocks": ["b", "z"]}]
+        })));
+        assert_eq!(
+            validation.problems,
+            vec![
+                PlanProblem::DuplicateId {
+                    id: "a".to_string(),
+                    count: 2
+                },
+                PlanProblem::DanglingDependency {
+                    task: "a".to_string(),
+                    dependency: "missing".to_string()
+                },
+                PlanProblem::DuplicateDependency {
+                    task: "b".to_string(),
+                    dependency: "a".to_string()
+                },
+                PlanProblem::MissingAcceptanceCriteria {
+                    task: "c".to_string()
+                },
+                PlanProblem::DanglingQuestionBlock {
+                    question: "Which DB?".to_string(),
+                    task: "z".to_string()
+                },
+            ]
+        );
+        // Dangling and repeated dependencies don't hold up ordering
+        assert_eq!(validation.layers, vec![vec!["a"], vec!["b"], vec!["c"]]);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let validation = validate_plan(&plan(json!({
+            "tasks": [
+                task("root", &[]),
+                task("a", &["b"]),
+                task("b", &["c", "root"]),
+                task("c", &["a"]),
+                task("d", &["d"]),
+                task("after", &["a"])
+            ]
+        })));
+        let cycles: Vec<String> = validation.problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            cycles,
+            vec!["dependency cycle: a → b → c → a", "dependency cycle: d → d"]
+        );
+        // Tasks on or behind a cycle can't be layered
+        assert_eq!(validation.layers, vec![vec!["root"]]);
+        assert_eq!(validation.critical_path, vec!["root"]);
+    }
+}
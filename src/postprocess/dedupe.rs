@@ -0,0 +1,294 @@
+//! Deterministic duplicate merging ahead of the AI reducer
+//!
+//! Two findings are duplicates when they point at the same file, their line
+//! ranges overlap (within `line_window` lines of slack), and their titles or
+//! snippets share enough words. Duplicates are merged transitively; the most
+//! urgent member is kept and the rest are recorded in `merged_from`.
+
+use super::SourcedFinding;
+use crate::config::LocalDedupeConfig;
+use crate::parser::{Confidence, Finding};
+use std::collections::{BTreeMap, HashSet};
+
+/// A finding standing in for itself and the duplicates merged into it
+#[derive(Debug, Clone)]
+pub struct LocalGroup {
+    pub finding: SourcedFinding,
+    /// Fingerprints of every merged finding, the kept one included
+    pub merged_from: Vec<String>,
+}
+
+impl LocalGroup {
+    pub fn single(finding: SourcedFinding) -> Self {
+        Self {
+            merged_from: vec![finding.fingerprint.clone()],
+            finding,
+        }
+    }
+}
+
+/// Merge duplicate findings, keeping the input order of the first member of each group
+pub fn dedupe(findings: Vec<SourcedFinding>, config: &LocalDedupeConfig) -> Vec<LocalGroup> {
+    let mut parent: Vec<usize> = (0..findings.len()).collect();
+
+    let mut by_file: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, sf) in findings.iter().enumerate() {
+        by_file
+            .entry(normalize_path(&sf.finding))
+            .or_default()
+            .push(i);
+    }
+    let titles: Vec<HashSet<String>> = findings
+        .iter()
+        .map(|sf| tokens(&sf.finding.title))
+        .collect();
+    let snippets: Vec<Option<HashSet<String>>> = findings
+        .iter()
+        .map(|sf| sf.finding.snippet.as_deref().map(tokens))
+        .collect();
+
+    for members in by_file.values() {
+        for (n, &a) in members.iter().enumerate() {
+            for &b in &members[n + 1..] {
+                if !lines_overlap(
+                    &findings[a].finding,
+                    &findings[b].finding,
+                    config.line_window,
+                ) {
+                    continue;
+                }
+                let similar = similarity(&titles[a], &titles[b]) >= config.similarity
+                    || matches!(
+                        (&snippets[a], &snippets[b]),
+                        (Some(x), Some(y)) if similarity(x, y) >= config.similarity
+                    );
+                if similar {
+                    union(&mut parent, a, b);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for i in 0..findings.len() {
+        let root = find(&mut parent, i);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, members)) => members.push(i),
+            None => groups.push((root, vec![i])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, members)| merge(&findings, &members))
+        .collect()
+}
+
+/// Keep the most urgent, most confident member and fold the others into it
+fn merge(findings: &[SourcedFinding], members: &[usize]) -> LocalGroup {
+    let keep = *members
+        .iter()
+        .min_by_key(|&&i| {
+            let f = &findings[i].finding;
+            (f.priority.rank(), confidence_rank(f.confidence), i)
+        })
+        .expect("groups are never empty");
+
+    let mut group = LocalGroup::single(findings[keep].clone());
+    let merged = &mut group.finding.finding;
+    for &i in members.iter().filter(|&&i| i != keep) {
+        let other = &findings[i];
+        group.merged_from.push(other.fingerprint.clone());
+
+        let f = &other.finding;
+        if f.line > 0 {
+            let end = merged
+                .end_line
+                .unwrap_or(merged.line)
+                .max(f.end_line.unwrap_or(f.line));
+            merged.line = merged.line.min(f.line);
+            if end > merged.line {
+                merged.end_line = Some(end);
+            }
+        }
+        extend_unique(&mut merged.acceptance_criteria, &f.acceptance_criteria);
+        extend_unique(&mut merged.references, &f.references);
+        extend_unique(&mut merged.related_locations, &f.related_locations);
+        extend_unique(&mut merged.cwe, &f.cwe);
+        extend_unique(&mut merged.owasp, &f.owasp);
+    }
+    group
+}
+
+fn lines_overlap(a: &Finding, b: &Finding, window: u32) -> bool {
+    // Whole-file findings only match each other
+    if a.line == 0 || b.line == 0 {
+        return a.line == b.line;
+    }
+    let a_end = a.end_line.unwrap_or(a.line).max(a.line);
+    let b_end = b.end_line.unwrap_or(b.line).max(b.line);
+    a.line <= b_end.saturating_add(window) && b.line <= a_end.saturating_add(window)
+}
+
+/// Lowercased alphanumeric words
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of two token sets
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn normalize_path(finding: &Finding) -> String {
+    let file = finding.file.to_string_lossy();
+    file.trim_start_matches("./").to_string()
+}
+
+fn confidence_rank(confidence: Option<Confidence>) -> u8 {
+    match confidence {
+        Some(Confidence::High) => 0,
+        Some(Confidence::Medium) | None => 1,
+        Some(Confidence::Low) => 2,
+    }
+}
+
+fn extend_unique<T: Clone + PartialEq>(into: &mut Vec<T>, from: &[T]) {
+    for item in from {
+        if !into.contains(item) {
+            into.push(item.clone());
+        }
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    if a != b {
+        parent[a.max(b)] = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+
+    fn sourced(
+        reviewer: &str,
+        file: &str,
+        line: u32,
+        title: &str,
+        priority: Priority,
+    ) -> SourcedFinding {
+        let finding = Finding {
+            id: format!("{}-{}", reviewer, line),
+            title: title.to_string(),
+            priority,
+            file: file.into(),
+            line,
+            description: "d".to_string(),
+            references: vec![format!("ref-{}", reviewer)],
+            ..Default::default()
+        };
+        SourcedFinding {
+            reviewer_id: reviewer.to_string(),
+            fingerprint: finding.fingerprint(reviewer),
+            finding,
+        }
+    }
+
+    #[test]
+    fn test_merges_near_duplicates_across_reviewers() {
+        let findings = vec![
+            sourced(
+                "security",
+                "src/db.py",
+                42,
+                "SQL injection in user lookup",
+                Priority::P1,
+            ),
+            sourced(
+                "perf",
+                "src/db.py",
+                10,
+                "N+1 query in user lookup",
+                Priority::P2,
+            ),
+            sourced(
+                "python",
+                "./src/db.py",
+                44,
+                "SQL injection in the user lookup query",
+                Priority::P0,
+            ),
+            sourced(
+                "python",
+                "src/db.py",
+                90,
+                "SQL injection in user lookup",
+                Priority::P1,
+            ),
+            sourced(
+                "api",
+                "src/api.py",
+                42,
+                "SQL injection in user lookup",
+                Priority::P1,
+            ),
+        ];
+        let fps: Vec<String> = findings.iter().map(|f| f.fingerprint.clone()).collect();
+        let groups = dedupe(findings, &LocalDedupeConfig::default());
+
+        assert_eq!(groups.len(), 4);
+        let merged = &groups[0];
+        assert_eq!(merged.finding.reviewer_id, "python");
+        assert_eq!(merged.finding.finding.priority, Priority::P0);
+        assert_eq!(merged.merged_from, vec![fps[2].clone(), fps[0].clone()]);
+        assert_eq!(merged.finding.finding.line, 42);
+        assert_eq!(merged.finding.finding.end_line, Some(44));
+        assert_eq!(
+            merged.finding.finding.references,
+            vec!["ref-python", "ref-security"]
+        );
+        assert_eq!(groups[1].merged_from, vec![fps[1].clone()]);
+    }
+
+    #[test]
+    fn test_snippet_similarity_and_whole_file_findings() {
+        let mut a = sourced("a", "x.rs", 5, "Unchecked unwrap", Priority::P1);
+        let mut b = sourced("b", "x.rs", 6, "Possible panic", Priority::P1);
+        a.finding.snippet = Some("let v = map.get(k).unwrap();".to_string());
+        b.finding.snippet = Some("let v = map.get(k).unwrap()".to_string());
+        let whole = sourced("c", "x.rs", 0, "Unchecked unwrap", Priority::P1);
+
+        let groups = dedupe(vec![a, b, whole], &LocalDedupeConfig::default());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].merged_from.len(), 2);
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(
+            similarity(&tokens("SQL injection"), &tokens("sql-injection")),
+            1.0
+        );
+        assert_eq!(similarity(&tokens(""), &tokens("")), 0.0);
+        assert!(similarity(&tokens("a b c d"), &tokens("a b")) == 0.5);
+    }
+}
@@ -0,0 +1,430 @@
+//! Hierarchical reduction for finding sets too large for one reducer call
+//!
+//! Findings are partitioned (by file, directory or type) into chunks of at
+//! most `max_findings_per_call`. The chunks are reduced in parallel, then
+//! their partial results are reduced again, round after round, until a
+//! single call covers everything. A chunk whose call fails passes its input
+//! on unreduced, so one failure doesn't lose the other partitions. Output
+//! that can't be parsed is saved as `reducer-output*.txt` in the report
+//! directory.
+
+use super::dedupe::LocalGroup;
+use super::incremental::{next_id, Previous};
+use super::{
+    expand_keys, invoke_cli, parse_reduced_output, reduce_local, FindingCluster, ReducedFinding,
+    ReducedOutput,
+};
+use crate::config::{Config, PartitionBy};
+use crate::error::PostprocessError;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// One reducer input: a finding or partial result, and the fingerprints it stands for
+#[derive(Debug, Clone)]
+struct Item {
+    /// Fingerprint the reducer refers to this item by
+    key: String,
+    file: PathBuf,
+    finding_type: String,
+    /// What the reducer sees
+    input: serde_json::Value,
+    /// What's kept if no call manages to reduce this item
+    fallback: ReducedFinding,
+    /// Original fingerprints this item stands for
+    covers: Vec<String>,
+}
+
+impl Item {
+    fn from_group(group: &LocalGroup) -> Result<Self, PostprocessError> {
+        Ok(Self {
+            key: group.finding.fingerprint.clone(),
+            file: group.finding.finding.file.clone(),
+            finding_type: group.finding.finding.finding_type.clone(),
+            input: serde_json::to_value(&group.finding)?,
+            fallback: reduce_local(group.clone()),
+            covers: group.merged_from.clone(),
+        })
+    }
+
+    fn from_partial(key: String, finding: ReducedFinding) -> Result<Self, PostprocessError> {
+        let mut input = serde_json::to_value(&finding)?;
+        if let Some(obj) = input.as_object_mut() {
+            obj.remove("merged_from");
+            obj.insert("fingerprint".to_string(), key.clone().into());
+        }
+        Ok(Self {
+            key,
+            file: finding.file.clone(),
+            finding_type: finding.finding_type.clone(),
+            input,
+            covers: finding.merged_from.clone(),
+            fallback: finding,
+        })
+    }
+}
+
+/// Reduce the groups, splitting the work into partitions and rounds when needed
+///
+/// Returns the reduction and the failed calls it recovered from. With
+/// `previous`, reducer findings reusing an earlier id are attachments: they
+/// are set aside as soon as they are returned and not reduced again.
+pub async fn reduce(
+    config: &Config,
+    prompt: &str,
+    context: &str,
+    groups: &[LocalGroup],
+    previous: Option<&Previous>,
+    report_dir: &Path,
+) -> Result<(ReducedOutput, Vec<String>), PostprocessError> {
+    let existing: HashSet<&str> = previous
+        .map(|p| p.findings.iter().map(|f| f.id.as_str()).collect())
+        .unwrap_or_default();
+    let mut items = groups
+        .iter()
+        .map(Item::from_group)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut attached: Vec<ReducedFinding> = Vec::new();
+    let mut clusters: Vec<FindingCluster> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    let mut round = 0;
+
+    let (mut findings, summary) = loop {
+        if items.is_empty() {
+            break (Vec::new(), None);
+        }
+        let count = items.len();
+        let chunks = partition(
+            items,
+            config.postprocess.partition_by,
+            config.postprocess.max_findings_per_call,
+        );
+
+        if chunks.len() == 1 {
+            let chunk = chunks.into_iter().next().unwrap_or_default();
+            let raw_output = report_dir.join(if round == 0 {
+                "reducer-output.txt"
+            } else {
+                "reducer-output-final.txt"
+            });
+            match reduce_chunk(config, prompt, context, &chunk, &raw_output).await {
+                Ok(out) => {
+                    merge_clusters(&mut clusters, out.clusters);
+                    let findings = set_aside(out.findings, &existing, &mut attached);
+                    break (findings, out.summary);
+                }
+                // With nothing reduced yet, the caller falls back to the local aggregate
+                Err(e) if round == 0 => return Err(e),
+                Err(e) => {
+                    warn!("Final reduction failed, keeping partial results: {}", e);
+                    failures.push(format!("final merge: {}", e));
+                    break (chunk.into_iter().map(|i| i.fallback).collect(), None);
+                }
+            }
+        }
+
+        round += 1;
+        info!(
+            "Reduction round {}: {} findings in {} partitions",
+            round,
+            count,
+            chunks.len()
+        );
+        let outcomes: Vec<_> = stream::iter(chunks.into_iter().enumerate().map(|(n, chunk)| {
+            let raw_output = report_dir.join(format!("reducer-output-{}-{}.txt", round, n + 1));
+            async move {
+                let result = reduce_chunk(config, prompt, context, &chunk, &raw_output).await;
+                (chunk, result)
+            }
+        }))
+        .buffered(config.concurrency.max(1))
+        .collect()
+        .await;
+
+        let failed = outcomes.iter().filter(|(_, r)| r.is_err()).count();
+        if round == 1 && failed == outcomes.len() {
+            let first = outcomes.into_iter().find_map(|(_, r)| r.err());
+            return Err(first.unwrap_or_else(|| {
+                PostprocessError::CliExecution("every partition failed".to_string())
+            }));
+        }
+
+        let mut next = Vec::new();
+        for (n, (chunk, result)) in outcomes.into_iter().enumerate() {
+            match result {
+                Ok(out) => {
+                    merge_clusters(&mut clusters, out.clusters);
+                    for (i, finding) in set_aside(out.findings, &existing, &mut attached)
+                        .into_iter()
+                        .enumerate()
+                    {
+                        let key = format!("partial-{}-{}-{}", round, n + 1, i + 1);
+                        next.push(Item::from_partial(key, finding)?);
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Partition {} of round {} failed, passing its {} findings on: {}",
+                        n + 1,
+                        round,
+                        chunk.len(),
+                        e
+                    );
+                    failures.push(format!("round {} partition {}: {}", round, n + 1, e));
+                    next.extend(chunk);
+                }
+            }
+        }
+
+        if next.len() >= count {
+            warn!(
+                "Reduction round {} didn't shrink {} findings, keeping partial results",
+                round, count
+            );
+            break (next.into_iter().map(|i| i.fallback).collect(), None);
+        }
+        items = next;
+    };
+
+    unique_ids(&mut findings, &existing);
+    findings.extend(attached);
+    let output = ReducedOutput {
+        findings,
+        clusters,
+        summary,
+    };
+    Ok((output, failures))
+}
+
+/// Reduce one chunk, mapping its `merged_from` and cluster keys back to original fingerprints
+///
+/// Output of a call that fails or can't be parsed is written to `raw_output`.
+async fn reduce_chunk(
+    config: &Config,
+    prompt: &str,
+    context: &str,
+    chunk: &[Item],
+    raw_output: &Path,
+) -> Result<ReducedOutput, PostprocessError> {
+    let inputs: Vec<&serde_json::Value> = chunk.iter().map(|i| &i.input).collect();
+    let mut full_prompt = format!(
+        "{}\n\n## Input Findings\n\n```json\n{}\n```",
+        prompt,
+        serde_json::to_string_pretty(&inputs)?
+    );
+    if !context.is_empty() {
+        full_prompt.push_str("\n\n");
+        full_prompt.push_str(context);
+    }
+
+    let timeout = Duration::from_secs(config.postprocess.timeout_sec);
+    let output = match invoke_cli(config, &full_prompt, timeout).await {
+        Ok(output) => output,
+        Err(failure) => {
+            warn!(
+                "{}; saving raw output to {}",
+                failure.error,
+                raw_output.display()
+            );
+            save_raw_output(raw_output, &failure.output)?;
+            return Err(PostprocessError::CliFailed {
+                error: failure.error.to_string(),
+                raw_output: raw_output.to_path_buf(),
+            });
+        }
+    };
+    let mut reduced = match parse_reduced_output(&output) {
+        Ok(reduced) => reduced,
+        Err(e) => {
+            warn!("{}; saving raw output to {}", e, raw_output.display());
+            save_raw_output(raw_output, &output)?;
+            return Err(PostprocessError::Unparsable {
+                raw_output: raw_output.to_path_buf(),
+            });
+        }
+    };
+
+    let by_key: HashMap<&str, &[String]> = chunk
+        .iter()
+        .map(|i| (i.key.as_str(), i.covers.as_slice()))
+        .collect();
+    for finding in &mut reduced.findings {
+        finding.merged_from = expand_keys(&finding.merged_from, &by_key);
+    }
+    for cluster in &mut reduced.clusters {
+        cluster.fingerprints = expand_keys(&cluster.fingerprints, &by_key);
+    }
+    Ok(reduced)
+}
+
+fn save_raw_output(path: &Path, output: &str) -> Result<(), PostprocessError> {
+    std::fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+    std::fs::write(path, output)?;
+    Ok(())
+}
+
+/// Split items into chunks of at most `max`, keeping each partition together where it fits
+///
+/// Partitions are packed in key order, so neighbouring files or directories
+/// share a call; a partition larger than `max` is split on its own.
+fn partition(items: Vec<Item>, by: PartitionBy, max: usize) -> Vec<Vec<Item>> {
+    if max == 0 || items.len() <= max {
+        return vec![items];
+    }
+
+    let mut partitions: BTreeMap<String, Vec<Item>> = BTreeMap::new();
+    for item in items {
+        partitions
+            .entry(partition_key(&item, by))
+            .or_default()
+            .push(item);
+    }
+
+    let mut chunks: Vec<Vec<Item>> = Vec::new();
+    for members in partitions.into_values() {
+        let mut members = members.into_iter().peekable();
+        while members.peek().is_some() {
+            let piece: Vec<Item> = members.by_ref().take(max).collect();
+            match chunks.last_mut() {
+                Some(last) if last.len() + piece.len() <= max => last.extend(piece),
+                _ => chunks.push(piece),
+            }
+        }
+    }
+    chunks
+}
+
+fn partition_key(item: &Item, by: PartitionBy) -> String {
+    let file = item.file.to_string_lossy();
+    let file = file.trim_start_matches("./");
+    match by {
+        PartitionBy::File => file.to_string(),
+        PartitionBy::Directory => Path::new(file)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        PartitionBy::Type => item.finding_type.to_ascii_lowercase(),
+    }
+}
+
+/// Move attachments to earlier findings into `attached`, returning the rest
+fn set_aside(
+    findings: Vec<ReducedFinding>,
+    existing: &HashSet<&str>,
+    attached: &mut Vec<ReducedFinding>,
+) -> Vec<ReducedFinding> {
+    let (earlier, rest): (Vec<_>, Vec<_>) = findings
+        .into_iter()
+        .partition(|f| existing.contains(f.id.as_str()));
+    attached.extend(earlier);
+    rest
+}
+
+/// Merge clusters by name
+fn merge_clusters(clusters: &mut Vec<FindingCluster>, new: Vec<FindingCluster>) {
+    for cluster in new {
+        match clusters.iter_mut().find(|c| c.name == cluster.name) {
+            Some(existing) => {
+                for fp in cluster.fingerprints {
+                    if !existing.fingerprints.contains(&fp) {
+                        existing.fingerprints.push(fp);
+                    }
+                }
+            }
+            None => clusters.push(cluster),
+        }
+    }
+}
+
+/// Renumber findings whose id is empty, repeated, or taken by an earlier reduction
+///
+/// Partial results from different partitions each number their findings
+/// from `REDUCED-001`, so ids only clash when they were never merged.
+fn unique_ids(findings: &mut [ReducedFinding], existing: &HashSet<&str>) {
+    let mut used: HashSet<String> = existing.iter().map(|id| id.to_string()).collect();
+    used.extend(findings.iter().map(|f| f.id.clone()));
+    let mut seen: HashSet<String> = existing.iter().map(|id| id.to_string()).collect();
+    for finding in findings {
+        if finding.id.is_empty() || seen.contains(&finding.id) {
+            let borrowed: HashSet<&str> = used.iter().map(String::as_str).collect();
+            finding.id = next_id(&borrowed);
+            used.insert(finding.id.clone());
+        }
+        seen.insert(finding.id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(file: &str, finding_type: &str, id: &str) -> Item {
+        let finding: ReducedFinding = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "type": finding_type,
+            "file": file,
+            "merged_from": [id],
+        }))
+        .unwrap();
+        Item::from_partial(id.to_string(), finding).unwrap()
+    }
+
+    fn keys(chunks: &[Vec<Item>]) -> Vec<Vec<&str>> {
+        chunks
+            .iter()
+            .map(|c| c.iter().map(|i| i.key.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_partition_by_directory() {
+        let items = vec![
+            item("src/api/users.py", "auth", "a1"),
+            item("src/db/query.py", "sql", "d1"),
+            item("./src/api/orders.py", "auth", "a2"),
+            item("src/db/pool.py", "leak", "d2"),
+            item("src/db/query.py", "sql", "d3"),
+            item("README.md", "docs", "r1"),
+        ];
+
+        assert_eq!(partition(items.clone(), PartitionBy::Directory, 0).len(), 1);
+        assert_eq!(
+            keys(&partition(items.clone(), PartitionBy::Directory, 3)),
+            vec![vec!["r1", "a1", "a2"], vec!["d1", "d2", "d3"]]
+        );
+        // An oversized partition is split; small ones fill the gaps
+        assert_eq!(
+            keys(&partition(items.clone(), PartitionBy::Directory, 2)),
+            vec![vec!["r1"], vec!["a1", "a2"], vec!["d1", "d2"], vec!["d3"]]
+        );
+        assert_eq!(
+            keys(&partition(items, PartitionBy::Type, 4)),
+            vec![vec!["a1", "a2", "r1", "d2"], vec!["d1", "d3"]]
+        );
+    }
+
+    #[test]
+    fn test_partial_input_uses_key() {
+        let partial = item("src/a.py", "auth", "REDUCED-001");
+        assert_eq!(partial.input["fingerprint"], "REDUCED-001");
+        assert!(partial.input.get("merged_from").is_none());
+        assert_eq!(partial.covers, vec!["REDUCED-001"]);
+    }
+
+    #[test]
+    fn test_unique_ids() {
+        let mut findings: Vec<ReducedFinding> = ["REDUCED-001", "REDUCED-002", "REDUCED-001", ""]
+            .iter()
+            .map(|id| item("a.py", "t", id).fallback)
+            .collect();
+        let existing: HashSet<&str> = ["REDUCED-002"].into_iter().collect();
+        unique_ids(&mut findings, &existing);
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["REDUCED-001", "REDUCED-003", "REDUCED-004", "REDUCED-005"]
+        );
+    }
+}
@@ -0,0 +1,647 @@
+//! Incremental reduction on top of an earlier run's `reduced.json`
+//!
+//! Findings whose fingerprints were reduced before keep that reduced finding's
+//! id without another trip through the reducer. Only new findings are sent,
+//! and the reducer may attach them to existing findings and clusters or create
+//! new ones. Earlier findings with no source findings left are dropped as
+//! resolved. Earlier findings that live on take their location and priority
+//! from today's source findings, since the code may have moved or calibration
+//! changed since. Every change is recorded in the changelog written with the
+//! result.
+
+use super::dedupe::LocalGroup;
+use super::{FindingCluster, ReducedFinding, ReducedOutput};
+use crate::config::PostProcessConfig;
+use crate::error::PostprocessError;
+use crate::parser::Finding;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// An earlier reduction to build on
+#[derive(Debug)]
+pub struct Previous {
+    pub path: PathBuf,
+    pub findings: Vec<ReducedFinding>,
+    pub clusters: Vec<FindingCluster>,
+}
+
+/// Reduced findings carried over by id, with today's fingerprints for each
+pub type Carried = Vec<(String, Vec<String>)>;
+
+/// Today's finding for each fingerprint: the representative of its local group
+pub type Sources = HashMap<String, Finding>;
+
+/// What changed relative to the previous reduction
+#[derive(Debug, Clone, Serialize)]
+pub struct ReductionChangelog {
+    /// The `reduced.json` this reduction built on
+    pub previous: PathBuf,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+impl ReductionChangelog {
+    /// One-line tally of the finding changes, e.g. "12 kept, 1 attached, 2 added, 3 resolved"
+    pub fn tally(&self) -> String {
+        let count = |change| self.entries.iter().filter(|e| e.change == change).count();
+        format!(
+            "{} kept, {} attached, {} added, {} resolved",
+            count(Change::Kept),
+            count(Change::Attached),
+            count(Change::Added),
+            count(Change::Resolved)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogEntry {
+    pub change: Change,
+    /// Reduced finding id, or cluster name
+    pub id: String,
+    /// Fingerprints the change applies to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fingerprints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// Existing finding, no new source findings
+    Kept,
+    /// New source findings merged into an existing finding
+    Attached,
+    /// New finding
+    Added,
+    /// Existing finding with no source findings left
+    Resolved,
+    ClusterAdded,
+    ClusterExtended,
+    ClusterRemoved,
+}
+
+/// Load the reduction to build on, if incremental reduction is enabled and one exists
+///
+/// An explicit `previous` must load; one found among earlier report
+/// directories is skipped with a warning when it can't be read.
+pub fn find_previous(
+    config: &PostProcessConfig,
+    report_dir: &Path,
+) -> Result<Option<Previous>, PostprocessError> {
+    if !config.incremental {
+        return Ok(None);
+    }
+    if let Some(path) = &config.previous {
+        return load(path).map(Some);
+    }
+    let Some(path) = latest_earlier(report_dir) else {
+        debug!("No earlier reduced.json found, reducing from scratch");
+        return Ok(None);
+    };
+    match load(&path) {
+        Ok(previous) => Ok(Some(previous)),
+        Err(e) => {
+            warn!("Ignoring earlier reduction {}: {}", path.display(), e);
+            Ok(None)
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<Previous, PostprocessError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        PostprocessError::Io(std::io::Error::new(
+            e.kind(),
+            format!("Failed to read {}: {}", path.display(), e),
+        ))
+    })?;
+    let output: ReducedOutput = serde_json::from_str(&content).map_err(|e| {
+        PostprocessError::ParseOutput(format!("Failed to parse {}: {}", path.display(), e))
+    })?;
+    Ok(Previous {
+        path: path.to_path_buf(),
+        findings: output.findings,
+        clusters: output.clusters,
+    })
+}
+
+/// `reduced.json` of the latest sibling report directory that sorts before `report_dir`
+///
+/// Report directories are named by date (`reports/YYYY-MM-DD`), so name
+/// order is run order.
+fn latest_earlier(report_dir: &Path) -> Option<PathBuf> {
+    let name = report_dir.file_name()?;
+    let parent = report_dir
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::read_dir(parent)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().as_os_str() < name)
+        .map(|entry| entry.path().join("reduced.json"))
+        .filter(|path| path.is_file())
+        .max()
+}
+
+/// Split today's groups into those already reduced and new ones
+///
+/// A group is carried over by the first earlier finding that lists one of
+/// its fingerprints in `merged_from`.
+pub fn carry_over(previous: &Previous, groups: Vec<LocalGroup>) -> (Carried, Vec<LocalGroup>) {
+    let mut owner: HashMap<&str, &str> = HashMap::new();
+    for finding in &previous.findings {
+        for fp in &finding.merged_from {
+            owner.entry(fp.as_str()).or_insert(finding.id.as_str());
+        }
+    }
+
+    let mut carried: Carried = Vec::new();
+    let mut new = Vec::new();
+    for group in groups {
+        let id = group
+            .merged_from
+            .iter()
+            .find_map(|fp| owner.get(fp.as_str()).copied());
+        match id {
+            Some(id) => match carried.iter_mut().find(|(kept, _)| kept == id) {
+                Some((_, fps)) => fps.extend(group.merged_from),
+                None => carried.push((id.to_string(), group.merged_from)),
+            },
+            None => new.push(group),
+        }
+    }
+    (carried, new)
+}
+
+/// Map every fingerprint in `groups` to its group's representative finding
+pub fn sources(groups: &[LocalGroup]) -> Sources {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .merged_from
+                .iter()
+                .map(|fp| (fp.clone(), group.finding.finding.clone()))
+        })
+        .collect()
+}
+
+/// Prompt section listing the earlier findings and clusters new findings may join
+pub fn prompt_section(previous: &Previous) -> Result<String, serde_json::Error> {
+    #[derive(Serialize)]
+    struct Existing<'a> {
+        id: &'a str,
+        #[serde(rename = "type")]
+        finding_type: &'a str,
+        title: &'a str,
+        priority: &'a str,
+        file: &'a Path,
+        line: u32,
+    }
+    #[derive(Serialize)]
+    struct Cluster<'a> {
+        name: &'a str,
+        rationale: &'a str,
+    }
+
+    let findings: Vec<Existing> = previous
+        .findings
+        .iter()
+        .map(|f| Existing {
+            id: &f.id,
+            finding_type: &f.finding_type,
+            title: &f.title,
+            priority: &f.priority,
+            file: &f.file,
+            line: f.line,
+        })
+        .collect();
+    let clusters: Vec<Cluster> = previous
+        .clusters
+        .iter()
+        .map(|c| Cluster {
+            name: &c.name,
+            rationale: &c.rationale,
+        })
+        .collect();
+    let used: HashSet<&str> = previous.findings.iter().map(|f| f.id.as_str()).collect();
+
+    Ok(format!(
+        "## Existing Reduced Findings\n\n\
+         These findings were reduced on an earlier run and keep their ids. \
+         They are not part of the input. When an input finding is the same issue \
+         as one of them, return a finding with that existing `id` and only the new \
+         fingerprints in `merged_from`; the other fields can be omitted. Number new \
+         findings from `{}`. To add findings to an existing cluster, reuse its exact \
+         `name`.\n\n```json\n{}\n```\n\n### Existing Clusters\n\n```json\n{}\n```",
+        next_id(&used),
+        serde_json::to_string_pretty(&findings)?,
+        serde_json::to_string_pretty(&clusters)?,
+    ))
+}
+
+/// Split reducer output into attachments to earlier findings and new findings
+pub fn split_attachments(
+    previous: &Previous,
+    reduced: Vec<ReducedFinding>,
+) -> (Carried, Vec<ReducedFinding>) {
+    let existing: HashSet<&str> = previous.findings.iter().map(|f| f.id.as_str()).collect();
+    let mut attached: Carried = Vec::new();
+    let mut added = Vec::new();
+    for finding in reduced {
+        if existing.contains(finding.id.as_str()) {
+            attached.push((finding.id, finding.merged_from));
+        } else {
+            added.push(finding);
+        }
+    }
+    (attached, added)
+}
+
+/// Combine the earlier reduction with today's carried, attached and new findings
+///
+/// Earlier findings that live on keep their id and wording, but take their
+/// location and priority from the source finding of their first fingerprint
+/// in `sources`.
+pub fn reconcile(
+    previous: Previous,
+    carried: Carried,
+    attached: Carried,
+    sources: &Sources,
+    mut added: Vec<ReducedFinding>,
+    new_clusters: Vec<FindingCluster>,
+) -> (Vec<ReducedFinding>, Vec<FindingCluster>, ReductionChangelog) {
+    let mut entries = Vec::new();
+    let mut findings = Vec::new();
+    let mut owner: HashMap<String, String> = HashMap::new();
+
+    for mut finding in previous.findings {
+        for fp in &finding.merged_from {
+            owner
+                .entry(fp.clone())
+                .or_insert_with(|| finding.id.clone());
+        }
+        let lookup = |list: &Carried| -> Vec<String> {
+            list.iter()
+                .filter(|(id, _)| *id == finding.id)
+                .flat_map(|(_, fps)| fps.iter().cloned())
+                .collect()
+        };
+        let kept = lookup(&carried);
+        let mut new: Vec<String> = Vec::new();
+        for fp in lookup(&attached) {
+            if !kept.contains(&fp) && !new.contains(&fp) {
+                new.push(fp);
+            }
+        }
+
+        if kept.is_empty() && new.is_empty() {
+            entries.push(ChangelogEntry {
+                change: Change::Resolved,
+                id: finding.id,
+                fingerprints: finding.merged_from,
+            });
+            continue;
+        }
+        entries.push(ChangelogEntry {
+            change: if new.is_empty() {
+                Change::Kept
+            } else {
+                Change::Attached
+            },
+            id: finding.id.clone(),
+            fingerprints: new.clone(),
+        });
+        finding.merged_from = kept;
+        finding.merged_from.extend(new);
+        if let Some(source) = finding.merged_from.iter().find_map(|fp| sources.get(fp)) {
+            refresh(&mut finding, source);
+        }
+        findings.push(finding);
+    }
+
+    // New findings must not reuse an id, including those of resolved findings
+    let mut used: HashSet<String> = owner.values().cloned().collect();
+    for finding in &mut added {
+        if finding.id.is_empty() || used.contains(&finding.id) {
+            let borrowed: HashSet<&str> = used.iter().map(String::as_str).collect();
+            finding.id = next_id(&borrowed);
+        }
+        used.insert(finding.id.clone());
+        entries.push(ChangelogEntry {
+            change: Change::Added,
+            id: finding.id.clone(),
+            fingerprints: finding.merged_from.clone(),
+        });
+    }
+    findings.extend(added);
+
+    let clusters = reconcile_clusters(
+        previous.clusters,
+        new_clusters,
+        &findings,
+        &owner,
+        &mut entries,
+    );
+
+    let changelog = ReductionChangelog {
+        previous: previous.path,
+        entries,
+    };
+    (findings, clusters, changelog)
+}
+
+/// Bring an earlier finding's location and priority up to date with `source`
+fn refresh(finding: &mut ReducedFinding, source: &Finding) {
+    finding.file = source.file.clone();
+    finding.line = source.line;
+    finding.end_line = source.end_line;
+    finding.column = source.column;
+    finding.end_column = source.end_column;
+    finding.anchor = source.anchor.clone();
+    finding.priority = source.priority.to_string();
+    finding.calibration = source.calibration.clone();
+}
+
+/// Keep earlier clusters (minus what was resolved), extending them or adding new ones
+///
+/// A cluster member whose fingerprint is gone but whose finding was carried
+/// over is replaced by the finding's id, which cluster grouping also accepts.
+fn reconcile_clusters(
+    previous: Vec<FindingCluster>,
+    new_clusters: Vec<FindingCluster>,
+    findings: &[ReducedFinding],
+    owner: &HashMap<String, String>,
+    entries: &mut Vec<ChangelogEntry>,
+) -> Vec<FindingCluster> {
+    let ids: HashSet<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+    let present: HashSet<&str> = findings
+        .iter()
+        .flat_map(|f| f.merged_from.iter().map(String::as_str))
+        .chain(ids.iter().copied())
+        .collect();
+
+    let mut clusters = Vec::new();
+    for mut cluster in previous {
+        let mut members: Vec<String> = Vec::new();
+        for member in &cluster.fingerprints {
+            let kept = if present.contains(member.as_str()) {
+                Some(member.clone())
+            } else {
+                owner
+                    .get(member)
+                    .filter(|id| ids.contains(id.as_str()))
+                    .cloned()
+            };
+            if let Some(kept) = kept {
+                if !members.contains(&kept) {
+                    members.push(kept);
+                }
+            }
+        }
+        if members.is_empty() {
+            entries.push(ChangelogEntry {
+                change: Change::ClusterRemoved,
+                id: cluster.name,
+                fingerprints: cluster.fingerprints,
+            });
+            continue;
+        }
+        cluster.fingerprints = members;
+        clusters.push(cluster);
+    }
+
+    for new in new_clusters {
+        match clusters.iter_mut().find(|c| c.name == new.name) {
+            Some(cluster) => {
+                let added: Vec<String> = new
+                    .fingerprints
+                    .into_iter()
+                    .filter(|fp| !cluster.fingerprints.contains(fp))
+                    .collect();
+                if !added.is_empty() {
+                    cluster.fingerprints.extend(added.iter().cloned());
+                    entries.push(ChangelogEntry {
+                        change: Change::ClusterExtended,
+                        id: cluster.name.clone(),
+                        fingerprints: added,
+                    });
+                }
+            }
+            None => {
+                entries.push(ChangelogEntry {
+                    change: Change::ClusterAdded,
+                    id: new.name.clone(),
+                    fingerprints: new.fingerprints.clone(),
+                });
+                clusters.push(new);
+            }
+        }
+    }
+    clusters
+}
+
+/// The `REDUCED-NNN` id after the highest one in use
+pub(super) fn next_id(used: &HashSet<&str>) -> String {
+    let highest = used
+        .iter()
+        .filter_map(|id| id.strip_prefix("REDUCED-"))
+        .filter_map(|n| n.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("REDUCED-{:03}", highest + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+
+    fn reduced(id: &str, merged_from: &[&str]) -> ReducedFinding {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": id,
+            "merged_from": merged_from,
+        }))
+        .unwrap()
+    }
+
+    fn cluster(name: &str, fingerprints: &[&str]) -> FindingCluster {
+        FindingCluster {
+            name: name.to_string(),
+            fingerprints: fingerprints.iter().map(|s| s.to_string()).collect(),
+            rationale: String::new(),
+        }
+    }
+
+    fn changes(changelog: &ReductionChangelog) -> Vec<(Change, &str)> {
+        changelog
+            .entries
+            .iter()
+            .map(|e| (e.change, e.id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reconcile_keeps_ids() {
+        let previous = Previous {
+            path: "reports/2024-01-14/reduced.json".into(),
+            findings: vec![
+                reduced("REDUCED-001", &["aaa", "bbb"]),
+                reduced("REDUCED-002", &["ccc"]),
+                reduced("REDUCED-003", &["ddd"]),
+            ],
+            clusters: vec![
+                cluster("Input validation", &["aaa", "ccc"]),
+                cluster("Error handling", &["ddd"]),
+            ],
+        };
+
+        // "aaa" is gone today but its finding lives on through "bbb"
+        let carried = vec![
+            ("REDUCED-001".to_string(), vec!["bbb".to_string()]),
+            ("REDUCED-002".to_string(), vec!["ccc".to_string()]),
+        ];
+        let (attached, added) = split_attachments(
+            &previous,
+            vec![
+                reduced("REDUCED-002", &["eee"]),
+                reduced("REDUCED-004", &["fff"]),
+                reduced("REDUCED-003", &["ggg"]),
+            ],
+        );
+        let (findings, clusters, changelog) = reconcile(
+            previous,
+            carried,
+            attached,
+            &Sources::new(),
+            added,
+            vec![
+                cluster("Input validation", &["eee"]),
+                cluster("Logging", &["fff"]),
+            ],
+        );
+
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["REDUCED-001", "REDUCED-002", "REDUCED-003", "REDUCED-004"]
+        );
+        assert_eq!(findings[1].merged_from, vec!["ccc", "eee"]);
+        assert_eq!(findings[2].merged_from, vec!["ggg"]);
+        assert_eq!(
+            changes(&changelog),
+            vec![
+                (Change::Kept, "REDUCED-001"),
+                (Change::Attached, "REDUCED-002"),
+                (Change::Attached, "REDUCED-003"),
+                (Change::Added, "REDUCED-004"),
+                (Change::ClusterExtended, "Input validation"),
+                (Change::ClusterAdded, "Logging"),
+            ]
+        );
+        assert_eq!(clusters[0].fingerprints, vec!["REDUCED-001", "ccc", "eee"]);
+        assert_eq!(clusters[1].fingerprints, vec!["REDUCED-003"]);
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(changelog.tally(), "1 kept, 2 attached, 1 added, 0 resolved");
+    }
+
+    #[test]
+    fn test_reconcile_refreshes_location_and_priority() {
+        let mut old = reduced("REDUCED-001", &["aaa", "bbb"]);
+        old.file = "src/old.py".into();
+        old.line = 10;
+        old.end_line = Some(12);
+        old.priority = "p2".to_string();
+        let previous = Previous {
+            path: "reduced.json".into(),
+            findings: vec![old, reduced("REDUCED-002", &["ccc"])],
+            clusters: vec![],
+        };
+
+        let source = |file: &str, line: u32, priority| Finding {
+            file: file.into(),
+            line,
+            priority,
+            ..Default::default()
+        };
+        let sources = Sources::from([
+            ("bbb".to_string(), source("src/new.py", 40, Priority::P0)),
+            ("ddd".to_string(), source("src/api.py", 7, Priority::P1)),
+        ]);
+        let carried = vec![("REDUCED-001".to_string(), vec!["bbb".to_string()])];
+        let attached = vec![("REDUCED-002".to_string(), vec!["ddd".to_string()])];
+        let (findings, _, _) = reconcile(previous, carried, attached, &sources, vec![], vec![]);
+
+        assert_eq!(findings[0].id, "REDUCED-001");
+        assert_eq!(findings[0].title, "REDUCED-001");
+        assert_eq!(findings[0].file, PathBuf::from("src/new.py"));
+        assert_eq!((findings[0].line, findings[0].end_line), (40, None));
+        assert_eq!(findings[0].priority, "p0");
+        assert_eq!(findings[1].id, "REDUCED-002");
+        assert_eq!(findings[1].file, PathBuf::from("src/api.py"));
+        assert_eq!(findings[1].priority, "p1");
+    }
+
+    #[test]
+    fn test_reconcile_resolves_and_numbers_new_findings() {
+        let previous = Previous {
+            path: "reduced.json".into(),
+            findings: vec![
+                reduced("REDUCED-001", &["aaa"]),
+                reduced("REDUCED-007", &["bbb"]),
+            ],
+            clusters: vec![cluster("Auth", &["bbb"])],
+        };
+        let carried = vec![("REDUCED-001".to_string(), vec!["aaa".to_string()])];
+        let (findings, clusters, changelog) = reconcile(
+            previous,
+            carried,
+            vec![],
+            &Sources::new(),
+            vec![
+                reduced("REDUCED-007", &["ccc"]),
+                reduced("", &["ddd"]),
+                reduced("SEC-1", &["eee"]),
+            ],
+            vec![],
+        );
+
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["REDUCED-001", "REDUCED-008", "REDUCED-009", "SEC-1"]
+        );
+        assert!(clusters.is_empty());
+        assert_eq!(
+            changes(&changelog),
+            vec![
+                (Change::Kept, "REDUCED-001"),
+                (Change::Resolved, "REDUCED-007"),
+                (Change::Added, "REDUCED-008"),
+                (Change::Added, "REDUCED-009"),
+                (Change::Added, "SEC-1"),
+                (Change::ClusterRemoved, "Auth"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_latest_earlier() {
+        let dir = tempfile::tempdir().unwrap();
+        for day in ["2024-01-13", "2024-01-14", "2024-01-15", "2024-01-16"] {
+            std::fs::create_dir(dir.path().join(day)).unwrap();
+        }
+        for day in ["2024-01-13", "2024-01-14", "2024-01-15", "2024-01-16"] {
+            if day != "2024-01-14" {
+                std::fs::write(dir.path().join(day).join("reduced.json"), "{}").unwrap();
+            }
+        }
+        assert_eq!(
+            latest_earlier(&dir.path().join("2024-01-15")),
+            Some(dir.path().join("2024-01-13").join("reduced.json"))
+        );
+        assert_eq!(latest_earlier(&dir.path().join("2024-01-13")), None);
+    }
+}
@@ -2,15 +2,30 @@
 //!
 //! After all reviewers complete, this module:
 //! 1. Collects all findings from `*.findings.json` files
-//! 2. Invokes the configured CLI to deduplicate and cluster findings
-//! 3. Writes the reduced output to `reduced.json`
+//! 2. Merges obvious duplicates locally (see `dedupe`)
+//! 3. Carries over findings already reduced on an earlier run (see `incremental`)
+//! 4. Invokes the configured CLI to deduplicate and cluster the rest, in
+//!    parallel partitions when there are too many for one call (see `hierarchy`)
+//! 5. Writes the reduced output to `reduced.json`, falling back to the local
+//!    aggregate when the reducer fails (see `ReductionStatus`)
+
+mod dedupe;
+mod hierarchy;
+mod incremental;
 
 use crate::config::Config;
 use crate::error::PostprocessError;
-use crate::parser::Finding;
+use crate::parser::{
+    Confidence, Finding, PriorityAdjustment, RelatedLocation, Verification, Votes,
+};
+use dedupe::LocalGroup;
+use incremental::{Carried, Previous, ReductionChangelog, Sources};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::time::Duration;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::timeout as tokio_timeout;
 use tracing::{debug, info, warn};
@@ -44,6 +59,10 @@ pub struct ReducedFinding {
     #[serde(default)]
     pub merged_from: Vec<String>,
 
+    /// Reviewers whose findings were merged into this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviewers: Vec<String>,
+
     #[serde(default)]
     pub id: String,
 
@@ -62,17 +81,57 @@ pub struct ReducedFinding {
     #[serde(default)]
     pub line: u32,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<u32>,
+
     #[serde(default)]
     pub description: String,
 
     #[serde(default)]
     pub remediation: String,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_patch: Option<String>,
+
     #[serde(default)]
     pub acceptance_criteria: Vec<String>,
 
     #[serde(default)]
     pub references: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<RelatedLocation>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cwe: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owasp: Vec<String>,
+
+    /// Enclosing definition carried over from the source finding (for fingerprinting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+
+    /// Consensus votes carried over from the source finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub votes: Option<Votes>,
+
+    /// Calibration changes carried over from the source finding
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calibration: Vec<PriorityAdjustment>,
+
+    /// Verdict of the `verify` stage, when it ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Verification>,
 }
 
 /// A cluster of related findings
@@ -94,6 +153,29 @@ pub struct PostprocessResult {
     pub clusters: Vec<FindingCluster>,
     pub findings: Vec<ReducedFinding>,
     pub summary: Option<String>,
+    pub reduction_status: ReductionStatus,
+    /// Changes relative to the earlier reduction this one built on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<ReductionChangelog>,
+}
+
+/// How the findings in reduced.json were produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ReductionStatus {
+    /// The reducer covered every finding
+    Reduced,
+    /// Some reducer calls failed; their findings were kept unreduced
+    Partial { failures: Vec<String> },
+    /// The reducer wasn't run; findings are the local aggregate
+    Local { reason: String },
+    /// The reducer failed; findings are the local aggregate
+    Fallback {
+        error: String,
+        /// Output of the failed or unparsable reducer call, saved for debugging
+        #[serde(skip_serializing_if = "Option::is_none")]
+        raw_output: Option<PathBuf>,
+    },
 }
 
 /// Run the postprocessing step
@@ -110,43 +192,83 @@ pub async fn run_postprocess(
 
     // Collect all findings
     let sourced_findings = collect_findings(report_dir)?;
+    let original_count = sourced_findings.len();
+    let reviewer_of: HashMap<String, String> = sourced_findings
+        .iter()
+        .map(|f| (f.fingerprint.clone(), f.reviewer_id.clone()))
+        .collect();
+
+    // Merge obvious duplicates before spending tokens on them
+    let groups = if config.postprocess.local_dedupe.enabled {
+        let groups = dedupe::dedupe(sourced_findings, &config.postprocess.local_dedupe);
+        if groups.len() < original_count {
+            info!(
+                "Local dedupe merged {} findings into {}",
+                original_count,
+                groups.len()
+            );
+        }
+        groups
+    } else {
+        sourced_findings
+            .into_iter()
+            .map(LocalGroup::single)
+            .collect()
+    };
 
-    if sourced_findings.len() < config.postprocess.min_findings {
-        info!(
-            "Only {} findings found, below threshold of {} - skipping reduction",
-            sourced_findings.len(),
-            config.postprocess.min_findings
-        );
+    // Findings reduced on an earlier run keep their ids; only new ones are reduced
+    let previous = incremental::find_previous(&config.postprocess, report_dir)?;
+    let (carried, sources, groups) = match &previous {
+        Some(prev) => {
+            let sources = incremental::sources(&groups);
+            let (carried, new) = incremental::carry_over(prev, groups);
+            info!(
+                "Building on {}: {} findings keep their ids, {} new",
+                prev.path.display(),
+                carried.len(),
+                new.len()
+            );
+            (carried, sources, new)
+        }
+        None => (Vec::new(), Sources::new(), groups),
+    };
+
+    if !config.postprocess.ai || groups.is_empty() || groups.len() < config.postprocess.min_findings
+    {
+        let reason = if !config.postprocess.ai {
+            debug!("AI reduction disabled, keeping locally deduplicated findings");
+            "AI reduction disabled".to_string()
+        } else if groups.is_empty() {
+            info!("No new findings to reduce");
+            "no new findings".to_string()
+        } else {
+            info!(
+                "Only {} findings found, below threshold of {} - skipping reduction",
+                groups.len(),
+                config.postprocess.min_findings
+            );
+            format!(
+                "{} findings, below min_findings of {}",
+                groups.len(),
+                config.postprocess.min_findings
+            )
+        };
         // Still write the aggregated findings even if we skip reduction
-        let result = PostprocessResult {
-            original_count: sourced_findings.len(),
-            reduced_count: sourced_findings.len(),
+        let reduced = ReducedOutput {
+            findings: groups.into_iter().map(reduce_local).collect(),
             clusters: vec![],
-            findings: sourced_findings
-                .into_iter()
-                .map(|sf| ReducedFinding {
-                    merged_from: vec![sf.fingerprint],
-                    id: sf.finding.id,
-                    finding_type: sf.finding.finding_type,
-                    title: sf.finding.title,
-                    priority: sf.finding.priority.to_string(),
-                    file: sf.finding.file,
-                    line: sf.finding.line,
-                    description: sf.finding.description,
-                    remediation: sf.finding.remediation,
-                    acceptance_criteria: sf.finding.acceptance_criteria,
-                    references: sf.finding.references,
-                })
-                .collect(),
             summary: None,
         };
+        let status = ReductionStatus::Local { reason };
+        let mut result = build_result(original_count, previous, carried, &sources, reduced, status);
+        attach_reviewers(&mut result.findings, &reviewer_of);
         write_result(report_dir, &result)?;
         return Ok(Some(result));
     }
 
     info!(
         "Reducing {} findings using {}",
-        sourced_findings.len(),
+        groups.len(),
         config.postprocess.tool
     );
 
@@ -172,28 +294,52 @@ pub async fn run_postprocess(
         }
     };
 
-    // Build the full prompt with findings
-    let findings_json = serde_json::to_string_pretty(&sourced_findings)?;
-    let full_prompt = format!(
-        "{}\n\n## Input Findings\n\n```json\n{}\n```",
-        prompt_content, findings_json
-    );
-
-    // Invoke the CLI
-    let timeout = Duration::from_secs(config.postprocess.timeout_sec);
-    let output = invoke_cli(config, &full_prompt, timeout).await?;
-
-    // Parse the output
-    let reduced = parse_reduced_output(&output)?;
+    // Existing ids and clusters the reducer may attach new findings to
+    let context = match &previous {
+        Some(prev) => incremental::prompt_section(prev)?,
+        None => String::new(),
+    };
 
-    let result = PostprocessResult {
-        original_count: sourced_findings.len(),
-        reduced_count: reduced.findings.len(),
-        clusters: reduced.clusters,
-        findings: reduced.findings,
-        summary: reduced.summary,
+    let reduction = hierarchy::reduce(
+        config,
+        &prompt_content,
+        &context,
+        &groups,
+        previous.as_ref(),
+        report_dir,
+    )
+    .await;
+
+    // A failed reducer still leaves a usable reduced.json behind
+    let (reduced, status) = match reduction {
+        Ok((reduced, failures)) if failures.is_empty() => (reduced, ReductionStatus::Reduced),
+        Ok((reduced, failures)) => (reduced, ReductionStatus::Partial { failures }),
+        Err(e) => {
+            warn!(
+                "Reducer failed, writing locally aggregated findings instead: {}",
+                e
+            );
+            let raw_output = match &e {
+                PostprocessError::Unparsable { raw_output }
+                | PostprocessError::CliFailed { raw_output, .. } => Some(raw_output.clone()),
+                _ => None,
+            };
+            let reduced = ReducedOutput {
+                findings: groups.into_iter().map(reduce_local).collect(),
+                clusters: vec![],
+                summary: None,
+            };
+            let status = ReductionStatus::Fallback {
+                error: e.to_string(),
+                raw_output,
+            };
+            (reduced, status)
+        }
     };
 
+    let mut result = build_result(original_count, previous, carried, &sources, reduced, status);
+    attach_reviewers(&mut result.findings, &reviewer_of);
+
     info!(
         "Reduction complete: {} -> {} findings ({} clusters)",
         result.original_count,
@@ -206,6 +352,126 @@ pub async fn run_postprocess(
     Ok(Some(result))
 }
 
+/// Assemble the result, reconciling it with the earlier reduction when there is one
+///
+/// Findings from the reducer reusing an earlier id are attachments to that
+/// finding; in a local aggregate (reviewer ids) a reused id is just renumbered.
+fn build_result(
+    original_count: usize,
+    previous: Option<Previous>,
+    carried: Carried,
+    sources: &Sources,
+    reduced: ReducedOutput,
+    reduction_status: ReductionStatus,
+) -> PostprocessResult {
+    let Some(previous) = previous else {
+        return PostprocessResult {
+            original_count,
+            reduced_count: reduced.findings.len(),
+            clusters: reduced.clusters,
+            findings: reduced.findings,
+            summary: reduced.summary,
+            reduction_status,
+            changelog: None,
+        };
+    };
+
+    let from_reducer = matches!(
+        reduction_status,
+        ReductionStatus::Reduced | ReductionStatus::Partial { .. }
+    );
+    let (attached, added) = if from_reducer {
+        incremental::split_attachments(&previous, reduced.findings)
+    } else {
+        (Vec::new(), reduced.findings)
+    };
+    let (findings, clusters, changelog) = incremental::reconcile(
+        previous,
+        carried,
+        attached,
+        sources,
+        added,
+        reduced.clusters,
+    );
+    PostprocessResult {
+        original_count,
+        reduced_count: findings.len(),
+        clusters,
+        findings,
+        summary: reduced.summary,
+        reduction_status,
+        changelog: Some(changelog),
+    }
+}
+
+/// Record which reviewers each finding came from, so per-reviewer issue policy
+/// still applies after reduction; findings whose sources are all from earlier
+/// runs keep the reviewers recorded then
+fn attach_reviewers(findings: &mut [ReducedFinding], reviewer_of: &HashMap<String, String>) {
+    for finding in findings {
+        let mut reviewers: Vec<String> = Vec::new();
+        for fp in &finding.merged_from {
+            if let Some(reviewer) = reviewer_of.get(fp) {
+                if !reviewers.contains(reviewer) {
+                    reviewers.push(reviewer.clone());
+                }
+            }
+        }
+        if !reviewers.is_empty() {
+            finding.reviewers = reviewers;
+        }
+    }
+}
+
+/// A locally deduplicated finding as it appears in `reduced.json`
+fn reduce_local(group: LocalGroup) -> ReducedFinding {
+    let f = group.finding.finding;
+    ReducedFinding {
+        merged_from: group.merged_from,
+        reviewers: Vec::new(),
+        id: f.id,
+        finding_type: f.finding_type,
+        title: f.title,
+        priority: f.priority.to_string(),
+        file: f.file,
+        line: f.line,
+        end_line: f.end_line,
+        column: f.column,
+        end_column: f.end_column,
+        description: f.description,
+        remediation: f.remediation,
+        suggested_patch: f.suggested_patch,
+        acceptance_criteria: f.acceptance_criteria,
+        references: f.references,
+        related_locations: f.related_locations,
+        confidence: f.confidence,
+        cwe: f.cwe,
+        owasp: f.owasp,
+        anchor: f.anchor,
+        votes: f.votes,
+        calibration: f.calibration,
+        verification: f.verification,
+    }
+}
+
+/// Replace each key the reducer was given (a locally merged representative's
+/// fingerprint, or a partial result) with the original fingerprints it stood for
+fn expand_keys(keys: &[String], by_key: &HashMap<&str, &[String]>) -> Vec<String> {
+    let mut expanded: Vec<String> = Vec::new();
+    for key in keys {
+        let originals = by_key
+            .get(key.as_str())
+            .copied()
+            .unwrap_or(std::slice::from_ref(key));
+        for original in originals {
+            if !expanded.contains(original) {
+                expanded.push(original.clone());
+            }
+        }
+    }
+    expanded
+}
+
 /// Collect all findings from *.findings.json files in the report directory
 fn collect_findings(report_dir: &Path) -> Result<Vec<SourcedFinding>, PostprocessError> {
     let mut out = Vec::new();
@@ -279,12 +545,19 @@ fn walk_dir_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Postpr
     Ok(())
 }
 
+/// A reducer call that failed, with whatever it printed before failing
+struct CliFailure {
+    error: PostprocessError,
+    /// stdout, then stderr under a separator line
+    output: String,
+}
+
 /// Invoke the configured CLI with the reduction prompt
 async fn invoke_cli(
     config: &Config,
     prompt: &str,
     timeout: Duration,
-) -> Result<String, PostprocessError> {
+) -> Result<String, CliFailure> {
     let tool = &config.postprocess.tool;
 
     // Determine provider type from string
@@ -339,23 +612,49 @@ async fn invoke_cli(
     cmd.current_dir(&config.target);
     cmd.env_remove("ANTHROPIC_API_KEY"); // Use subscription auth
     cmd.args(&args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd.spawn().map_err(|e| CliFailure {
+        error: PostprocessError::Io(e),
+        output: String::new(),
+    })?;
+    let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+
+    // Read as the CLI writes, so a timed out call still leaves its partial output
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let status = tokio_timeout(timeout, async {
+        let (status, _, _) = tokio::join!(
+            child.wait(),
+            stdout_pipe.read_to_end(&mut stdout),
+            stderr_pipe.read_to_end(&mut stderr)
+        );
+        status
+    })
+    .await;
 
-    let output = tokio_timeout(timeout, cmd.output())
-        .await
-        .map_err(|_| PostprocessError::Timeout(timeout))?
-        .map_err(PostprocessError::Io)?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(PostprocessError::CliExecution(format!(
+    let error = match status {
+        Ok(Ok(status)) if status.success() => {
+            return Ok(String::from_utf8_lossy(&stdout).to_string());
+        }
+        Ok(Ok(status)) => PostprocessError::CliExecution(format!(
             "CLI exited with code {}: {}",
-            output.status.code().unwrap_or(-1),
-            stderr
-        )));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(stdout)
+            status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&stderr)
+        )),
+        Ok(Err(e)) => PostprocessError::Io(e),
+        Err(_) => PostprocessError::Timeout(timeout),
+    };
+    Err(CliFailure {
+        error,
+        output: format!(
+            "{}\n--- stderr ---\n{}",
+            String::from_utf8_lossy(&stdout),
+            String::from_utf8_lossy(&stderr)
+        ),
+    })
 }
 
 /// Parse the CLI output to extract reduced findings
@@ -434,7 +733,7 @@ fn try_parse_reduced(s: &str) -> Option<ReducedOutput> {
 }
 
 /// Extract JSON object or array from a string that might contain markdown code blocks
-fn extract_json(s: &str) -> Option<String> {
+pub(crate) fn extract_json(s: &str) -> Option<String> {
     let trimmed = s.trim();
 
     // First try: the whole string is valid JSON (object or array)
@@ -527,4 +826,109 @@ Here is the reduced output:
         assert_eq!(reduced.findings.len(), 1);
         assert_eq!(reduced.findings[0].merged_from, vec!["abc123"]);
     }
+
+    #[test]
+    fn test_expand_keys() {
+        // "aaa" stands for a local group that absorbed "bbb"
+        let group = vec!["aaa".to_string(), "bbb".to_string()];
+        let by_key: HashMap<&str, &[String]> = HashMap::from([("aaa", group.as_slice())]);
+        let findings = try_parse_reduced(
+            r#"[{"merged_from": ["aaa", "ccc", "bbb"], "title": "t", "file": "a.py"}]"#,
+        )
+        .unwrap()
+        .findings;
+        assert_eq!(
+            expand_keys(&findings[0].merged_from, &by_key),
+            vec!["aaa", "bbb", "ccc"]
+        );
+    }
+
+    // Stands in `false`, `true` and a shell script for the reducer binary
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_reducer_failure_falls_back_to_local_aggregate() {
+        let dir = tempfile::tempdir().unwrap();
+        let report_dir = dir.path().join("2024-01-15");
+        std::fs::create_dir_all(&report_dir).unwrap();
+        std::fs::write(
+            report_dir.join("security.findings.json"),
+            r#"[{"id": "SEC-1", "title": "SQL injection", "priority": "p0", "file": "db.py", "line": 4, "description": "d"},
+                {"id": "SEC-2", "title": "Missing auth", "priority": "p1", "file": "api.py", "line": 9, "description": "d"}]"#,
+        )
+        .unwrap();
+
+        let mut config = Config {
+            target: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        config.postprocess.enabled = true;
+        config.postprocess.tool = "codex_cli".to_string();
+
+        // A reducer that exits non-zero
+        config.providers.codex_cli.binary = "false".into();
+        let result = run_postprocess(&config, &report_dir)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.reduced_count, 2);
+        assert!(matches!(
+            &result.reduction_status,
+            ReductionStatus::Fallback {
+                raw_output: Some(path),
+                ..
+            } if *path == report_dir.join("reducer-output.txt")
+        ));
+        let written: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(report_dir.join("reduced.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written["reduction_status"]["state"], "fallback");
+        assert_eq!(written["findings"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            written["findings"][0]["reviewers"],
+            serde_json::json!(["security"])
+        );
+
+        // A reducer whose output isn't JSON
+        config.providers.codex_cli.binary = "true".into();
+        let result = run_postprocess(&config, &report_dir)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result.reduction_status,
+            ReductionStatus::Fallback {
+                error: format!(
+                    "Could not parse reduced findings from CLI output (raw output saved to {})",
+                    report_dir.join("reducer-output.txt").display()
+                ),
+                raw_output: Some(report_dir.join("reducer-output.txt")),
+            }
+        );
+        assert!(report_dir.join("reducer-output.txt").exists());
+
+        // A reducer that times out keeps what it printed so far
+        let script = dir.path().join("slow-reducer.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho partial\necho oops >&2\nsleep 10\n",
+        )
+        .unwrap();
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        config.providers.codex_cli.binary = script;
+        config.postprocess.timeout_sec = 1;
+        let result = run_postprocess(&config, &report_dir)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            &result.reduction_status,
+            ReductionStatus::Fallback { error, .. } if error.starts_with("Execution timed out")
+        ));
+        assert_eq!(
+            std::fs::read_to_string(report_dir.join("reducer-output.txt")).unwrap(),
+            "partial\n\n--- stderr ---\noops\n"
+        );
+    }
 }
@@ -4,7 +4,7 @@ mod codex;
 pub use claude::ClaudeRunner;
 pub use codex::CodexRunner;
 
-use crate::config::{Config, Provider, Reviewer};
+use crate::config::{Config, Provider};
 use crate::error::ProviderError;
 use async_trait::async_trait;
 use std::path::PathBuf;
@@ -43,19 +43,19 @@ pub trait Runner: Send + Sync {
     ) -> Result<ProviderOutput, ProviderError>;
 }
 
-/// Create a runner based on the reviewer's provider configuration
-pub fn create_runner(config: &Config, reviewer: &Reviewer) -> Arc<dyn Runner> {
-    match reviewer.provider {
+/// Create a reviewer's runner for a provider and model
+pub fn create_runner(config: &Config, provider: Provider, model: &str) -> Arc<dyn Runner> {
+    match provider {
         Provider::ClaudeCli => Arc::new(ClaudeRunner {
             binary: config.providers.claude_cli.binary.clone(),
-            model: config.providers.claude_cli.model.clone(),
+            model: model.to_string(),
             tools: config.providers.claude_cli.tools.clone(),
             permission_mode: config.providers.claude_cli.permission_mode.clone(),
             working_dir: config.target.clone(),
         }),
         Provider::CodexCli => Arc::new(CodexRunner {
             binary: config.providers.codex_cli.binary.clone(),
-            model: config.providers.codex_cli.model.clone(),
+            model: model.to_string(),
             working_dir: config.target.clone(),
         }),
     }
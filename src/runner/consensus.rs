@@ -0,0 +1,172 @@
+//! Consensus merging for reviewers that run several models
+//!
+//! Every model reviews the same files. Findings from different models that
+//! share a file and type and overlap in lines are treated as one issue: the
+//! first model's wording and priority are kept and the agreeing models are recorded as
+//! `votes`. Confidence follows the agreement: unanimous findings are high,
+//! majority findings medium, the rest low. Two findings from the same model
+//! are never merged with each other, however close they are.
+
+use crate::parser::{Confidence, Finding, Votes};
+
+/// Lines of slack when matching findings from different models
+const LINE_WINDOW: u32 = 3;
+
+/// Merge each model's findings, dropping those with fewer than `min_votes` votes
+///
+/// `runs` holds the model name and findings of every model that completed,
+/// in configuration order.
+pub fn merge_votes(runs: Vec<(String, Vec<Finding>)>, min_votes: Option<usize>) -> Vec<Finding> {
+    let total = runs.len();
+    let mut groups: Vec<(Finding, Vec<String>)> = Vec::new();
+
+    for (model, findings) in runs {
+        for finding in findings {
+            match groups
+                .iter_mut()
+                .find(|(kept, voters)| !voters.contains(&model) && same_issue(kept, &finding))
+            {
+                Some((_, voters)) => voters.push(model.clone()),
+                None => groups.push((finding, vec![model.clone()])),
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, voters)| min_votes.is_none_or(|n| voters.len() >= n))
+        .map(|(mut finding, voters)| {
+            finding.confidence = Some(consensus_confidence(voters.len(), total));
+            finding.votes = Some(Votes {
+                count: voters.len(),
+                total,
+                models: voters,
+            });
+            finding
+        })
+        .collect()
+}
+
+fn same_issue(a: &Finding, b: &Finding) -> bool {
+    let path = |f: &Finding| {
+        f.file
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .to_string()
+    };
+    if path(a) != path(b) || !a.finding_type.eq_ignore_ascii_case(&b.finding_type) {
+        return false;
+    }
+    // Whole-file findings only match each other
+    if a.line == 0 || b.line == 0 {
+        return a.line == b.line;
+    }
+    let a_end = a.end_line.unwrap_or(a.line).max(a.line);
+    let b_end = b.end_line.unwrap_or(b.line).max(b.line);
+    a.line <= b_end + LINE_WINDOW && b.line <= a_end + LINE_WINDOW
+}
+
+fn consensus_confidence(votes: usize, total: usize) -> Confidence {
+    if votes >= total {
+        Confidence::High
+    } else if votes * 2 > total {
+        Confidence::Medium
+    } else {
+        Confidence::Low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file: &str, line: u32, finding_type: &str, title: &str) -> Finding {
+        Finding {
+            id: title.to_string(),
+            title: title.to_string(),
+            finding_type: finding_type.to_string(),
+            file: file.into(),
+            line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_votes() {
+        let runs = vec![
+            (
+                "opus".to_string(),
+                vec![
+                    finding("src/db.py", 42, "sql-injection", "SQL injection"),
+                    finding("src/api.py", 10, "auth", "Missing auth check"),
+                ],
+            ),
+            (
+                "o3".to_string(),
+                vec![
+                    finding("./src/db.py", 44, "SQL-Injection", "Unparameterized query"),
+                    finding("src/db.py", 90, "sql-injection", "Another query"),
+                ],
+            ),
+            (
+                "sonnet".to_string(),
+                vec![finding("src/db.py", 40, "sql-injection", "Injection")],
+            ),
+        ];
+
+        let merged = merge_votes(runs.clone(), None);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].title, "SQL injection");
+        assert_eq!(
+            merged[0].votes,
+            Some(Votes {
+                count: 3,
+                total: 3,
+                models: vec!["opus".into(), "o3".into(), "sonnet".into()],
+            })
+        );
+        assert_eq!(merged[0].confidence, Some(Confidence::High));
+        assert_eq!(merged[1].confidence, Some(Confidence::Low));
+
+        let agreed = merge_votes(runs, Some(2));
+        assert_eq!(agreed.len(), 1);
+        assert_eq!(
+            agreed[0].votes.as_ref().unwrap().to_string(),
+            "3/3 (opus, o3, sonnet)"
+        );
+    }
+
+    #[test]
+    fn test_merge_votes_keeps_same_model_findings_apart() {
+        let runs = vec![
+            (
+                "opus".to_string(),
+                vec![
+                    finding("src/db.py", 42, "sql-injection", "First query"),
+                    finding("src/db.py", 44, "sql-injection", "Second query"),
+                ],
+            ),
+            (
+                "o3".to_string(),
+                vec![finding("src/db.py", 43, "sql-injection", "Injection")],
+            ),
+        ];
+
+        let merged = merge_votes(runs, None);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].title, "First query");
+        assert_eq!(merged[0].votes.as_ref().unwrap().count, 2);
+        assert_eq!(merged[1].title, "Second query");
+        assert_eq!(
+            merged[1].votes.as_ref().unwrap().models,
+            vec!["opus".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_consensus_confidence() {
+        assert_eq!(consensus_confidence(2, 2), Confidence::High);
+        assert_eq!(consensus_confidence(2, 3), Confidence::Medium);
+        assert_eq!(consensus_confidence(1, 2), Confidence::Low);
+    }
+}
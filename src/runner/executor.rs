@@ -1,13 +1,20 @@
-use crate::config::{Config, Reviewer};
+use crate::calibration::Calibration;
+use crate::config::{Config, Provider, Reviewer};
 use crate::discovery::{chunk_files, discover_files_for_reviewer};
 use crate::error::RunnerError;
-use crate::parser::{parse_findings, Finding};
-use crate::provider::{create_runner, SessionInfo};
+use crate::parser::{attach_anchors, build_repair_prompt, parse_findings, Finding, ParseOutcome};
+use crate::patch::check_patches;
+use crate::provider::{create_runner, Runner, SessionInfo};
+use crate::suppression::{SuppressionSource, Suppressions};
+use futures::future::join_all;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use super::consensus::merge_votes;
+use super::location::check_locations;
 use super::retry::retry_with_backoff;
 use super::{ReviewerResult, ReviewerStatus};
 
@@ -62,10 +69,71 @@ fn build_chunk_prompt(
     }
 }
 
+/// Parse reviewer output, resuming the session to ask for corrected JSON
+/// when the output is unparseable or has invalid items
+async fn parse_with_repair(
+    config: &Config,
+    reviewer: &Reviewer,
+    runner: &Arc<dyn Runner>,
+    raw: &str,
+    session_id: Option<&str>,
+    timeout: Duration,
+) -> ParseOutcome {
+    let mut outcome = parse_findings(raw, &reviewer.id, reviewer.priority_default);
+
+    for attempt in 1..=config.repair_attempts {
+        if outcome.is_clean() {
+            break;
+        }
+        let Some(sid) = session_id else {
+            debug!(
+                "Reviewer {} has no session to resume; skipping output repair",
+                reviewer.id
+            );
+            break;
+        };
+
+        info!(
+            "Reviewer {} output invalid ({} errors), requesting repair {}/{}",
+            reviewer.id,
+            outcome.errors.len(),
+            attempt,
+            config.repair_attempts
+        );
+
+        let session = SessionInfo {
+            session_id: Some(sid.to_string()),
+            is_resume: true,
+        };
+        match runner
+            .execute(&build_repair_prompt(&outcome), &[], timeout, Some(&session))
+            .await
+        {
+            Ok(output) => {
+                let repaired =
+                    parse_findings(&output.stdout, &reviewer.id, reviewer.priority_default);
+                if repaired.is_better_than(&outcome) {
+                    outcome = repaired;
+                } else {
+                    debug!("Reviewer {} repair did not improve output", reviewer.id);
+                }
+            }
+            Err(e) => {
+                warn!("Reviewer {} repair attempt failed: {}", reviewer.id, e);
+                break;
+            }
+        }
+    }
+
+    outcome
+}
+
 pub async fn execute_reviewer(
     config: &Config,
     reviewer: &Reviewer,
     diff_base: Option<&str>,
+    suppressions: &Suppressions,
+    calibration: &Calibration,
 ) -> Result<ReviewerResult, RunnerError> {
     let start = std::time::Instant::now();
 
@@ -81,6 +149,8 @@ pub async fn execute_reviewer(
                 },
                 files_scanned: 0,
                 findings: Vec::new(),
+                suppressed: 0,
+                parse_errors: Vec::new(),
                 duration: start.elapsed(),
             });
         }
@@ -96,6 +166,8 @@ pub async fn execute_reviewer(
             },
             files_scanned: 0,
             findings: Vec::new(),
+            suppressed: 0,
+            parse_errors: Vec::new(),
             duration: start.elapsed(),
         });
     }
@@ -124,41 +196,186 @@ pub async fn execute_reviewer(
                 },
                 files_scanned: 0,
                 findings: Vec::new(),
+                suppressed: 0,
+                parse_errors: Vec::new(),
                 duration: start.elapsed(),
             });
         }
     };
 
-    // Create runner for this reviewer's provider
-    let runner = create_runner(config, reviewer);
-
-    // Get timeout and max_files settings
-    let timeout = Duration::from_secs(reviewer.timeout_sec.unwrap_or(config.timeout_sec));
     let max_files = reviewer.max_files.unwrap_or(config.max_files);
 
     // Chunk files if needed
     let chunks = chunk_files(&files, max_files);
-    let total_chunks = chunks.len();
     debug!(
         "Reviewer {} split into {} chunks",
-        reviewer.id, total_chunks
+        reviewer.id,
+        chunks.len()
     );
 
+    // Consensus reviewers run every listed model; others their provider's model
+    let models: Vec<(Provider, String)> = if reviewer.models.is_empty() {
+        vec![(
+            reviewer.provider,
+            config.providers.model(reviewer.provider).to_string(),
+        )]
+    } else {
+        reviewer
+            .models
+            .iter()
+            .map(|m| {
+                let model = m
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| config.providers.model(m.provider).to_string());
+                (m.provider, model)
+            })
+            .collect()
+    };
+
+    let mut runs = join_all(models.iter().map(|(provider, model)| {
+        review_with_model(
+            config,
+            reviewer,
+            *provider,
+            model,
+            &prompt,
+            &chunks,
+            suppressions,
+        )
+    }))
+    .await;
+
+    // Calibrate per model, so the priority consensus keeps is already calibrated
+    for run in &mut runs {
+        calibration.apply_rules(&mut run.findings, &reviewer.id, &run.model);
+    }
+
+    let (status, mut findings, suppressed, parse_errors) = if runs.len() == 1 {
+        let run = runs.remove(0);
+        (run.status(), run.findings, run.suppressed, run.parse_errors)
+    } else {
+        let total = runs.len();
+        let suppressed = runs.iter().map(|r| r.suppressed).max().unwrap_or(0);
+        let parse_errors = runs
+            .iter()
+            .flat_map(|r| {
+                r.parse_errors
+                    .iter()
+                    .map(move |e| format!("{}: {}", r.model, e))
+            })
+            .collect();
+        let (completed, failed): (Vec<ModelRun>, Vec<ModelRun>) = runs
+            .into_iter()
+            .partition(|r| r.status() == ReviewerStatus::Completed);
+        let status = match failed.first() {
+            None => ReviewerStatus::Completed,
+            Some(run) if completed.is_empty() => run.status(),
+            Some(_) => ReviewerStatus::Failed {
+                error: format!(
+                    "{} of {} models failed; consensus from the rest",
+                    failed.len(),
+                    total
+                ),
+            },
+        };
+        let findings = merge_votes(
+            completed
+                .into_iter()
+                .map(|r| (r.model, r.findings))
+                .collect(),
+            reviewer.min_votes,
+        );
+        (status, findings, suppressed, parse_errors)
+    };
+    calibration.apply_caps(&mut findings, &reviewer.id);
+
+    Ok(ReviewerResult {
+        reviewer_id: reviewer.id.clone(),
+        reviewer_name: reviewer.name.clone(),
+        status,
+        files_scanned: files.len(),
+        findings,
+        suppressed,
+        parse_errors,
+        duration: start.elapsed(),
+    })
+}
+
+/// One model's pass over a reviewer's files
+struct ModelRun {
+    model: String,
+    findings: Vec<Finding>,
+    suppressed: usize,
+    parse_errors: Vec<String>,
+    invalid_output: bool,
+    chunk_successes: usize,
+    chunk_failures: usize,
+    last_error: Option<String>,
+}
+
+impl ModelRun {
+    fn status(&self) -> ReviewerStatus {
+        if self.chunk_successes == 0 {
+            ReviewerStatus::Failed {
+                error: self
+                    .last_error
+                    .clone()
+                    .unwrap_or_else(|| "all chunks failed".to_string()),
+            }
+        } else if self.chunk_failures > 0 {
+            ReviewerStatus::Failed {
+                error: format!(
+                    "{} of {} chunks failed; partial results returned",
+                    self.chunk_failures,
+                    self.chunk_failures + self.chunk_successes
+                ),
+            }
+        } else if self.invalid_output {
+            ReviewerStatus::InvalidOutput {
+                error: self
+                    .parse_errors
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "unparseable output".to_string()),
+            }
+        } else {
+            ReviewerStatus::Completed
+        }
+    }
+}
+
+/// Run every chunk through one provider/model and collect its findings
+async fn review_with_model(
+    config: &Config,
+    reviewer: &Reviewer,
+    provider: Provider,
+    model: &str,
+    prompt: &str,
+    chunks: &[Vec<PathBuf>],
+    suppressions: &Suppressions,
+) -> ModelRun {
+    let runner = create_runner(config, provider, model);
+    let timeout = Duration::from_secs(reviewer.timeout_sec.unwrap_or(config.timeout_sec));
+    let total_chunks = chunks.len();
+
     let mut all_findings: Vec<Finding> = Vec::new();
+    let mut suppressed_count = 0usize;
+    let mut parse_errors: Vec<String> = Vec::new();
+    let mut invalid_output = false;
     let mut chunk_successes = 0usize;
     let mut chunk_failures = 0usize;
     let mut last_error: Option<String> = None;
-    let files_scanned = files.len();
 
-    // Session ID for multi-chunk runs
+    // Session ID for multi-chunk runs and output repair
     // Claude: generate one to enable --session-id/--resume
     // Codex: will be filled from provider output after first chunk
-    let mut session_id: Option<String> =
-        if reviewer.provider == crate::config::Provider::ClaudeCli && total_chunks > 1 {
-            Some(Uuid::new_v4().to_string())
-        } else {
-            None
-        };
+    let wants_session = total_chunks > 1 || config.repair_attempts > 0;
+    let mut session_id: Option<String> = if provider == Provider::ClaudeCli && wants_session {
+        Some(Uuid::new_v4().to_string())
+    } else {
+        None
+    };
 
     // Execute each chunk with retries
     for (chunk_idx, chunk) in chunks.iter().enumerate() {
@@ -171,7 +388,7 @@ pub async fn execute_reviewer(
         );
 
         // Build chunk-aware prompt
-        let chunk_prompt = build_chunk_prompt(&prompt, chunk_idx, total_chunks, chunk);
+        let chunk_prompt = build_chunk_prompt(prompt, chunk_idx, total_chunks, chunk);
 
         // Build session info for this chunk
         let session_info = session_id.as_ref().map(|sid| SessionInfo {
@@ -219,21 +436,58 @@ pub async fn execute_reviewer(
 
                 // Only parse findings from the final chunk (or single chunk)
                 if chunk_idx + 1 == total_chunks {
-                    let mut findings =
-                        parse_findings(&output.stdout, &reviewer.id, reviewer.priority_default);
-                    // Set the model on each finding based on provider
-                    let model = match reviewer.provider {
-                        crate::config::Provider::ClaudeCli => {
-                            Some(config.providers.claude_cli.model.clone())
-                        }
-                        crate::config::Provider::CodexCli => {
-                            Some(config.providers.codex_cli.model.clone())
-                        }
-                    };
+                    let outcome = parse_with_repair(
+                        config,
+                        reviewer,
+                        &runner,
+                        &output.stdout,
+                        session_id.as_deref(),
+                        timeout,
+                    )
+                    .await;
+                    invalid_output = !outcome.recognized;
+                    parse_errors = outcome.errors;
+                    let mut findings = outcome.findings;
+
                     for finding in &mut findings {
-                        finding.model = model.clone();
+                        finding.model = Some(model.to_string());
                     }
-                    all_findings.extend(findings);
+
+                    // Check reported file/line/snippet against the source
+                    let (mut findings, unlocated) =
+                        check_locations(findings, &config.target, config.location_check);
+                    if unlocated > 0 {
+                        warn!(
+                            "Reviewer {} dropped {} findings that couldn't be located in the source",
+                            reviewer.id, unlocated
+                        );
+                    }
+
+                    // Record enclosing definitions so fingerprints survive line shifts
+                    attach_anchors(&mut findings, &config.target);
+
+                    // Mark which suggested patches still apply to the working tree
+                    check_patches(&mut findings, &config.target);
+
+                    let (kept, suppressed) =
+                        suppressions.apply(findings, &reviewer.id, &config.target);
+                    for (finding, source) in &suppressed {
+                        let (kind, reason) = match source {
+                            SuppressionSource::Inline { reason } => ("inline comment", reason),
+                            SuppressionSource::File { reason } => ("suppressions file", reason),
+                        };
+                        debug!(
+                            "Reviewer {} suppressed {} at {}:{} ({}: {})",
+                            reviewer.id,
+                            finding.id,
+                            finding.file.display(),
+                            finding.line,
+                            kind,
+                            reason
+                        );
+                    }
+                    suppressed_count += suppressed.len();
+                    all_findings.extend(kept);
                 } else {
                     debug!(
                         "Reviewer {} chunk {} acknowledged: {}",
@@ -265,28 +519,14 @@ pub async fn execute_reviewer(
         }
     }
 
-    let status = if chunk_successes == 0 {
-        ReviewerStatus::Failed {
-            error: last_error.unwrap_or_else(|| "all chunks failed".to_string()),
-        }
-    } else if chunk_failures > 0 {
-        ReviewerStatus::Failed {
-            error: format!(
-                "{} of {} chunks failed; partial results returned",
-                chunk_failures,
-                chunk_failures + chunk_successes
-            ),
-        }
-    } else {
-        ReviewerStatus::Completed
-    };
-
-    Ok(ReviewerResult {
-        reviewer_id: reviewer.id.clone(),
-        reviewer_name: reviewer.name.clone(),
-        status,
-        files_scanned,
+    ModelRun {
+        model: model.to_string(),
         findings: all_findings,
-        duration: start.elapsed(),
-    })
+        suppressed: suppressed_count,
+        parse_errors,
+        invalid_output,
+        chunk_successes,
+        chunk_failures,
+        last_error,
+    }
 }
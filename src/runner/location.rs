@@ -0,0 +1,286 @@
+use crate::config::LocationCheck;
+use crate::parser::{Finding, LocationStatus};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// Check each finding's file, line and snippet against the source under `target`
+///
+/// Paths are normalized to be relative to `target`, lines are corrected to where
+/// the snippet actually is (or clamped to the file length), and `verified` is set.
+/// With `LocationCheck::Drop`, findings that can't be located are removed.
+/// Returns the kept findings and the number dropped.
+pub fn check_locations(
+    findings: Vec<Finding>,
+    target: &Path,
+    mode: LocationCheck,
+) -> (Vec<Finding>, usize) {
+    if mode == LocationCheck::Off {
+        return (findings, 0);
+    }
+
+    let canonical_target = std::fs::canonicalize(target).ok();
+    let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut kept = Vec::with_capacity(findings.len());
+    let mut dropped = 0;
+
+    for mut finding in findings {
+        finding.file = normalize_path(&finding.file, target, canonical_target.as_deref());
+
+        let full_path = target.join(&finding.file);
+        // Paths that escape the target are never read
+        let status = if !is_within_target(&finding.file) || !full_path.is_file() {
+            LocationStatus::FileNotFound
+        } else {
+            let source = sources
+                .entry(finding.file.clone())
+                .or_insert_with(|| std::fs::read_to_string(&full_path).ok());
+            match source {
+                Some(source) => locate(&mut finding, source),
+                // Exists but isn't readable text; nothing more to check
+                None => LocationStatus::FileOnly,
+            }
+        };
+
+        debug!(
+            "Location {}:{} ({}) -> {}",
+            finding.file.display(),
+            finding.line,
+            finding.id,
+            status
+        );
+        finding.verified = Some(status);
+
+        if mode == LocationCheck::Drop && !status.is_located() {
+            dropped += 1;
+        } else {
+            kept.push(finding);
+        }
+    }
+
+    (kept, dropped)
+}
+
+/// Make a reported path relative to the target (strips `./` and absolute prefixes)
+fn normalize_path(file: &Path, target: &Path, canonical_target: Option<&Path>) -> PathBuf {
+    let relative = if file.is_absolute() {
+        canonical_target
+            .and_then(|t| file.strip_prefix(t).ok())
+            .or_else(|| file.strip_prefix(target).ok())
+            .unwrap_or(file)
+    } else {
+        file
+    };
+
+    relative
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// Whether a normalized path stays inside the target
+///
+/// Absolute paths (which `Path::join` would use as-is) and `..` components
+/// could point anywhere on disk.
+pub(crate) fn is_within_target(file: &Path) -> bool {
+    file.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Find the snippet in `source`, fixing up `finding.line`
+fn locate(finding: &mut Finding, source: &str) -> LocationStatus {
+    let lines: Vec<String> = source.lines().map(normalize).collect();
+    let line_count = lines.len() as u32;
+
+    let needles: Vec<String> = finding
+        .snippet
+        .as_deref()
+        .unwrap_or("")
+        .lines()
+        .map(normalize)
+        // Skip blank lines and elisions like "..." or "// ..."
+        .filter(|l| {
+            !l.trim_matches(|c| c == '.' || c == '/' || c == ' ')
+                .is_empty()
+        })
+        .collect();
+
+    let Some(first) = needles.first() else {
+        finding.line = finding.line.min(line_count);
+        return LocationStatus::FileOnly;
+    };
+
+    // 1-based line numbers where the snippet's first line appears
+    let candidates: Vec<u32> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.contains(first.as_str()))
+        .map(|(i, _)| i as u32 + 1)
+        .collect();
+
+    let reported = finding.line;
+    let span = needles.len() as u32;
+    let nearest = candidates
+        .iter()
+        .copied()
+        .min_by_key(|c| c.abs_diff(reported));
+
+    match nearest {
+        // Reported line falls inside the snippet's span
+        Some(start) if reported >= start && reported < start + span => LocationStatus::Verified,
+        Some(start) => {
+            // Move the whole range, keeping its length
+            finding.end_line = finding
+                .end_line
+                .map(|end| (end + start).saturating_sub(reported).max(start));
+            finding.line = start;
+            LocationStatus::LineCorrected
+        }
+        None => {
+            finding.line = finding.line.min(line_count);
+            LocationStatus::SnippetNotFound
+        }
+    }
+}
+
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+
+    fn finding(file: &str, line: u32, snippet: Option<&str>) -> Finding {
+        Finding {
+            id: "T-1".to_string(),
+            finding_type: "test".to_string(),
+            title: "Test".to_string(),
+            priority: Priority::P1,
+            file: PathBuf::from(file),
+            line,
+            snippet: snippet.map(String::from),
+            description: String::new(),
+            remediation: String::new(),
+            acceptance_criteria: vec![],
+            references: vec![],
+            model: None,
+            ..Default::default()
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/db.py"),
+            "import os\n\ndef load(id):\n    q = f\"SELECT {id}\"\n    return run(q)\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_verified_and_corrected() {
+        let dir = fixture();
+        let (kept, dropped) = check_locations(
+            vec![
+                finding("src/db.py", 4, Some("q = f\"SELECT {id}\"")),
+                finding("./src/db.py", 1, Some("  return   run(q)")),
+                Finding {
+                    end_line: Some(2),
+                    ..finding(
+                        "src/db.py",
+                        1,
+                        Some("def load(id):\n    q = f\"SELECT {id}\""),
+                    )
+                },
+            ],
+            dir.path(),
+            LocationCheck::Flag,
+        );
+
+        assert_eq!(dropped, 0);
+        assert_eq!(kept[0].verified, Some(LocationStatus::Verified));
+        assert_eq!(kept[1].verified, Some(LocationStatus::LineCorrected));
+        assert_eq!(kept[1].line, 5);
+        assert_eq!(kept[1].file, PathBuf::from("src/db.py"));
+        assert_eq!(kept[2].verified, Some(LocationStatus::LineCorrected));
+        assert_eq!((kept[2].line, kept[2].end_line), (3, Some(4)));
+    }
+
+    #[test]
+    fn test_clamps_line_past_eof() {
+        let dir = fixture();
+        let (kept, _) = check_locations(
+            vec![finding("src/db.py", 500, None)],
+            dir.path(),
+            LocationCheck::Flag,
+        );
+
+        assert_eq!(kept[0].verified, Some(LocationStatus::FileOnly));
+        assert_eq!(kept[0].line, 5);
+    }
+
+    #[test]
+    fn test_flag_keeps_and_drop_removes_unlocated() {
+        let dir = fixture();
+        let unlocated = || {
+            vec![
+                finding("src/missing.py", 1, None),
+                finding("src/db.py", 2, Some("eval(user_input)")),
+            ]
+        };
+
+        let (kept, dropped) = check_locations(unlocated(), dir.path(), LocationCheck::Flag);
+        assert_eq!(dropped, 0);
+        assert_eq!(kept[0].verified, Some(LocationStatus::FileNotFound));
+        assert_eq!(kept[1].verified, Some(LocationStatus::SnippetNotFound));
+
+        let (kept, dropped) = check_locations(unlocated(), dir.path(), LocationCheck::Drop);
+        assert!(kept.is_empty());
+        assert_eq!(dropped, 2);
+    }
+
+    #[test]
+    fn test_absolute_path_under_target() {
+        let dir = fixture();
+        let abs = std::fs::canonicalize(dir.path()).unwrap().join("src/db.py");
+        let (kept, _) = check_locations(
+            vec![finding(abs.to_str().unwrap(), 3, Some("def load(id):"))],
+            dir.path(),
+            LocationCheck::Flag,
+        );
+
+        assert_eq!(kept[0].file, PathBuf::from("src/db.py"));
+        assert_eq!(kept[0].verified, Some(LocationStatus::Verified));
+    }
+
+    #[test]
+    fn test_paths_outside_target_not_found() {
+        let dir = fixture();
+        let outside = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(outside.path(), "secret\n").unwrap();
+        let escaping = format!(
+            "../{}",
+            outside.path().file_name().unwrap().to_str().unwrap()
+        );
+        let target = dir.path().join("src");
+
+        let (kept, _) = check_locations(
+            vec![
+                finding(outside.path().to_str().unwrap(), 1, Some("secret")),
+                finding(&escaping, 1, Some("secret")),
+                finding("../src/db.py", 1, None),
+            ],
+            &target,
+            LocationCheck::Flag,
+        );
+
+        assert!(kept
+            .iter()
+            .all(|f| f.verified == Some(LocationStatus::FileNotFound)));
+        assert_eq!(kept[0].file, outside.path());
+    }
+}
@@ -1,5 +1,10 @@
+mod consensus;
 mod executor;
+mod location;
 mod orchestrator;
 mod retry;
 
-pub use orchestrator::{Orchestrator, ReviewerResult, ReviewerStatus, RunOptions, RunReport};
+pub(crate) use location::is_within_target;
+pub use orchestrator::{
+    FindingCounts, Orchestrator, ReviewerResult, ReviewerStatus, RunOptions, RunReport,
+};
@@ -1,8 +1,10 @@
+use crate::calibration::Calibration;
 use crate::config::Config;
 use crate::error::RunnerError;
-use crate::output::write_reviewer_report;
+use crate::output::{write_reviewer_report, Permalinks, SourceRevision};
 use crate::parser::Finding;
 use crate::state::State;
+use crate::suppression::Suppressions;
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::path::Path;
 use std::sync::Arc;
@@ -27,6 +29,8 @@ pub struct RunOptions {
 pub struct RunReport {
     pub reviewer_results: Vec<ReviewerResult>,
     pub total_duration: Duration,
+    /// Commit of the target when the run started
+    pub revision: Option<SourceRevision>,
 }
 
 impl RunReport {
@@ -48,6 +52,7 @@ impl RunReport {
                 .iter()
                 .filter(|f| f.priority == crate::config::Priority::P2)
                 .count();
+            counts.suppressed += result.suppressed;
         }
         counts
     }
@@ -58,6 +63,7 @@ pub struct FindingCounts {
     pub p0: usize,
     pub p1: usize,
     pub p2: usize,
+    pub suppressed: usize,
 }
 
 #[derive(Debug)]
@@ -67,6 +73,10 @@ pub struct ReviewerResult {
     pub status: ReviewerStatus,
     pub files_scanned: usize,
     pub findings: Vec<Finding>,
+    /// Findings dropped by inline comments or the suppressions file
+    pub suppressed: usize,
+    /// Validation errors for reviewer output items that were discarded
+    pub parse_errors: Vec<String>,
     pub duration: Duration,
 }
 
@@ -77,6 +87,8 @@ pub enum ReviewerStatus {
     Skipped { reason: String },
     TimedOut, // Future: when timeout is hit
     Failed { error: String },
+    /// Provider ran but its output couldn't be parsed into findings
+    InvalidOutput { error: String },
 }
 
 impl std::fmt::Display for ReviewerStatus {
@@ -86,6 +98,7 @@ impl std::fmt::Display for ReviewerStatus {
             ReviewerStatus::Skipped { reason } => write!(f, "skipped: {}", reason),
             ReviewerStatus::TimedOut => write!(f, "timed_out"),
             ReviewerStatus::Failed { error } => write!(f, "failed: {}", error),
+            ReviewerStatus::InvalidOutput { error } => write!(f, "invalid_output: {}", error),
         }
     }
 }
@@ -109,6 +122,18 @@ impl Orchestrator {
     ) -> Result<RunReport, RunnerError> {
         let start = std::time::Instant::now();
 
+        // Pin report links to the commit being reviewed
+        let revision = SourceRevision::capture(&self.config.target);
+        if let Some(rev) = revision.as_ref().filter(|r| r.dirty) {
+            warn!(
+                "Target has uncommitted changes; links to {} may not match the reviewed lines",
+                rev.sha
+            );
+        }
+        let permalinks = revision
+            .as_ref()
+            .and_then(|r| Permalinks::from_config(&self.config, None, r));
+
         // Filter reviewers based on options
         let all_reviewers: Vec<_> = self
             .config
@@ -150,6 +175,8 @@ impl Orchestrator {
                     },
                     files_scanned: 0,
                     findings: Vec::new(),
+                    suppressed: 0,
+                    parse_errors: Vec::new(),
                     duration: Duration::ZERO,
                 });
             } else {
@@ -168,6 +195,7 @@ impl Orchestrator {
             return Ok(RunReport {
                 reviewer_results: skipped_results,
                 total_duration: start.elapsed(),
+                revision,
             });
         }
 
@@ -177,6 +205,25 @@ impl Orchestrator {
             self.config.concurrency
         );
 
+        let suppressions = match Suppressions::load(&self.config.target) {
+            Ok(s) => Arc::new(s),
+            Err(e) => {
+                warn!("Failed to load suppressions, none will be applied: {}", e);
+                Arc::new(Suppressions::default())
+            }
+        };
+
+        let calibration = match Calibration::from_config(&self.config.calibration) {
+            Ok(c) => Arc::new(c),
+            Err(e) => {
+                warn!(
+                    "Invalid calibration, priorities will not be adjusted: {}",
+                    e
+                );
+                Arc::new(Calibration::default())
+            }
+        };
+
         let mut futures = FuturesUnordered::new();
         let launch_delay = Duration::from_millis(self.config.launch_delay_ms);
 
@@ -189,10 +236,19 @@ impl Orchestrator {
             let permit = self.semaphore.clone().acquire_owned().await?;
             let config = self.config.clone();
             let diff_base = options.diff_base.clone();
+            let suppressions = suppressions.clone();
+            let calibration = calibration.clone();
 
             futures.push(tokio::spawn(async move {
                 let _permit = permit; // hold until done
-                execute_reviewer(&config, &reviewer, diff_base.as_deref()).await
+                execute_reviewer(
+                    &config,
+                    &reviewer,
+                    diff_base.as_deref(),
+                    &suppressions,
+                    &calibration,
+                )
+                .await
             }));
         }
 
@@ -208,7 +264,7 @@ impl Orchestrator {
                     );
 
                     // Write report immediately (streaming mode)
-                    if let Err(e) = write_reviewer_report(report_dir, &report) {
+                    if let Err(e) = write_reviewer_report(report_dir, &report, permalinks.as_ref()) {
                         warn!("Failed to write report for {}: {}", report.reviewer_id, e);
                     } else {
                         info!(
@@ -232,6 +288,7 @@ impl Orchestrator {
         Ok(RunReport {
             reviewer_results: results,
             total_duration: start.elapsed(),
+            revision,
         })
     }
 }
@@ -0,0 +1,397 @@
+//! Suppressions: permanently silence known false positives
+//!
+//! Two sources are supported:
+//! 1. Inline comments on the flagged line or the line above:
+//!    `polyrev-ignore[: <finding_type|reviewer_id>] <reason>`
+//! 2. `.polyrev/suppressions.yaml`, matching by fingerprint, path glob,
+//!    finding type and/or reviewer, with a reason and optional expiry date
+
+use crate::error::ConfigError;
+use crate::parser::Finding;
+use crate::runner::is_within_target;
+use chrono::{Local, NaiveDate};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::warn;
+
+const SUPPRESSIONS_DIR: &str = ".polyrev";
+const SUPPRESSIONS_FILE: &str = "suppressions.yaml";
+
+/// On-disk format of `.polyrev/suppressions.yaml`
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SuppressionsFile {
+    #[serde(default)]
+    pub suppressions: Vec<SuppressionEntry>,
+}
+
+/// A single suppression rule; every field that is set must match
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SuppressionEntry {
+    /// Finding fingerprint (current or legacy scheme)
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+
+    /// Glob matched against the finding's file path
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Finding type (e.g. "sql-injection")
+    #[serde(default, rename = "type", alias = "finding_type")]
+    pub finding_type: Option<String>,
+
+    /// Reviewer id
+    #[serde(default)]
+    pub reviewer: Option<String>,
+
+    /// Why this is suppressed
+    pub reason: String,
+
+    /// Last day (inclusive) the suppression applies
+    #[serde(default)]
+    pub expires: Option<NaiveDate>,
+}
+
+/// Why a finding was suppressed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuppressionSource {
+    /// `polyrev-ignore` comment in the source file
+    Inline { reason: String },
+    /// Entry in the suppressions file
+    File { reason: String },
+}
+
+struct CompiledEntry {
+    entry: SuppressionEntry,
+    path: Option<GlobMatcher>,
+}
+
+/// Loaded suppression rules (expired entries already dropped)
+#[derive(Default)]
+pub struct Suppressions {
+    entries: Vec<CompiledEntry>,
+}
+
+impl Suppressions {
+    /// Load `.polyrev/suppressions.yaml` from the target directory
+    ///
+    /// A missing file yields no suppressions. Expired entries are reported
+    /// with a warning and ignored.
+    pub fn load(target: &Path) -> Result<Self, ConfigError> {
+        let path = target.join(SUPPRESSIONS_DIR).join(SUPPRESSIONS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::ReadFile {
+            path: path.clone(),
+            source: e,
+        })?;
+        let file: SuppressionsFile = serde_yaml::from_str(&content)?;
+
+        Self::from_entries(file.suppressions, Local::now().date_naive())
+    }
+
+    fn from_entries(entries: Vec<SuppressionEntry>, today: NaiveDate) -> Result<Self, ConfigError> {
+        let mut compiled = Vec::new();
+
+        for entry in entries {
+            if entry.fingerprint.is_none()
+                && entry.path.is_none()
+                && entry.finding_type.is_none()
+                && entry.reviewer.is_none()
+            {
+                warn!(
+                    "Ignoring suppression with no fingerprint, path, type or reviewer: {}",
+                    entry.reason
+                );
+                continue;
+            }
+
+            if let Some(expires) = entry.expires {
+                if expires < today {
+                    warn!(
+                        "Suppression expired on {} and is no longer applied: {}",
+                        expires, entry.reason
+                    );
+                    continue;
+                }
+            }
+
+            let path = match &entry.path {
+                Some(pattern) => Some(
+                    Glob::new(pattern)
+                        .map_err(|e| {
+                            ConfigError::InvalidSuppression(format!(
+                                "bad path glob '{}': {}",
+                                pattern, e
+                            ))
+                        })?
+                        .compile_matcher(),
+                ),
+                None => None,
+            };
+
+            compiled.push(CompiledEntry { entry, path });
+        }
+
+        Ok(Self { entries: compiled })
+    }
+
+    /// Find the first file rule matching this finding
+    fn match_file(&self, finding: &Finding, reviewer_id: &str) -> Option<&SuppressionEntry> {
+        let fingerprint = finding.fingerprint(reviewer_id);
+        let legacy_fingerprint = finding.legacy_fingerprint(reviewer_id);
+
+        self.entries
+            .iter()
+            .find(|c| {
+                let e = &c.entry;
+                e.fingerprint
+                    .as_deref()
+                    .map(|fp| fp == fingerprint || fp == legacy_fingerprint)
+                    .unwrap_or(true)
+                    && c.path
+                        .as_ref()
+                        .map(|m| m.is_match(&finding.file))
+                        .unwrap_or(true)
+                    && e.finding_type
+                        .as_deref()
+                        .map(|t| t == finding.finding_type)
+                        .unwrap_or(true)
+                    && e.reviewer
+                        .as_deref()
+                        .map(|r| r == reviewer_id)
+                        .unwrap_or(true)
+            })
+            .map(|c| &c.entry)
+    }
+
+    /// Split findings into (kept, suppressed), reading sources under `target`
+    /// for inline `polyrev-ignore` comments
+    pub fn apply(
+        &self,
+        findings: Vec<Finding>,
+        reviewer_id: &str,
+        target: &Path,
+    ) -> (Vec<Finding>, Vec<(Finding, SuppressionSource)>) {
+        let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+        let mut kept = Vec::new();
+        let mut suppressed = Vec::new();
+
+        for finding in findings {
+            if let Some(entry) = self.match_file(&finding, reviewer_id) {
+                let reason = entry.reason.clone();
+                suppressed.push((finding, SuppressionSource::File { reason }));
+                continue;
+            }
+
+            // Only files inside the target can carry inline suppressions
+            let source = sources.entry(finding.file.clone()).or_insert_with(|| {
+                is_within_target(&finding.file)
+                    .then(|| std::fs::read_to_string(target.join(&finding.file)).ok())
+                    .flatten()
+            });
+
+            let inline = source
+                .as_deref()
+                .and_then(|s| find_inline_suppression(s, &finding, reviewer_id));
+
+            match inline {
+                Some(reason) => suppressed.push((finding, SuppressionSource::Inline { reason })),
+                None => kept.push(finding),
+            }
+        }
+
+        (kept, suppressed)
+    }
+}
+
+/// Look for a `polyrev-ignore` comment on the finding's line or the line above
+///
+/// Returns the reason if a comment applies to this finding.
+fn find_inline_suppression(source: &str, finding: &Finding, reviewer_id: &str) -> Option<String> {
+    if finding.line == 0 {
+        return None;
+    }
+
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"polyrev-ignore(?::\s*([\w./-]+))?\s*(.*)$")
+            .expect("suppression regex is valid")
+    });
+    let lines: Vec<&str> = source.lines().collect();
+    let idx = finding.line as usize - 1;
+
+    [Some(idx), idx.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| lines.get(i))
+        .filter_map(|l| re.captures(l))
+        .find(|caps| match caps.get(1) {
+            Some(t) => t.as_str() == finding.finding_type || t.as_str() == reviewer_id,
+            None => true,
+        })
+        .map(|caps| {
+            let reason = caps
+                .get(2)
+                .map(|r| {
+                    r.as_str()
+                        .trim()
+                        .trim_end_matches("*/")
+                        .trim_end_matches("-->")
+                        .trim()
+                })
+                .unwrap_or("");
+            if reason.is_empty() {
+                "no reason given".to_string()
+            } else {
+                reason.to_string()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+
+    fn finding(file: &str, line: u32, finding_type: &str) -> Finding {
+        Finding {
+            id: "T-1".to_string(),
+            finding_type: finding_type.to_string(),
+            title: "Test".to_string(),
+            priority: Priority::P1,
+            file: PathBuf::from(file),
+            line,
+            snippet: None,
+            description: String::new(),
+            remediation: String::new(),
+            acceptance_criteria: vec![],
+            references: vec![],
+            model: None,
+            ..Default::default()
+        }
+    }
+
+    fn entry(path: Option<&str>, finding_type: Option<&str>) -> SuppressionEntry {
+        SuppressionEntry {
+            fingerprint: None,
+            path: path.map(String::from),
+            finding_type: finding_type.map(String::from),
+            reviewer: None,
+            reason: "test".to_string(),
+            expires: None,
+        }
+    }
+
+    #[test]
+    fn test_inline_same_line_and_line_above() {
+        let src = "a = 1\n# polyrev-ignore: sql-injection sanitized upstream\nquery(a)\nexec(b)  # polyrev-ignore trusted input\n";
+
+        let above = finding("x.py", 3, "sql-injection");
+        assert_eq!(
+            find_inline_suppression(src, &above, "sec").as_deref(),
+            Some("sanitized upstream")
+        );
+
+        let same = finding("x.py", 4, "command-injection");
+        assert_eq!(
+            find_inline_suppression(src, &same, "sec").as_deref(),
+            Some("trusted input")
+        );
+    }
+
+    #[test]
+    fn test_inline_target_must_match() {
+        let src = "// polyrev-ignore: hardcoded-secret test key\nlet k = \"abc\";\n";
+
+        let other_type = finding("x.rs", 2, "sql-injection");
+        assert_eq!(find_inline_suppression(src, &other_type, "sec"), None);
+
+        let by_reviewer = finding("x.rs", 2, "sql-injection");
+        let src = "// polyrev-ignore: sec not applicable\nlet k = \"abc\";\n";
+        assert!(find_inline_suppression(src, &by_reviewer, "sec").is_some());
+    }
+
+    #[test]
+    fn test_file_rule_path_and_type() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let s = Suppressions::from_entries(
+            vec![entry(Some("tests/**"), Some("hardcoded-secret"))],
+            today,
+        )
+        .unwrap();
+
+        let (kept, suppressed) = s.apply(
+            vec![
+                finding("tests/fixtures/keys.py", 3, "hardcoded-secret"),
+                finding("src/keys.py", 3, "hardcoded-secret"),
+                finding("tests/fixtures/keys.py", 3, "sql-injection"),
+            ],
+            "sec",
+            Path::new("/nonexistent"),
+        );
+
+        assert_eq!(kept.len(), 2);
+        assert_eq!(suppressed.len(), 1);
+        assert_eq!(
+            suppressed[0].0.file,
+            PathBuf::from("tests/fixtures/keys.py")
+        );
+    }
+
+    #[test]
+    fn test_inline_ignores_files_outside_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        let src = "query(a)  # polyrev-ignore trusted input\n";
+        std::fs::write(dir.path().join("outside.py"), src).unwrap();
+        std::fs::write(target.join("inside.py"), src).unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let s = Suppressions::from_entries(vec![], today).unwrap();
+        let (kept, suppressed) = s.apply(
+            vec![
+                finding("inside.py", 1, "x"),
+                finding("../outside.py", 1, "x"),
+            ],
+            "sec",
+            &target,
+        );
+        assert_eq!(suppressed.len(), 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].file, PathBuf::from("../outside.py"));
+    }
+
+    #[test]
+    fn test_file_rule_fingerprint_matches_legacy() {
+        let f = finding("src/db.py", 42, "sql-injection");
+        let mut e = entry(None, None);
+        e.fingerprint = Some(f.legacy_fingerprint("sec"));
+
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let s = Suppressions::from_entries(vec![e], today).unwrap();
+        assert!(s.match_file(&f, "sec").is_some());
+        assert!(s.match_file(&f, "other").is_none());
+    }
+
+    #[test]
+    fn test_expired_and_empty_entries_dropped() {
+        let mut expired = entry(Some("**"), None);
+        expired.expires = NaiveDate::from_ymd_opt(2025, 12, 31);
+        let mut current = entry(Some("src/**"), None);
+        current.expires = NaiveDate::from_ymd_opt(2026, 1, 1);
+
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let s =
+            Suppressions::from_entries(vec![expired, entry(None, None), current], today).unwrap();
+
+        assert_eq!(s.entries.len(), 1);
+        assert_eq!(s.entries[0].entry.path.as_deref(), Some("src/**"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Cluster-level issues from postprocess output
+//!
+//! The reducer groups related findings into `FindingCluster`s by the
+//! fingerprints of the raw findings. A reduced finding belongs to a cluster
+//! when any of its `merged_from` fingerprints (or its id) is listed there.
+
+use crate::postprocess::FindingCluster;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// A cluster resolved against the reduced findings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterGroup {
+    pub name: String,
+    pub rationale: String,
+    /// Stable fingerprint of the cluster issue
+    pub fingerprint: String,
+    /// Indexes into the reduced findings, in report order
+    pub members: Vec<usize>,
+}
+
+/// Identity of a reduced finding as far as clusters are concerned
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClusterKey {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub merged_from: Vec<String>,
+}
+
+/// Fingerprint of a cluster issue, independent of member order
+pub fn cluster_fingerprint(fingerprints: &[String]) -> String {
+    let mut sorted: Vec<&str> = fingerprints.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let hash = Sha256::digest(format!("cluster|{}", sorted.join(",")).as_bytes());
+    format!("{:x}", hash)[..12].to_string()
+}
+
+/// Resolve clusters to reduced findings
+///
+/// A finding listed by several clusters goes to the first one. Clusters with
+/// fewer than two members are dropped: their findings are filed on their own.
+pub fn group_findings(clusters: &[FindingCluster], findings: &[ClusterKey]) -> Vec<ClusterGroup> {
+    let mut taken = HashSet::new();
+    let mut groups = Vec::new();
+
+    for cluster in clusters {
+        let listed: HashSet<&str> = cluster.fingerprints.iter().map(String::as_str).collect();
+        let members: Vec<usize> = findings
+            .iter()
+            .enumerate()
+            .filter(|(i, key)| {
+                !taken.contains(i)
+                    && (listed.contains(key.id.as_str())
+                        || key
+                            .merged_from
+                            .iter()
+                            .any(|fp| listed.contains(fp.as_str())))
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        if members.len() < 2 {
+            continue;
+        }
+        taken.extend(members.iter().copied());
+        groups.push(ClusterGroup {
+            name: cluster.name.clone(),
+            rationale: cluster.rationale.clone(),
+            fingerprint: cluster_fingerprint(&cluster.fingerprints),
+            members,
+        });
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cluster(name: &str, fingerprints: &[&str]) -> FindingCluster {
+        FindingCluster {
+            name: name.to_string(),
+            fingerprints: fingerprints.iter().map(|s| s.to_string()).collect(),
+            rationale: "same root cause".to_string(),
+        }
+    }
+
+    fn key(id: &str, merged_from: &[&str]) -> ClusterKey {
+        ClusterKey {
+            id: id.to_string(),
+            merged_from: merged_from.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_group_findings_by_merged_from() {
+        let findings = vec![
+            key("SEC-001", &["aaa", "bbb"]),
+            key("SEC-002", &["ccc"]),
+            key("SEC-003", &["ddd"]),
+            key("PERF-001", &["eee"]),
+        ];
+        let clusters = vec![
+            cluster("Unescaped SQL", &["bbb", "ddd"]),
+            // SEC-003 is already taken; a single remaining member isn't a cluster
+            cluster("Input handling", &["ddd", "eee"]),
+            // Clusters may name reduced ids instead of raw fingerprints
+            cluster("Misc", &["SEC-002", "PERF-001"]),
+        ];
+
+        let groups = group_findings(&clusters, &findings);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "Unescaped SQL");
+        assert_eq!(groups[0].members, vec![0, 2]);
+        assert_eq!(groups[1].members, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_cluster_fingerprint_ignores_order() {
+        let a = cluster_fingerprint(&["bbb".to_string(), "aaa".to_string()]);
+        let b = cluster_fingerprint(&["aaa".to_string(), "bbb".to_string(), "aaa".to_string()]);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 12);
+        assert_ne!(a, cluster_fingerprint(&["aaa".to_string()]));
+    }
+}
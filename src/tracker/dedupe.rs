@@ -0,0 +1,50 @@
+use super::{IssueState, IssueTracker};
+use crate::error::TrackerError;
+use std::sync::Arc;
+
+pub struct DedupeChecker {
+    tracker: Arc<dyn IssueTracker>,
+}
+
+#[derive(Debug)]
+pub enum DedupeResult {
+    NotFound,
+    Found { issue: String, state: IssueState },
+}
+
+impl DedupeChecker {
+    pub fn new(tracker: Arc<dyn IssueTracker>) -> Self {
+        Self { tracker }
+    }
+
+    /// Check fingerprints in order (current scheme first, then legacy keys)
+    /// and return the first existing issue
+    pub async fn check_any(&self, fingerprints: &[&str]) -> Result<DedupeResult, TrackerError> {
+        for fingerprint in fingerprints {
+            if let found @ DedupeResult::Found { .. } = self.check(fingerprint).await? {
+                return Ok(found);
+            }
+        }
+        Ok(DedupeResult::NotFound)
+    }
+
+    /// Check if an issue with this fingerprint already exists
+    ///
+    /// When several issues carry the fingerprint, an open one is preferred.
+    pub async fn check(&self, fingerprint: &str) -> Result<DedupeResult, TrackerError> {
+        let issues = self.tracker.find_by_fingerprint(fingerprint).await?;
+
+        let best = issues
+            .iter()
+            .find(|i| i.state == IssueState::Open)
+            .or_else(|| issues.first());
+
+        match best {
+            None => Ok(DedupeResult::NotFound),
+            Some(issue) => Ok(DedupeResult::Found {
+                issue: issue.id.clone(),
+                state: issue.state.clone(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use super::cluster::ClusterGroup;
+use super::dedupe::{DedupeChecker, DedupeResult};
+use super::policy::IssueRouting;
+use super::template::{AutoFixContext, ClusterContext, IssueTemplate, TemplateContext};
+use super::{IssueState, IssueTracker, NewIssue};
+use crate::config::AutoFixConfig;
+use crate::error::TrackerError;
+use crate::output::{markdown_location, Permalinks};
+use crate::parser::Finding;
+use std::sync::Arc;
+use tracing::debug;
+
+#[derive(Debug)]
+#[allow(dead_code)]
+pub enum IssueResult {
+    Created { url: String, agent_triggered: bool },
+    Skipped { issue: String },
+    Commented { issue: String },
+    Reopened { issue: String },
+}
+
+/// A finding filed as part of a cluster issue
+pub struct ClusterMember<'a> {
+    pub reviewer_id: &'a str,
+    pub finding: &'a Finding,
+    /// Child issue (URL or id) to link; inlined into the cluster issue when `None`
+    pub issue: Option<String>,
+}
+
+pub struct IssueCreator {
+    tracker: Arc<dyn IssueTracker>,
+    labels: Vec<String>,
+    assignees: Vec<String>,
+    dedupe: bool,
+    dedupe_action: crate::config::DedupeAction,
+    auto_fix: AutoFixConfig,
+    default_model: String,
+    template: Option<Arc<IssueTemplate>>,
+    /// Links to the reviewed commit
+    permalinks: Option<Permalinks>,
+}
+
+impl IssueCreator {
+    pub fn new(
+        tracker: Arc<dyn IssueTracker>,
+        dedupe: bool,
+        dedupe_action: crate::config::DedupeAction,
+        labels: Vec<String>,
+        assignees: Vec<String>,
+        auto_fix: AutoFixConfig,
+        default_model: String,
+    ) -> Result<Self, TrackerError> {
+        Ok(Self {
+            tracker,
+            labels,
+            assignees,
+            dedupe,
+            dedupe_action,
+            auto_fix,
+            default_model,
+            template: None,
+            permalinks: None,
+        })
+    }
+
+    /// Render titles and bodies with a user template instead of the built-in layout
+    pub fn with_template(mut self, template: Option<Arc<IssueTemplate>>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Link findings to the reviewed commit
+    pub fn with_permalinks(mut self, permalinks: Option<Permalinks>) -> Self {
+        self.permalinks = permalinks;
+        self
+    }
+
+    pub async fn create_or_update(
+        &self,
+        finding: &Finding,
+        reviewer_id: &str,
+        routing: &IssueRouting,
+        cluster: Option<&ClusterGroup>,
+    ) -> Result<IssueResult, TrackerError> {
+        if let Some(result) = self.update_only(finding, reviewer_id).await? {
+            return Ok(result);
+        }
+
+        // Create new issue (with @agent in body if auto_fix enabled)
+        let fingerprint = finding.fingerprint(reviewer_id);
+        let url = self
+            .create_new_issue(finding, reviewer_id, &fingerprint, routing, cluster)
+            .await?;
+
+        // auto_fix is triggered via @agent mention in issue body
+        let agent_triggered = self.auto_fix.enabled;
+
+        Ok(IssueResult::Created {
+            url,
+            agent_triggered,
+        })
+    }
+
+    /// Apply `dedupe_action` to the finding's existing issue, never opening a new one
+    ///
+    /// Returns `None` when there is no existing issue (or dedupe is off).
+    pub async fn update_only(
+        &self,
+        finding: &Finding,
+        reviewer_id: &str,
+    ) -> Result<Option<IssueResult>, TrackerError> {
+        let fingerprint = finding.fingerprint(reviewer_id);
+        let legacy_fingerprint = finding.legacy_fingerprint(reviewer_id);
+
+        let comment = || {
+            format!(
+                "Update for fingerprint {} ({} by {}):\n\n{}",
+                fingerprint,
+                finding.location(),
+                reviewer_id,
+                finding.description
+            )
+        };
+        self.update_existing(&[&fingerprint, &legacy_fingerprint], comment)
+            .await
+    }
+
+    /// Create (or dedupe) the issue for a cluster of related findings
+    pub async fn create_or_update_cluster(
+        &self,
+        cluster: &ClusterGroup,
+        members: &[ClusterMember<'_>],
+    ) -> Result<IssueResult, TrackerError> {
+        if let Some(result) = self.update_cluster_only(cluster, members).await? {
+            return Ok(result);
+        }
+
+        let priority = members
+            .iter()
+            .map(|m| m.finding.priority)
+            .min_by_key(|&p| p.rank())
+            .unwrap_or_default();
+
+        let mut labels = self.labels.clone();
+        labels.push(priority.to_string());
+        for member in members {
+            if !labels.iter().any(|l| l == member.reviewer_id) {
+                labels.push(member.reviewer_id.to_string());
+            }
+        }
+        labels.push("cluster".to_string());
+
+        // Linked children carry their own auto-fix mention; only a fully inlined cluster is handed to the agent
+        let inline = members.iter().all(|m| m.issue.is_none());
+        let issue = NewIssue {
+            title: format!(
+                "[{}] {} ({} findings)",
+                priority,
+                cluster.name,
+                members.len()
+            ),
+            body: self.format_cluster_body(cluster, members, inline),
+            labels,
+            assignees: self.assignees.clone(),
+            fingerprint: cluster.fingerprint.clone(),
+            priority,
+            milestone: None,
+        };
+        let url = self.tracker.create_issue(&issue).await?;
+
+        Ok(IssueResult::Created {
+            url,
+            agent_triggered: inline && self.auto_fix.enabled,
+        })
+    }
+
+    /// Apply `dedupe_action` to the cluster's existing issue, never opening a new one
+    ///
+    /// Returns `None` when there is no existing issue (or dedupe is off).
+    pub async fn update_cluster_only(
+        &self,
+        cluster: &ClusterGroup,
+        members: &[ClusterMember<'_>],
+    ) -> Result<Option<IssueResult>, TrackerError> {
+        let comment = || {
+            format!(
+                "Cluster still reported with {} finding{}:\n\n{}",
+                members.len(),
+                if members.len() == 1 { "" } else { "s" },
+                members
+                    .iter()
+                    .map(|m| format!("- {}", member_line(m, self.permalinks.as_ref())))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        };
+        self.update_existing(&[&cluster.fingerprint], comment).await
+    }
+
+    /// Apply `dedupe_action` to an existing issue with one of `fingerprints`
+    ///
+    /// Returns `None` when dedupe is off or no issue matches.
+    async fn update_existing(
+        &self,
+        fingerprints: &[&str],
+        comment: impl FnOnce() -> String,
+    ) -> Result<Option<IssueResult>, TrackerError> {
+        if !self.dedupe {
+            return Ok(None);
+        }
+
+        let checker = DedupeChecker::new(self.tracker.clone());
+        match checker.check_any(fingerprints).await? {
+            DedupeResult::Found { issue, state } => {
+                debug!(
+                    "Found existing issue {} (state: {:?}) for fingerprint {}",
+                    issue, state, fingerprints[0]
+                );
+                match self.dedupe_action {
+                    crate::config::DedupeAction::Skip => Ok(Some(IssueResult::Skipped { issue })),
+                    crate::config::DedupeAction::Comment => {
+                        self.tracker.comment_issue(&issue, &comment()).await?;
+                        Ok(Some(IssueResult::Commented { issue }))
+                    }
+                    crate::config::DedupeAction::Reopen => {
+                        if state == IssueState::Closed {
+                            self.tracker.reopen_issue(&issue).await?;
+                        }
+                        self.tracker.comment_issue(&issue, &comment()).await?;
+                        Ok(Some(IssueResult::Reopened { issue }))
+                    }
+                }
+            }
+            DedupeResult::NotFound => {
+                debug!(
+                    "No existing issue found for fingerprint {}",
+                    fingerprints[0]
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    async fn create_new_issue(
+        &self,
+        finding: &Finding,
+        reviewer_id: &str,
+        fingerprint: &str,
+        routing: &IssueRouting,
+        cluster: Option<&ClusterGroup>,
+    ) -> Result<String, TrackerError> {
+        let mut labels = self.labels.clone();
+
+        // Priority label
+        labels.push(finding.priority.to_string());
+
+        // Reviewer label
+        labels.push(reviewer_id.to_string());
+
+        // Model label (use finding's model if set, otherwise default)
+        let model_label = finding.model.as_deref().unwrap_or(&self.default_model);
+        labels.push(model_label.to_string());
+
+        // Reviewer policy labels
+        for label in &routing.labels {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+
+        // Reviewer override (or default) assignees, plus path owners
+        let mut assignees = routing
+            .assignees
+            .clone()
+            .unwrap_or_else(|| self.assignees.clone());
+        for owner in &routing.owners {
+            if !assignees.contains(owner) {
+                assignees.push(owner.clone());
+            }
+        }
+
+        let (title, body) = match &self.template {
+            Some(template) => {
+                let ctx = TemplateContext {
+                    finding,
+                    reviewer_id,
+                    fingerprint,
+                    model: model_label,
+                    location: finding.location(),
+                    permalink: self
+                        .permalinks
+                        .as_ref()
+                        .map(|p| p.link(&finding.file, finding.line, finding.end_line)),
+                    cluster: cluster.map(|c| ClusterContext {
+                        name: &c.name,
+                        rationale: &c.rationale,
+                        size: c.members.len(),
+                    }),
+                    auto_fix: self.auto_fix.enabled.then(|| AutoFixContext {
+                        agent: &self.auto_fix.agent,
+                        prompt: &self.auto_fix.prompt,
+                    }),
+                };
+                let mut body = match template.render_body(&ctx)? {
+                    Some(body) => body,
+                    None => self.format_body(finding, reviewer_id, fingerprint),
+                };
+                // The agent is only triggered by its mention, so keep it even if the template drops it
+                if self.auto_fix.enabled && !body.contains(&format!("@{}", self.auto_fix.agent)) {
+                    body.push_str(&self.auto_fix_section());
+                }
+                let title = template
+                    .render_title(&ctx)?
+                    .unwrap_or_else(|| format!("[{}] {}", finding.priority, finding.title));
+                (title, body)
+            }
+            None => (
+                format!("[{}] {}", finding.priority, finding.title),
+                self.format_body(finding, reviewer_id, fingerprint),
+            ),
+        };
+
+        let issue = NewIssue {
+            title,
+            body,
+            labels,
+            assignees,
+            fingerprint: fingerprint.to_string(),
+            priority: finding.priority,
+            milestone: routing.milestone.clone(),
+        };
+
+        self.tracker.create_issue(&issue).await
+    }
+
+    fn format_body(&self, finding: &Finding, reviewer_id: &str, fingerprint: &str) -> String {
+        let acceptance_criteria = if finding.acceptance_criteria.is_empty() {
+            "- [ ] Address finding\n- [ ] Add test coverage".to_string()
+        } else {
+            finding
+                .acceptance_criteria
+                .iter()
+                .map(|c| format!("- [ ] {}", c))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let references = if finding.references.is_empty() {
+            "N/A".to_string()
+        } else {
+            finding
+                .references
+                .iter()
+                .map(|r| format!("- {}", r))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let snippet = finding.snippet.as_deref().unwrap_or("N/A");
+
+        let location = markdown_location(
+            self.permalinks.as_ref(),
+            &finding.location(),
+            &finding.file,
+            finding.line,
+            finding.end_line,
+        );
+        let location = match (finding.column, finding.end_column) {
+            (Some(col), Some(end)) => format!("{} (col {}-{})", location, col, end),
+            (Some(col), None) => format!("{} (col {})", location, col),
+            _ => location,
+        };
+
+        // Optional rows for confidence, consensus and taxonomy tags
+        let mut extra_rows = String::new();
+        if let Some(confidence) = finding.confidence {
+            extra_rows.push_str(&format!("| **Confidence** | `{}` |\n", confidence));
+        }
+        if let Some(votes) = &finding.votes {
+            extra_rows.push_str(&format!("| **Votes** | {} |\n", votes));
+        }
+        for adjustment in &finding.calibration {
+            extra_rows.push_str(&format!("| **Calibrated** | {} |\n", adjustment));
+        }
+        if !finding.cwe.is_empty() {
+            extra_rows.push_str(&format!("| **CWE** | {} |\n", finding.cwe.join(", ")));
+        }
+        if !finding.owasp.is_empty() {
+            extra_rows.push_str(&format!("| **OWASP** | {} |\n", finding.owasp.join(", ")));
+        }
+
+        let related_section = if finding.related_locations.is_empty() {
+            String::new()
+        } else {
+            let items = finding
+                .related_locations
+                .iter()
+                .map(|r| {
+                    let location = markdown_location(
+                        self.permalinks.as_ref(),
+                        &r.location(),
+                        &r.file,
+                        r.line,
+                        r.end_line,
+                    );
+                    if r.message.is_empty() {
+                        format!("- {}", location)
+                    } else {
+                        format!("- {} — {}", location, r.message)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("\n## Related Locations\n\n{}\n", items)
+        };
+
+        let auto_fix_section = self.auto_fix_section();
+
+        format!(
+            r#"<!-- polyrev:fp:{fingerprint} -->
+
+## Description
+
+{description}
+
+## Location
+
+| Field | Value |
+|-------|-------|
+| **File** | {location} |
+| **Type** | `{finding_type}` |
+| **Reviewer** | `{reviewer_id}` |
+| **Priority** | `{priority}` |
+{extra_rows}{related_section}
+## Code
+
+```
+{snippet}
+```
+
+## Remediation
+
+{remediation}
+
+## Acceptance Criteria
+
+{acceptance_criteria}
+
+## References
+
+{references}
+{auto_fix_section}
+---
+*Generated by [polyrev](https://github.com/rk23/polyrev)*
+"#,
+            fingerprint = fingerprint,
+            description = finding.description,
+            location = location,
+            extra_rows = extra_rows,
+            related_section = related_section,
+            finding_type = if finding.finding_type.is_empty() {
+                "general"
+            } else {
+                &finding.finding_type
+            },
+            reviewer_id = reviewer_id,
+            priority = finding.priority,
+            snippet = snippet,
+            remediation = finding.remediation,
+            acceptance_criteria = acceptance_criteria,
+            references = references,
+            auto_fix_section = auto_fix_section,
+        )
+    }
+
+    /// Auto-fix section: include @agent mention to trigger GitHub Action
+    fn auto_fix_section(&self) -> String {
+        if self.auto_fix.enabled {
+            format!(
+                r#"
+
+## Auto-Fix
+
+@{agent} {prompt}
+"#,
+                agent = self.auto_fix.agent,
+                prompt = self.auto_fix.prompt,
+            )
+        } else {
+            String::new()
+        }
+    }
+
+    fn format_cluster_body(
+        &self,
+        cluster: &ClusterGroup,
+        members: &[ClusterMember<'_>],
+        inline: bool,
+    ) -> String {
+        let tasks = members
+            .iter()
+            .filter(|m| m.issue.is_some())
+            .map(|m| format!("- [ ] {}", member_line(m, self.permalinks.as_ref())))
+            .collect::<Vec<_>>();
+        let tasks_section = if tasks.is_empty() {
+            String::new()
+        } else {
+            format!("\n## Tasks\n\n{}\n", tasks.join("\n"))
+        };
+
+        let inlined = members
+            .iter()
+            .filter(|m| m.issue.is_none())
+            .map(|m| {
+                let f = m.finding;
+                format!(
+                    "### [{}] {}\n\n{} · reviewer `{}` · fingerprint `{}`\n\n{}\n\n**Remediation:** {}\n",
+                    f.priority,
+                    f.title,
+                    markdown_location(
+                        self.permalinks.as_ref(),
+                        &f.location(),
+                        &f.file,
+                        f.line,
+                        f.end_line
+                    ),
+                    m.reviewer_id,
+                    f.fingerprint(m.reviewer_id),
+                    f.description,
+                    f.remediation
+                )
+            })
+            .collect::<Vec<_>>();
+        let findings_section = if inlined.is_empty() {
+            String::new()
+        } else {
+            format!("\n## Findings\n\n{}", inlined.join("\n"))
+        };
+
+        format!(
+            r#"<!-- polyrev:fp:{fingerprint} -->
+
+## Cluster: {name}
+
+{rationale}
+{tasks_section}{findings_section}{auto_fix_section}
+---
+*Generated by [polyrev](https://github.com/rk23/polyrev)*
+"#,
+            fingerprint = cluster.fingerprint,
+            name = cluster.name,
+            rationale = cluster.rationale,
+            tasks_section = tasks_section,
+            findings_section = findings_section,
+            auto_fix_section = if inline {
+                self.auto_fix_section()
+            } else {
+                String::new()
+            },
+        )
+    }
+}
+
+/// One-line summary of a cluster member, led by its child issue when linked
+fn member_line(member: &ClusterMember<'_>, permalinks: Option<&Permalinks>) -> String {
+    let f = member.finding;
+    let summary = format!(
+        "[{}] {} ({})",
+        f.priority,
+        f.title,
+        markdown_location(permalinks, &f.location(), &f.file, f.line, f.end_line)
+    );
+    match member.issue.as_deref() {
+        Some(issue) if issue.parse::<u64>().is_ok() => format!("#{} {}", issue, summary),
+        Some(issue) => format!("{} {}", issue, summary),
+        None => summary,
+    }
+}
@@ -0,0 +1,574 @@
+//! Linear issue tracker (GraphQL API)
+//!
+//! Linear doesn't keep HTML comments in descriptions, so the fingerprint
+//! marker is appended as a visible footer and dedupe searches for it. Labels
+//! that don't exist yet are created on the team, since sync finds polyrev's
+//! issues by their reviewer label.
+
+use super::{
+    fingerprint_in, fingerprint_marker, ExistingIssue, IssueState, IssueTracker, NewIssue,
+    TrackedIssue,
+};
+use crate::config::LinearConfig;
+use crate::error::{LinearError, TrackerError};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::{debug, info, warn};
+
+/// Default GraphQL endpoint
+pub const DEFAULT_API_URL: &str = "https://api.linear.app/graphql";
+
+/// Page size for connection queries (Linear's maximum)
+const PAGE_SIZE: usize = 250;
+
+/// Safety cap on followed pages
+const MAX_PAGES: usize = 50;
+
+/// Minimal Linear GraphQL client for one team
+pub struct LinearClient {
+    http: reqwest::Client,
+    api_url: String,
+    config: LinearConfig,
+    team_key: String,
+    team_id: OnceCell<String>,
+    /// Lowercased label name → id, loaded on first use
+    label_ids: Mutex<Option<HashMap<String, String>>>,
+}
+
+impl LinearClient {
+    pub fn new(
+        api_url: &str,
+        api_key: &str,
+        team_key: &str,
+        config: LinearConfig,
+    ) -> Result<Self, LinearError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("polyrev"));
+        let mut auth = HeaderValue::from_str(api_key)
+            .map_err(|e| LinearError::Config(format!("invalid API key: {}", e)))?;
+        auth.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth);
+
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            http,
+            api_url: api_url.to_string(),
+            config,
+            team_key: team_key.to_string(),
+            team_id: OnceCell::new(),
+            label_ids: Mutex::new(None),
+        })
+    }
+
+    /// Build a client from config and $LINEAR_API_KEY
+    pub fn from_config(config: &LinearConfig, team: Option<String>) -> Result<Self, LinearError> {
+        let team = team.or_else(|| config.team.clone()).ok_or_else(|| {
+            LinearError::Config("a team key is required (--repo or linear.team)".to_string())
+        })?;
+        let api_key = std::env::var("LINEAR_API_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| LinearError::Config("set LINEAR_API_KEY".to_string()))?;
+
+        debug!("Using Linear backend for team {}", team);
+        Self::new(DEFAULT_API_URL, &api_key, &team, config.clone())
+    }
+
+    /// Run a GraphQL operation and return its `data`
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value, LinearError> {
+        let response = self
+            .http
+            .post(&self.api_url)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+
+        // GraphQL errors can come with 200 or 4xx
+        if let Some(errors) = body.get("errors").and_then(Value::as_array) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|e| e.get("message").and_then(Value::as_str))
+                .collect();
+            if !messages.is_empty() {
+                return Err(LinearError::GraphQl(messages.join("; ")));
+            }
+        }
+        if !status.is_success() {
+            return Err(LinearError::Api {
+                status: status.as_u16(),
+                message: status.to_string(),
+            });
+        }
+
+        Ok(body.get("data").cloned().unwrap_or(Value::Null))
+    }
+
+    async fn team_id(&self) -> Result<&str, LinearError> {
+        let id = self
+            .team_id
+            .get_or_try_init(|| async {
+                let data = self
+                    .graphql(
+                        "query($key: String!) { teams(filter: {key: {eq: $key}}) { nodes { id } } }",
+                        json!({ "key": self.team_key }),
+                    )
+                    .await?;
+                data.pointer("/teams/nodes/0/id")
+                    .and_then(Value::as_str)
+                    .map(String::from)
+                    .ok_or_else(|| {
+                        LinearError::Config(format!("team '{}' not found", self.team_key))
+                    })
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// Run a query over a connection, following `pageInfo` cursors
+    ///
+    /// `query` takes an `$after: String` cursor and selects `nodes` and
+    /// `pageInfo { hasNextPage endCursor }` of `connection`.
+    async fn nodes(
+        &self,
+        query: &str,
+        mut variables: Value,
+        connection: &str,
+    ) -> Result<Vec<Value>, LinearError> {
+        let mut nodes = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let data = self.graphql(query, variables.clone()).await?;
+            let page = data.get(connection).cloned().unwrap_or(Value::Null);
+            if let Some(page_nodes) = page.get("nodes").and_then(Value::as_array) {
+                nodes.extend(page_nodes.iter().cloned());
+            }
+            let next = page
+                .pointer("/pageInfo/endCursor")
+                .and_then(Value::as_str)
+                .filter(|_| page.pointer("/pageInfo/hasNextPage") == Some(&Value::Bool(true)));
+            match next {
+                Some(cursor) => variables["after"] = json!(cursor),
+                None => return Ok(nodes),
+            }
+        }
+        warn!("Linear {} query stopped after {} pages", connection, MAX_PAGES);
+        Ok(nodes)
+    }
+
+    /// Ids of the named labels, creating those the team doesn't have yet
+    ///
+    /// The team's and workspace labels are fetched once per client.
+    async fn label_ids(&self, names: &[String]) -> Result<Vec<String>, LinearError> {
+        let team = self.team_id().await?.to_string();
+        let mut cache = self.label_ids.lock().await;
+        let known = match cache.as_mut() {
+            Some(known) => known,
+            None => {
+                let nodes = self
+                    .nodes(
+                        "query($team: ID!, $first: Int!, $after: String) { issueLabels(first: $first, after: $after, filter: {or: [{team: {id: {eq: $team}}}, {team: {null: true}}]}) { nodes { id name } pageInfo { hasNextPage endCursor } } }",
+                        json!({ "team": team, "first": PAGE_SIZE, "after": null }),
+                        "issueLabels",
+                    )
+                    .await?;
+                let labels = nodes
+                    .iter()
+                    .filter_map(|n| {
+                        Some((
+                            n.get("name")?.as_str()?.to_lowercase(),
+                            n.get("id")?.as_str()?.to_string(),
+                        ))
+                    })
+                    .collect();
+                cache.insert(labels)
+            }
+        };
+
+        let mut ids = Vec::new();
+        for name in names {
+            let id = match known.get(&name.to_lowercase()) {
+                Some(id) => id.clone(),
+                None => {
+                    let data = self
+                        .graphql(
+                            "mutation($input: IssueLabelCreateInput!) { issueLabelCreate(input: $input) { success issueLabel { id } } }",
+                            json!({ "input": { "name": name, "teamId": team } }),
+                        )
+                        .await?;
+                    let id = data
+                        .pointer("/issueLabelCreate/issueLabel/id")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| {
+                            LinearError::GraphQl(format!(
+                                "issueLabelCreate did not return a label for '{}'",
+                                name
+                            ))
+                        })?
+                        .to_string();
+                    info!("Created Linear label '{}'", name);
+                    known.insert(name.to_lowercase(), id.clone());
+                    id
+                }
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Move an issue to the team's first workflow state of `state_type`
+    async fn set_state(&self, id: &str, state_type: &str) -> Result<(), LinearError> {
+        let data = self
+            .graphql(
+                "query($team: ID!, $type: String!) { workflowStates(filter: {team: {id: {eq: $team}}, type: {eq: $type}}) { nodes { id } } }",
+                json!({ "team": self.team_id().await?, "type": state_type }),
+            )
+            .await?;
+        let state_id = data
+            .pointer("/workflowStates/nodes/0/id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                LinearError::Config(format!("team has no '{}' workflow state", state_type))
+            })?;
+
+        self.graphql(
+            "mutation($id: String!, $state: String!) { issueUpdate(id: $id, input: {stateId: $state}) { success } }",
+            json!({ "id": id, "state": state_id }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for LinearClient {
+    async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Vec<ExistingIssue>, TrackerError> {
+        let nodes = self
+            .nodes(
+                "query($team: ID!, $marker: String!, $first: Int!, $after: String) { issues(first: $first, after: $after, includeArchived: true, filter: {team: {id: {eq: $team}}, description: {contains: $marker}}) { nodes { identifier state { type } } pageInfo { hasNextPage endCursor } } }",
+                json!({
+                    "team": self.team_id().await?,
+                    "marker": fingerprint_marker(fingerprint),
+                    "first": PAGE_SIZE,
+                    "after": null,
+                }),
+                "issues",
+            )
+            .await?;
+        Ok(nodes
+            .iter()
+            .filter_map(|n| {
+                let state = n.pointer("/state/type").and_then(Value::as_str);
+                Some(ExistingIssue {
+                    id: n.get("identifier")?.as_str()?.to_string(),
+                    state: match state {
+                        Some("completed") | Some("canceled") => IssueState::Closed,
+                        _ => IssueState::Open,
+                    },
+                })
+            })
+            .collect())
+    }
+
+    async fn create_issue(&self, issue: &NewIssue) -> Result<String, TrackerError> {
+        let description = format!(
+            "{}\n\n---\n`{}`\n",
+            issue.body,
+            fingerprint_marker(&issue.fingerprint)
+        );
+        let input = json!({
+            "teamId": self.team_id().await?,
+            "title": issue.title,
+            "description": description,
+            "priority": self.config.priority_value(issue.priority),
+            "labelIds": self.label_ids(&issue.labels).await?,
+        });
+
+        let data = self
+            .graphql(
+                "mutation($input: IssueCreateInput!) { issueCreate(input: $input) { success issue { identifier url } } }",
+                json!({ "input": input }),
+            )
+            .await?;
+        match data
+            .pointer("/issueCreate/issue/url")
+            .and_then(Value::as_str)
+        {
+            Some(url) => Ok(url.to_string()),
+            None => {
+                warn!("Linear issueCreate returned no issue: {}", data);
+                Err(LinearError::GraphQl("issueCreate did not return an issue".to_string()).into())
+            }
+        }
+    }
+
+    async fn comment_issue(&self, id: &str, body: &str) -> Result<(), TrackerError> {
+        self.graphql(
+            "mutation($id: String!, $body: String!) { commentCreate(input: {issueId: $id, body: $body}) { success } }",
+            json!({ "id": id, "body": body }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn reopen_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_state(id, "unstarted").await?)
+    }
+
+    async fn close_issue(&self, id: &str) -> Result<(), TrackerError> {
+        Ok(self.set_state(id, "completed").await?)
+    }
+
+    async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, TrackerError> {
+        let nodes = self
+            .nodes(
+                "query($team: ID!, $label: String!, $first: Int!, $after: String) { issues(first: $first, after: $after, filter: {team: {id: {eq: $team}}, labels: {some: {name: {eqIgnoreCase: $label}}}, state: {type: {nin: [\"completed\", \"canceled\"]}}, description: {contains: \"polyrev:fp:\"}}) { nodes { identifier description } pageInfo { hasNextPage endCursor } } }",
+                json!({
+                    "team": self.team_id().await?,
+                    "label": label,
+                    "first": PAGE_SIZE,
+                    "after": null,
+                }),
+                "issues",
+            )
+            .await?;
+        Ok(nodes
+            .iter()
+            .filter_map(|n| {
+                Some(TrackedIssue {
+                    id: n.get("identifier")?.as_str()?.to_string(),
+                    fingerprint: fingerprint_in(n.get("description")?.as_str()?)?,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+    use crate::tracker::sync::{sync_reviewer, SyncRun, SyncState};
+    use std::collections::HashSet;
+    use wiremock::matchers::{body_string_contains, header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_team(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(body_string_contains("teams("))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"teams": {"nodes": [{"id": "team-1"}]}}
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    fn client(server: &MockServer) -> LinearClient {
+        LinearClient::new(&server.uri(), "lin_key", "ENG", LinearConfig::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_by_fingerprint_maps_state_type() {
+        let server = MockServer::start().await;
+        mock_team(&server).await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "lin_key"))
+            .and(body_string_contains("polyrev:fp:abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issues": {"nodes": [
+                    {"identifier": "ENG-3", "state": {"type": "started"}},
+                    {"identifier": "ENG-1", "state": {"type": "canceled"}}
+                ]}}
+            })))
+            .mount(&server)
+            .await;
+
+        let issues = client(&server).find_by_fingerprint("abc").await.unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ExistingIssue {
+                    id: "ENG-3".to_string(),
+                    state: IssueState::Open
+                },
+                ExistingIssue {
+                    id: "ENG-1".to_string(),
+                    state: IssueState::Closed
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_sets_priority_and_creates_missing_labels() {
+        let server = MockServer::start().await;
+        mock_team(&server).await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("issueLabels"))
+            .and(body_string_contains("\"after\":\"cursor-1\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issueLabels": {
+                    "nodes": [{"id": "lbl-1", "name": "Polyrev"}],
+                    "pageInfo": {"hasNextPage": false, "endCursor": "cursor-2"}
+                }}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("issueLabels"))
+            .and(body_string_contains("\"after\":null"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issueLabels": {
+                    "nodes": [{"id": "lbl-0", "name": "frontend"}],
+                    "pageInfo": {"hasNextPage": true, "endCursor": "cursor-1"}
+                }}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("issueLabelCreate"))
+            .and(body_string_contains("\"name\":\"p0\""))
+            .and(body_string_contains("\"teamId\":\"team-1\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issueLabelCreate": {"success": true, "issueLabel": {"id": "lbl-2"}}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("issueCreate"))
+            .and(body_string_contains("\"priority\":1"))
+            .and(body_string_contains("\"labelIds\":[\"lbl-1\",\"lbl-2\"]"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issueCreate": {"success": true, "issue": {
+                    "identifier": "ENG-9",
+                    "url": "https://linear.app/acme/issue/ENG-9"
+                }}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let issue = NewIssue {
+            title: "[p0] Bug".to_string(),
+            labels: vec!["polyrev".to_string(), "p0".to_string()],
+            fingerprint: "abc".to_string(),
+            priority: Priority::P0,
+            ..Default::default()
+        };
+        let linear = client(&server);
+        let url = linear.create_issue(&issue).await.unwrap();
+        assert_eq!(url, "https://linear.app/acme/issue/ENG-9");
+
+        // Labels are fetched and created once
+        assert_eq!(
+            linear.label_ids(&issue.labels).await.unwrap(),
+            vec!["lbl-1", "lbl-2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_closes_issues_across_pages() {
+        let server = MockServer::start().await;
+        mock_team(&server).await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eqIgnoreCase"))
+            .and(body_string_contains("\"label\":\"security\""))
+            .and(body_string_contains("\"after\":null"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issues": {
+                    "nodes": [{"identifier": "ENG-1", "description": "Bug\n\n---\n`polyrev:fp:aaa`\n"}],
+                    "pageInfo": {"hasNextPage": true, "endCursor": "cursor-1"}
+                }}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eqIgnoreCase"))
+            .and(body_string_contains("\"after\":\"cursor-1\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issues": {
+                    "nodes": [{"identifier": "ENG-2", "description": "Bug\n\n---\n`polyrev:fp:bbb`\n"}],
+                    "pageInfo": {"hasNextPage": false, "endCursor": null}
+                }}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("commentCreate"))
+            .and(body_string_contains("\"id\":\"ENG-2\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"commentCreate": {"success": true}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("workflowStates"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"workflowStates": {"nodes": [{"id": "state-done"}]}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("issueUpdate"))
+            .and(body_string_contains("\"id\":\"ENG-2\""))
+            .and(body_string_contains("\"state\":\"state-done\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"issueUpdate": {"success": true}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut state = SyncState::default();
+        let reported: HashSet<String> = ["aaa".to_string()].into();
+        let run = SyncRun {
+            run_id: "run-1",
+            commit: "abc123",
+            grace_runs: 1,
+            dry_run: false,
+        };
+        let outcome = sync_reviewer(&client(&server), &mut state, "security", &reported, &run)
+            .await
+            .unwrap();
+        assert_eq!(outcome.to_close.len(), 1);
+        assert_eq!(outcome.to_close[0].id, "ENG-2");
+    }
+
+    #[tokio::test]
+    async fn test_graphql_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "errors": [{"message": "Authentication required"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .comment_issue("ENG-1", "hi")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Authentication required"));
+    }
+}
@@ -0,0 +1,255 @@
+//! Issue tracker abstraction
+//!
+//! `IssueCreator` and `DedupeChecker` work against any `IssueTracker`.
+//! Forges with more than issues live in their own modules (`github`,
+//! `gitlab`); issue-only trackers (Jira, Linear) live here.
+
+pub mod cluster;
+mod dedupe;
+mod issue;
+mod jira;
+mod linear;
+pub mod policy;
+pub mod review;
+pub mod sync;
+pub mod template;
+
+pub use issue::{ClusterMember, IssueCreator, IssueResult};
+
+use crate::config::{AutoFixConfig, Config, DedupeAction, Priority, TrackerKind};
+use crate::error::TrackerError;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{HeaderMap, LINK};
+use std::sync::{Arc, OnceLock};
+
+/// An issue to create
+#[derive(Debug, Clone, Default)]
+pub struct NewIssue {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Finding fingerprint (also embedded in `body` as a hidden marker)
+    pub fingerprint: String,
+    pub priority: Priority,
+    /// Milestone title (GitHub and GitLab)
+    pub milestone: Option<String>,
+}
+
+/// An existing issue found by fingerprint
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExistingIssue {
+    /// Tracker-specific id: issue number, GitLab iid, ...
+    pub id: String,
+    pub state: IssueState,
+}
+
+/// An open polyrev issue and the fingerprint it tracks
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedIssue {
+    pub id: String,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+impl IssueState {
+    /// Parse an API state ("open", "OPEN", "opened"); anything else is closed
+    pub fn from_api(state: &str) -> Self {
+        let state = state.to_ascii_lowercase();
+        if state == "open" || state == "opened" {
+            IssueState::Open
+        } else {
+            IssueState::Closed
+        }
+    }
+}
+
+/// Operations polyrev needs from an issue tracker
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    /// Find issues (open or closed) carrying this finding fingerprint
+    async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Vec<ExistingIssue>, TrackerError>;
+
+    /// Create an issue and return its URL
+    async fn create_issue(&self, issue: &NewIssue) -> Result<String, TrackerError>;
+
+    async fn comment_issue(&self, id: &str, body: &str) -> Result<(), TrackerError>;
+
+    async fn reopen_issue(&self, id: &str) -> Result<(), TrackerError>;
+
+    async fn close_issue(&self, id: &str) -> Result<(), TrackerError>;
+
+    /// Open issues labelled `label` that carry a fingerprint marker
+    async fn list_open_issues(&self, label: &str) -> Result<Vec<TrackedIssue>, TrackerError>;
+}
+
+/// Hidden marker embedded in issue bodies and comments
+pub fn fingerprint_marker(fingerprint: &str) -> String {
+    format!("polyrev:fp:{}", fingerprint)
+}
+
+/// Extract a fingerprint from an issue body or label
+///
+/// Accepts the body marker (`polyrev:fp:<fp>`) and the Jira label form
+/// (`polyrev-fp-<fp>`).
+pub fn fingerprint_in(text: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"polyrev[:-]fp[:-]([0-9a-f]+)").expect("fingerprint regex is valid")
+    })
+    .captures(text)
+    .map(|c| c[1].to_string())
+}
+
+/// Parse a numeric issue id (GitHub number, GitLab iid)
+pub fn numeric_id(id: &str) -> Result<u64, TrackerError> {
+    id.trim_start_matches('#')
+        .parse()
+        .map_err(|_| TrackerError::InvalidId(id.to_string()))
+}
+
+/// Issue settings for the configured tracker
+pub struct IssueSettings {
+    pub enabled: bool,
+    pub dedupe: bool,
+    pub dedupe_action: DedupeAction,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    pub auto_fix: AutoFixConfig,
+}
+
+impl IssueSettings {
+    pub fn from_config(config: &Config) -> Self {
+        match config.tracker {
+            TrackerKind::Github => Self {
+                enabled: config.github.enabled,
+                dedupe: config.github.dedupe,
+                dedupe_action: config.github.dedupe_action,
+                labels: config.github.labels.clone(),
+                assignees: config.github.assignees.clone(),
+                auto_fix: config.github.auto_fix.clone(),
+            },
+            // Auto-fix relies on the GitHub Action
+            TrackerKind::Gitlab => Self {
+                enabled: true,
+                dedupe: config.gitlab.dedupe,
+                dedupe_action: config.gitlab.dedupe_action,
+                labels: config.gitlab.labels.clone(),
+                assignees: config.gitlab.assignees.clone(),
+                auto_fix: AutoFixConfig::default(),
+            },
+            // Jira and Linear assign by account id, which we don't look up
+            TrackerKind::Jira => Self {
+                enabled: true,
+                dedupe: config.jira.dedupe,
+                dedupe_action: config.jira.dedupe_action,
+                labels: config.jira.labels.clone(),
+                assignees: Vec::new(),
+                auto_fix: AutoFixConfig::default(),
+            },
+            TrackerKind::Linear => Self {
+                enabled: true,
+                dedupe: config.linear.dedupe,
+                dedupe_action: config.linear.dedupe_action,
+                labels: config.linear.labels.clone(),
+                assignees: Vec::new(),
+                auto_fix: AutoFixConfig::default(),
+            },
+        }
+    }
+}
+
+/// Create the configured tracker; `repo` overrides the configured repo/project
+pub fn create_tracker(
+    config: &Config,
+    repo: Option<String>,
+) -> Result<Arc<dyn IssueTracker>, TrackerError> {
+    match config.tracker {
+        TrackerKind::Github => {
+            let repo = repo.or_else(|| config.github.repo.clone());
+            Ok(crate::github::create_backend(&config.github, repo)?)
+        }
+        TrackerKind::Gitlab => {
+            let project = repo.or_else(|| config.gitlab.project.clone());
+            Ok(Arc::new(crate::gitlab::GitLabClient::from_config(
+                &config.gitlab,
+                project,
+            )?))
+        }
+        TrackerKind::Jira => Ok(Arc::new(jira::JiraClient::from_config(&config.jira, repo)?)),
+        TrackerKind::Linear => Ok(Arc::new(linear::LinearClient::from_config(
+            &config.linear,
+            repo,
+        )?)),
+    }
+}
+
+/// Extract the `rel="next"` URL from a `Link` header (GitHub and GitLab)
+pub fn next_link(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == "rel=\"next\"")
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_next_link() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                "<https://api.github.com/x?page=2>; rel=\"next\", <https://api.github.com/x?page=5>; rel=\"last\"",
+            ),
+        );
+        assert_eq!(
+            next_link(&headers).as_deref(),
+            Some("https://api.github.com/x?page=2")
+        );
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_issue_state_and_numeric_id() {
+        assert_eq!(IssueState::from_api("OPEN"), IssueState::Open);
+        assert_eq!(IssueState::from_api("opened"), IssueState::Open);
+        assert_eq!(IssueState::from_api("closed"), IssueState::Closed);
+        assert_eq!(numeric_id("#12").unwrap(), 12);
+        assert!(numeric_id("PROJ-1").is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_in() {
+        assert_eq!(
+            fingerprint_in("<!-- polyrev:fp:0a1b2c3d4e5f -->\n## Description").as_deref(),
+            Some("0a1b2c3d4e5f")
+        );
+        assert_eq!(
+            fingerprint_in("polyrev-fp-abc123").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(fingerprint_in("no marker here"), None);
+    }
+}
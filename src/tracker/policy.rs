@@ -0,0 +1,291 @@
+//! Issue filing policy: which findings get issues, and where they go
+//!
+//! Configured under `issue_policy`. Findings below `min_priority` are not
+//! filed, and at most `max_new_issues` new issues are opened per run,
+//! highest priority first. Assignees come from the reviewer override (or the
+//! tracker default) plus every matching route and, optionally, CODEOWNERS.
+
+use crate::config::{IssuePolicyConfig, Priority, ReviewerIssuePolicy};
+use crate::error::ConfigError;
+use crate::parser::Finding;
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Where GitHub, GitLab and others look for CODEOWNERS, in order
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Compiled `issue_policy`
+#[derive(Default)]
+pub struct IssuePolicy {
+    min_priority: Option<Priority>,
+    max_new_issues: Option<usize>,
+    reviewers: HashMap<String, ReviewerIssuePolicy>,
+    routes: Vec<(GlobSet, Vec<String>)>,
+    /// CODEOWNERS rules in file order; the last match wins
+    codeowners: Vec<(GlobMatcher, Vec<String>)>,
+}
+
+/// Per-finding overrides applied when its issue is created
+#[derive(Debug, Default, PartialEq)]
+pub struct IssueRouting {
+    /// Added to the tracker's labels
+    pub labels: Vec<String>,
+    /// Replaces the tracker's default assignees
+    pub assignees: Option<Vec<String>>,
+    /// Added from routes and CODEOWNERS
+    pub owners: Vec<String>,
+    pub repo: Option<String>,
+    pub milestone: Option<String>,
+}
+
+impl IssuePolicy {
+    pub fn from_config(config: &IssuePolicyConfig, target: &Path) -> Result<Self, ConfigError> {
+        let mut routes = Vec::new();
+        for route in &config.routes {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &route.paths {
+                builder.add(Glob::new(pattern).map_err(|e| {
+                    ConfigError::InvalidIssuePolicy(format!("bad route glob '{}': {}", pattern, e))
+                })?);
+            }
+            let set = builder
+                .build()
+                .map_err(|e| ConfigError::InvalidIssuePolicy(e.to_string()))?;
+            routes.push((set, route.assignees.clone()));
+        }
+
+        let codeowners = if config.codeowners {
+            load_codeowners(target)
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            min_priority: config.min_priority,
+            max_new_issues: config.max_new_issues,
+            reviewers: config.reviewers.clone(),
+            routes,
+            codeowners,
+        })
+    }
+
+    /// Whether a finding of this priority gets an issue
+    pub fn files(&self, priority: Priority) -> bool {
+        self.min_priority
+            .is_none_or(|min| priority.rank() <= min.rank())
+    }
+
+    pub fn max_new_issues(&self) -> Option<usize> {
+        self.max_new_issues
+    }
+
+    /// Indexes of the findings to file, highest priority first (stable within a priority)
+    pub fn order(&self, findings: &[(String, Finding)]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..findings.len())
+            .filter(|&i| self.files(findings[i].1.priority))
+            .collect();
+        order.sort_by_key(|&i| findings[i].1.priority.rank());
+        order
+    }
+
+    /// Labels, assignees, repo and milestone for one finding's issue
+    ///
+    /// Reduced findings use the override of the first source reviewer that has one.
+    pub fn route(&self, reviewer_id: &str, finding: &Finding) -> IssueRouting {
+        let reviewer = self
+            .reviewers
+            .get(reviewer_id)
+            .or_else(|| finding.reviewers.iter().find_map(|r| self.reviewers.get(r)));
+        let path = finding.file.strip_prefix("./").unwrap_or(&finding.file);
+
+        let mut owners: Vec<String> = Vec::new();
+        let matched_routes = self
+            .routes
+            .iter()
+            .filter(|(set, _)| set.is_match(path))
+            .flat_map(|(_, assignees)| assignees.iter());
+        let codeowners = self
+            .codeowners
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .map(|(_, users)| users.iter())
+            .into_iter()
+            .flatten();
+        for owner in matched_routes.chain(codeowners) {
+            if !owners.contains(owner) {
+                owners.push(owner.clone());
+            }
+        }
+
+        IssueRouting {
+            labels: reviewer.map(|r| r.labels.clone()).unwrap_or_default(),
+            assignees: reviewer.and_then(|r| r.assignees.clone()),
+            owners,
+            repo: reviewer.and_then(|r| r.repo.clone()),
+            milestone: reviewer.and_then(|r| r.milestone.clone()),
+        }
+    }
+}
+
+fn load_codeowners(target: &Path) -> Vec<(GlobMatcher, Vec<String>)> {
+    let Some((path, content)) = CODEOWNERS_PATHS.iter().find_map(|p| {
+        let path = target.join(p);
+        std::fs::read_to_string(&path).ok().map(|c| (path, c))
+    }) else {
+        warn!("issue_policy.codeowners is set but no CODEOWNERS file was found");
+        return Vec::new();
+    };
+
+    let rules = parse_codeowners(&content);
+    debug!("Loaded {} CODEOWNERS rules from {:?}", rules.len(), path);
+    rules
+}
+
+/// Parse CODEOWNERS into (matcher, users) rules
+///
+/// Only `@user` owners are kept: teams and email addresses can't be assigned.
+fn parse_codeowners(content: &str) -> Vec<(GlobMatcher, Vec<String>)> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else {
+            continue;
+        };
+        let users: Vec<String> = parts
+            .filter_map(|owner| owner.strip_prefix('@'))
+            .filter(|owner| !owner.contains('/'))
+            .map(str::to_string)
+            .collect();
+
+        match GlobBuilder::new(&codeowners_glob(pattern))
+            .literal_separator(true)
+            .build()
+        {
+            Ok(glob) => rules.push((glob.compile_matcher(), users)),
+            Err(e) => warn!("Skipping CODEOWNERS pattern '{}': {}", pattern, e),
+        }
+    }
+    rules
+}
+
+/// Translate a gitignore-style CODEOWNERS pattern into a glob
+///
+/// Patterns containing a slash are anchored at the root; others match at any
+/// depth. A pattern also matches everything under a directory it names.
+fn codeowners_glob(pattern: &str) -> String {
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    let base = if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+    format!("{{{base},{base}/**}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IssueRoute;
+
+    fn finding(file: &str, priority: Priority) -> Finding {
+        serde_json::from_value(serde_json::json!({
+            "id": "X-1",
+            "title": "t",
+            "priority": priority.to_string(),
+            "file": file,
+            "line": 1,
+            "description": "d",
+            "remediation": "r",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_min_priority_and_order() {
+        let policy = IssuePolicy {
+            min_priority: Some(Priority::P1),
+            ..Default::default()
+        };
+        let findings = vec![
+            ("a".to_string(), finding("a.rs", Priority::P2)),
+            ("a".to_string(), finding("b.rs", Priority::P1)),
+            ("a".to_string(), finding("c.rs", Priority::P0)),
+            ("a".to_string(), finding("d.rs", Priority::P1)),
+        ];
+        assert_eq!(policy.order(&findings), vec![2, 1, 3]);
+        assert!(IssuePolicy::default().files(Priority::P2));
+    }
+
+    #[test]
+    fn test_route_merges_reviewer_routes_and_codeowners() {
+        let mut config = IssuePolicyConfig::default();
+        config.reviewers.insert(
+            "security".to_string(),
+            ReviewerIssuePolicy {
+                labels: vec!["security".to_string()],
+                assignees: Some(vec!["sec-lead".to_string()]),
+                repo: Some("acme/security".to_string()),
+                milestone: Some("Q3".to_string()),
+            },
+        );
+        config.routes.push(IssueRoute {
+            paths: vec!["src/api/**".to_string()],
+            assignees: vec!["bob".to_string()],
+        });
+        let mut policy = IssuePolicy::from_config(&config, Path::new(".")).unwrap();
+        policy.codeowners = parse_codeowners(
+            "# owners\n* @default\n/src/api/ @bob @carol @acme/api-team\n*.md docs@acme.com\n",
+        );
+
+        let routing = policy.route("security", &finding("./src/api/users.rs", Priority::P0));
+        assert_eq!(routing.labels, vec!["security"]);
+        assert_eq!(routing.assignees, Some(vec!["sec-lead".to_string()]));
+        assert_eq!(routing.owners, vec!["bob", "carol"]);
+        assert_eq!(routing.repo.as_deref(), Some("acme/security"));
+        assert_eq!(routing.milestone.as_deref(), Some("Q3"));
+
+        let routing = policy.route("perf", &finding("lib/main.rs", Priority::P1));
+        assert_eq!(
+            routing,
+            IssueRouting {
+                owners: vec!["default".to_string()],
+                ..Default::default()
+            }
+        );
+
+        // Last matching rule wins, even when it has no assignable owners
+        let routing = policy.route("perf", &finding("docs/guide.md", Priority::P1));
+        assert!(routing.owners.is_empty());
+
+        // Reduced findings fall back to the reviewers they were merged from
+        let mut reduced = finding("lib/main.rs", Priority::P0);
+        reduced.reviewers = vec!["perf".to_string(), "security".to_string()];
+        let routing = policy.route("reduced", &reduced);
+        assert_eq!(routing.repo.as_deref(), Some("acme/security"));
+    }
+
+    #[test]
+    fn test_codeowners_glob() {
+        let matches = |pattern: &str, path: &str| {
+            GlobBuilder::new(&codeowners_glob(pattern))
+                .literal_separator(true)
+                .build()
+                .unwrap()
+                .compile_matcher()
+                .is_match(path)
+        };
+        assert!(matches("*.js", "web/app.js"));
+        assert!(matches("*.js", "app.js"));
+        assert!(matches("/build/", "build/out/x.o"));
+        assert!(!matches("/build/", "src/build/x.o"));
+        assert!(matches("logs/", "a/logs/today.txt"));
+        assert!(matches("docs/*", "docs/index.md"));
+        assert!(!matches("docs/*", "sub/docs/index.md"));
+    }
+}
@@ -0,0 +1,342 @@
+//! Shared planning for inline review comments (GitHub PRs, GitLab MRs)
+//!
+//! Findings on lines that are part of the diff become inline comments;
+//! the rest are listed in a summary. Every comment carries the finding
+//! fingerprint as a hidden marker so re-runs update existing comments and
+//! mark ones that are no longer reported as resolved.
+
+use crate::output::repo_path;
+use crate::parser::Finding;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Marks a review body / summary note as written by polyrev
+pub const REVIEW_MARKER: &str = "<!-- polyrev:review -->";
+
+/// Appended to the fingerprint marker of comments that are no longer reported
+const RESOLVED_SUFFIX: &str = ":resolved";
+
+/// An existing inline review comment
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewComment {
+    pub id: u64,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Inline comment to include in a new review
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewReviewComment {
+    pub path: String,
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
+    pub side: &'static str,
+    pub body: String,
+}
+
+/// What to change on the pull/merge request
+#[derive(Debug, Default)]
+pub struct ReviewPlan {
+    /// Inline comments for findings without an existing comment
+    pub new_comments: Vec<NewReviewComment>,
+    /// Existing comments whose text changed: (comment id, new body)
+    pub updated: Vec<(u64, String)>,
+    /// Existing comments for findings no longer reported: (comment id, new body)
+    pub resolved: Vec<(u64, String)>,
+    /// Findings outside the diff, rendered for the review body
+    pub body_findings: Vec<String>,
+}
+
+impl ReviewPlan {
+    pub fn is_empty(&self) -> bool {
+        self.new_comments.is_empty()
+            && self.updated.is_empty()
+            && self.resolved.is_empty()
+            && self.body_findings.is_empty()
+    }
+
+    /// Review body listing findings outside the diff
+    pub fn body(&self, inline_total: usize) -> String {
+        let mut body = format!("{}\n## polyrev review\n\n", REVIEW_MARKER);
+        body.push_str(&format!(
+            "{} finding(s) on changed lines are posted inline.\n",
+            inline_total
+        ));
+        if !self.body_findings.is_empty() {
+            body.push_str("\n### Outside the diff\n\n");
+            for line in &self.body_findings {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+        body
+    }
+}
+
+/// Outcome of publishing a review
+#[derive(Debug, Default)]
+pub struct ReviewSummary {
+    pub inline: usize,
+    pub in_body: usize,
+    pub updated: usize,
+    pub resolved: usize,
+}
+
+impl ReviewSummary {
+    pub fn from_plan(plan: &ReviewPlan) -> Self {
+        Self {
+            inline: plan.new_comments.len(),
+            in_body: plan.body_findings.len(),
+            updated: plan.updated.len(),
+            resolved: plan.resolved.len(),
+        }
+    }
+}
+
+/// Compute the review changes from findings, the PR diff and existing comments
+///
+/// `prefix` is the target's directory within the repository; diff paths are
+/// relative to the repository root.
+pub fn plan_review(
+    findings: &[(String, Finding)],
+    diff: &HashMap<String, HashSet<u32>>,
+    existing: &[ReviewComment],
+    prefix: &str,
+) -> ReviewPlan {
+    let mut plan = ReviewPlan::default();
+
+    // Existing polyrev comments by fingerprint
+    let mut by_fingerprint: HashMap<String, &ReviewComment> = HashMap::new();
+    for comment in existing {
+        if let Some(fp) = comment_fingerprint(&comment.body) {
+            by_fingerprint.entry(fp).or_insert(comment);
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for (reviewer_id, finding) in findings {
+        let fingerprint = finding.fingerprint(reviewer_id);
+        if !reported.insert(fingerprint.clone()) {
+            continue;
+        }
+        let body = comment_body(finding, &fingerprint);
+
+        if let Some(comment) = by_fingerprint.get(&fingerprint) {
+            if comment.body.trim() != body.trim() {
+                plan.updated.push((comment.id, body));
+            }
+            continue;
+        }
+
+        let path = repo_path(prefix, &finding.file);
+        let lines = diff.get(&path);
+        let in_diff = |line: u32| lines.is_some_and(|l| l.contains(&line));
+
+        if finding.line > 0 && in_diff(finding.line) {
+            // Span the whole range when both ends are in the diff
+            let (start_line, line) = match finding.end_line {
+                Some(end) if end > finding.line && in_diff(end) => (Some(finding.line), end),
+                _ => (None, finding.line),
+            };
+            plan.new_comments.push(NewReviewComment {
+                path,
+                line,
+                start_line,
+                side: "RIGHT",
+                body,
+            });
+        } else {
+            plan.body_findings.push(format!(
+                "- **[{}] {}** — `{}` <!-- polyrev:fp:{} -->",
+                finding.priority,
+                finding.title,
+                finding.location(),
+                fingerprint
+            ));
+        }
+    }
+
+    for (fingerprint, comment) in by_fingerprint {
+        if !reported.contains(&fingerprint) && !is_resolved(&comment.body) {
+            plan.resolved
+                .push((comment.id, resolved_body(&comment.body, &fingerprint)));
+        }
+    }
+    plan.resolved.sort_by_key(|(id, _)| *id);
+
+    plan
+}
+
+/// Right-side line numbers that can take a comment (added and context lines)
+pub fn commentable_lines(patch: &str) -> HashSet<u32> {
+    let mut lines = HashSet::new();
+    let mut current = 0u32;
+
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("@@") {
+            // "@@ -a,b +c,d @@" -> c
+            current = header
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+        } else if line.starts_with('-') || line.starts_with('\\') {
+            continue;
+        } else if current > 0 {
+            lines.insert(current);
+            current += 1;
+        }
+    }
+
+    lines
+}
+
+fn marker_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"<!-- polyrev:fp:([0-9a-f]+)(:resolved)? -->").expect("marker regex is valid")
+    })
+}
+
+/// Fingerprint from a comment's hidden marker, if it was written by polyrev
+fn comment_fingerprint(body: &str) -> Option<String> {
+    marker_regex()
+        .captures(body)
+        .map(|caps| caps[1].to_string())
+}
+
+fn is_resolved(body: &str) -> bool {
+    marker_regex()
+        .captures(body)
+        .is_some_and(|caps| caps.get(2).is_some())
+}
+
+fn comment_body(finding: &Finding, fingerprint: &str) -> String {
+    let mut body = format!(
+        "<!-- polyrev:fp:{} -->\n**[{}] {}**",
+        fingerprint, finding.priority, finding.title
+    );
+    if !finding.finding_type.is_empty() {
+        body.push_str(&format!(" (`{}`)", finding.finding_type));
+    }
+    body.push_str(&format!("\n\n{}\n", finding.description));
+    if !finding.remediation.is_empty() {
+        body.push_str(&format!("\n**Remediation:** {}\n", finding.remediation));
+    }
+    body
+}
+
+fn resolved_body(body: &str, fingerprint: &str) -> String {
+    let original = marker_regex().replace(body, "");
+    format!(
+        "<!-- polyrev:fp:{}{} -->\n✅ **Resolved** — no longer reported by polyrev.\n\n<details><summary>Original comment</summary>\n\n{}\n</details>\n",
+        fingerprint,
+        RESOLVED_SUFFIX,
+        original.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Priority;
+    use std::path::PathBuf;
+
+    fn finding(file: &str, line: u32, title: &str) -> (String, Finding) {
+        let finding = Finding {
+            id: title.to_string(),
+            finding_type: "bug".to_string(),
+            title: title.to_string(),
+            priority: Priority::P1,
+            file: PathBuf::from(file),
+            line,
+            description: "desc".to_string(),
+            ..Default::default()
+        };
+        ("sec".to_string(), finding)
+    }
+
+    #[test]
+    fn test_commentable_lines() {
+        let patch = "@@ -1,3 +1,4 @@\n a\n-b\n+c\n+d\n e\n@@ -20,2 +21,2 @@\n x\n+y\n\\ No newline at end of file";
+        let lines = commentable_lines(patch);
+        let mut sorted: Vec<_> = lines.into_iter().collect();
+        sorted.sort();
+        assert_eq!(sorted, vec![1, 2, 3, 4, 21, 22]);
+    }
+
+    #[test]
+    fn test_plan_splits_inline_and_body() {
+        let diff = HashMap::from([("src/a.rs".to_string(), HashSet::from([10, 11]))]);
+        let findings = vec![
+            finding("src/a.rs", 10, "In diff"),
+            finding("src/a.rs", 50, "Outside diff"),
+            finding("src/b.rs", 10, "Other file"),
+        ];
+
+        let plan = plan_review(&findings, &diff, &[], "");
+        assert_eq!(plan.new_comments.len(), 1);
+        assert_eq!(plan.new_comments[0].path, "src/a.rs");
+        assert_eq!(plan.new_comments[0].line, 10);
+        assert_eq!(plan.body_findings.len(), 2);
+        assert!(plan.body(1).contains(REVIEW_MARKER));
+    }
+
+    #[test]
+    fn test_plan_matches_diff_paths_from_repository_root() {
+        let diff = HashMap::from([("services/api/src/a.rs".to_string(), HashSet::from([10]))]);
+        let findings = vec![finding("./src/a.rs", 10, "In diff")];
+
+        let plan = plan_review(&findings, &diff, &[], "services/api/");
+        assert_eq!(plan.new_comments.len(), 1);
+        assert_eq!(plan.new_comments[0].path, "services/api/src/a.rs");
+
+        // Without the prefix the finding isn't found in the diff
+        let plan = plan_review(&findings, &diff, &[], "");
+        assert!(plan.new_comments.is_empty());
+        assert_eq!(plan.body_findings.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_updates_and_resolves_existing() {
+        let diff = HashMap::from([("src/a.rs".to_string(), HashSet::from([10]))]);
+        let current = finding("src/a.rs", 10, "Still here");
+        let fp = current.1.fingerprint("sec");
+        let gone = finding("src/a.rs", 20, "Fixed");
+        let gone_fp = gone.1.fingerprint("sec");
+
+        let existing = vec![
+            ReviewComment {
+                id: 1,
+                body: format!("<!-- polyrev:fp:{} -->\nold text", fp),
+            },
+            ReviewComment {
+                id: 2,
+                body: comment_body(&gone.1, &gone_fp),
+            },
+            ReviewComment {
+                id: 3,
+                body: "human comment".to_string(),
+            },
+        ];
+
+        let plan = plan_review(&[current], &diff, &existing, "");
+        assert!(plan.new_comments.is_empty());
+        assert_eq!(plan.updated.len(), 1);
+        assert_eq!(plan.updated[0].0, 1);
+        assert_eq!(plan.resolved.len(), 1);
+        assert_eq!(plan.resolved[0].0, 2);
+        assert!(is_resolved(&plan.resolved[0].1));
+
+        // Resolved comments aren't resolved twice
+        let existing = vec![ReviewComment {
+            id: 2,
+            body: plan.resolved[0].1.clone(),
+        }];
+        assert!(plan_review(&[], &diff, &existing, "").is_empty());
+    }
+}
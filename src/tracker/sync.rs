@@ -0,0 +1,239 @@
+//! Close issues whose findings no longer reproduce
+//!
+//! After a full run, an open polyrev issue labelled with a reviewer id whose
+//! fingerprint that reviewer didn't report counts one clean run. Once it has
+//! been clean for `grace_runs` consecutive runs it is commented on and closed.
+//! Counts persist in `.polyrev/sync.json` under the review target.
+
+use super::{IssueTracker, TrackedIssue};
+use crate::error::TrackerError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+const STATE_DIR: &str = ".polyrev";
+const SYNC_FILE: &str = "sync.json";
+
+/// Consecutive clean runs per fingerprint
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    pub issues: HashMap<String, CleanRuns>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanRuns {
+    pub reviewer: String,
+    pub count: u32,
+    /// Run that last incremented `count`, so re-syncing a report doesn't count twice
+    pub last_run: String,
+}
+
+impl SyncState {
+    pub fn load(target: &Path) -> Self {
+        fs::read_to_string(Self::path(target))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, target: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(target.join(STATE_DIR))?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(target), json)
+    }
+
+    fn path(target: &Path) -> PathBuf {
+        target.join(STATE_DIR).join(SYNC_FILE)
+    }
+}
+
+/// Result of syncing one reviewer's issues
+#[derive(Debug, Default)]
+pub struct SyncOutcome {
+    /// Issues clean for the whole grace period
+    pub to_close: Vec<TrackedIssue>,
+    /// Issues not reported this run but still in their grace period, with clean-run counts
+    pub pending: Vec<(TrackedIssue, u32)>,
+}
+
+/// Update clean-run counts for a reviewer's open issues and pick the ones to close
+///
+/// `reported` holds every fingerprint (current and legacy) reported this run.
+pub fn plan_sync(
+    state: &mut SyncState,
+    reviewer: &str,
+    open: Vec<TrackedIssue>,
+    reported: &HashSet<String>,
+    run_id: &str,
+    grace_runs: u32,
+) -> SyncOutcome {
+    let mut outcome = SyncOutcome::default();
+    let mut seen = HashSet::new();
+
+    for issue in open {
+        seen.insert(issue.fingerprint.clone());
+        if reported.contains(&issue.fingerprint) {
+            state.issues.remove(&issue.fingerprint);
+            continue;
+        }
+
+        let entry = state
+            .issues
+            .entry(issue.fingerprint.clone())
+            .or_insert_with(|| CleanRuns {
+                reviewer: reviewer.to_string(),
+                count: 0,
+                last_run: String::new(),
+            });
+        if entry.last_run != run_id {
+            entry.count += 1;
+            entry.last_run = run_id.to_string();
+        }
+
+        if entry.count >= grace_runs {
+            state.issues.remove(&issue.fingerprint);
+            outcome.to_close.push(issue);
+        } else {
+            let count = entry.count;
+            outcome.pending.push((issue, count));
+        }
+    }
+
+    // Forget issues of this reviewer that were closed or relabelled elsewhere
+    state
+        .issues
+        .retain(|fp, runs| runs.reviewer != reviewer || seen.contains(fp));
+
+    outcome
+}
+
+/// Comment posted on an issue before it is closed
+pub fn close_comment(commit: &str, clean_runs: u32) -> String {
+    format!(
+        "No longer detected in {} ({} consecutive full run{} without this finding). Closing automatically; polyrev will reopen or refile it if the finding comes back.",
+        commit,
+        clean_runs,
+        if clean_runs == 1 { "" } else { "s" }
+    )
+}
+
+/// The run being synced
+pub struct SyncRun<'a> {
+    /// Identifies the run (summary timestamp)
+    pub run_id: &'a str,
+    /// Commit named in the closing comment
+    pub commit: &'a str,
+    pub grace_runs: u32,
+    pub dry_run: bool,
+}
+
+/// Sync one reviewer's open issues against this run's fingerprints
+pub async fn sync_reviewer(
+    tracker: &dyn IssueTracker,
+    state: &mut SyncState,
+    reviewer: &str,
+    reported: &HashSet<String>,
+    run: &SyncRun<'_>,
+) -> Result<SyncOutcome, TrackerError> {
+    let open = tracker.list_open_issues(reviewer).await?;
+    debug!("{}: {} open polyrev issues", reviewer, open.len());
+
+    let outcome = plan_sync(state, reviewer, open, reported, run.run_id, run.grace_runs);
+    if !run.dry_run {
+        for issue in &outcome.to_close {
+            tracker
+                .comment_issue(&issue.id, &close_comment(run.commit, run.grace_runs))
+                .await?;
+            tracker.close_issue(&issue.id).await?;
+            info!("Closed {} (fingerprint {})", issue.id, issue.fingerprint);
+        }
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, fingerprint: &str) -> TrackedIssue {
+        TrackedIssue {
+            id: id.to_string(),
+            fingerprint: fingerprint.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_grace_period_counts_distinct_runs() {
+        let mut state = SyncState::default();
+        let reported: HashSet<String> = ["bbb".to_string()].into();
+        let open = || vec![issue("1", "aaa"), issue("2", "bbb")];
+
+        let first = plan_sync(&mut state, "sec", open(), &reported, "run-1", 2);
+        assert!(first.to_close.is_empty());
+        assert_eq!(first.pending, vec![(issue("1", "aaa"), 1)]);
+
+        // Re-syncing the same run doesn't advance the count
+        let again = plan_sync(&mut state, "sec", open(), &reported, "run-1", 2);
+        assert!(again.to_close.is_empty());
+
+        let second = plan_sync(&mut state, "sec", open(), &reported, "run-2", 2);
+        assert_eq!(second.to_close, vec![issue("1", "aaa")]);
+        assert!(state.issues.is_empty());
+    }
+
+    #[test]
+    fn test_reported_again_resets_count() {
+        let mut state = SyncState::default();
+        let none = HashSet::new();
+        plan_sync(
+            &mut state,
+            "sec",
+            vec![issue("1", "aaa")],
+            &none,
+            "run-1",
+            3,
+        );
+        assert_eq!(state.issues["aaa"].count, 1);
+
+        let reported: HashSet<String> = ["aaa".to_string()].into();
+        plan_sync(
+            &mut state,
+            "sec",
+            vec![issue("1", "aaa")],
+            &reported,
+            "run-2",
+            3,
+        );
+        assert!(!state.issues.contains_key("aaa"));
+    }
+
+    #[test]
+    fn test_forgets_issues_no_longer_open() {
+        let mut state = SyncState::default();
+        let none = HashSet::new();
+        plan_sync(
+            &mut state,
+            "sec",
+            vec![issue("1", "aaa")],
+            &none,
+            "run-1",
+            3,
+        );
+        plan_sync(
+            &mut state,
+            "perf",
+            vec![issue("2", "ccc")],
+            &none,
+            "run-1",
+            3,
+        );
+
+        // Issue 1 was closed by hand; perf's entry is untouched
+        plan_sync(&mut state, "sec", vec![], &none, "run-2", 3);
+        assert!(!state.issues.contains_key("aaa"));
+        assert!(state.issues.contains_key("ccc"));
+    }
+}
@@ -0,0 +1,231 @@
+//! User-provided issue title and body templates
+//!
+//! Templates use Jinja syntax (minijinja) and see the finding as `finding`,
+//! plus `reviewer_id`, `fingerprint`, `model`, `location`, `permalink`,
+//! `cluster` and `auto_fix`. The fingerprint marker is added to the rendered
+//! body when the template leaves it out, so dedupe keeps working.
+
+use super::{fingerprint_in, fingerprint_marker};
+use crate::config::IssueTemplateConfig;
+use crate::error::TrackerError;
+use crate::parser::Finding;
+use minijinja::Environment;
+use serde::Serialize;
+use std::path::Path;
+
+const TITLE: &str = "title";
+const BODY: &str = "body";
+
+/// Everything a template can reference
+#[derive(Serialize)]
+pub struct TemplateContext<'a> {
+    pub finding: &'a Finding,
+    pub reviewer_id: &'a str,
+    pub fingerprint: &'a str,
+    /// The finding's model, or the configured default
+    pub model: &'a str,
+    /// `file:line[-end]`
+    pub location: String,
+    /// Source link pinned to the reviewed commit, when known
+    pub permalink: Option<String>,
+    /// Postprocess cluster the finding belongs to
+    pub cluster: Option<ClusterContext<'a>>,
+    /// Set when auto-fix is enabled
+    pub auto_fix: Option<AutoFixContext<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ClusterContext<'a> {
+    pub name: &'a str,
+    pub rationale: &'a str,
+    /// Number of findings in the cluster
+    pub size: usize,
+}
+
+#[derive(Serialize)]
+pub struct AutoFixContext<'a> {
+    pub agent: &'a str,
+    pub prompt: &'a str,
+}
+
+/// Compiled `github.template`
+pub struct IssueTemplate {
+    env: Environment<'static>,
+}
+
+impl IssueTemplate {
+    /// Compile the configured templates; `None` when neither is set
+    ///
+    /// A relative `body` path is resolved against the review target.
+    pub fn load(config: &IssueTemplateConfig, target: &Path) -> Result<Option<Self>, TrackerError> {
+        if config.title.is_none() && config.body.is_none() {
+            return Ok(None);
+        }
+
+        let mut env = Environment::new();
+        env.set_keep_trailing_newline(true);
+        if let Some(title) = &config.title {
+            env.add_template_owned(TITLE, title.clone())
+                .map_err(|e| template_error("title", e))?;
+        }
+        if let Some(path) = &config.body {
+            let path = if path.is_absolute() {
+                path.clone()
+            } else {
+                target.join(path)
+            };
+            let source = std::fs::read_to_string(&path).map_err(|e| {
+                TrackerError::Template(format!("failed to read {}: {}", path.display(), e))
+            })?;
+            env.add_template_owned(BODY, source)
+                .map_err(|e| template_error(&path.display().to_string(), e))?;
+        }
+
+        Ok(Some(Self { env }))
+    }
+
+    /// Rendered title, or `None` to use the default
+    pub fn render_title(&self, ctx: &TemplateContext) -> Result<Option<String>, TrackerError> {
+        Ok(self
+            .render(TITLE, ctx)?
+            .map(|title| title.lines().map(str::trim).collect::<Vec<_>>().join(" ")))
+    }
+
+    /// Rendered body with the fingerprint marker guaranteed, or `None` to use the default
+    pub fn render_body(&self, ctx: &TemplateContext) -> Result<Option<String>, TrackerError> {
+        Ok(self
+            .render(BODY, ctx)?
+            .map(|body| ensure_marker(body, ctx.fingerprint)))
+    }
+
+    fn render(&self, name: &str, ctx: &TemplateContext) -> Result<Option<String>, TrackerError> {
+        let Ok(template) = self.env.get_template(name) else {
+            return Ok(None);
+        };
+        template
+            .render(ctx)
+            .map(Some)
+            .map_err(|e| template_error(name, e))
+    }
+}
+
+/// Prepend the hidden fingerprint marker unless the body already carries it
+fn ensure_marker(body: String, fingerprint: &str) -> String {
+    if fingerprint_in(&body).as_deref() == Some(fingerprint) {
+        body
+    } else {
+        format!("<!-- {} -->\n\n{}", fingerprint_marker(fingerprint), body)
+    }
+}
+
+fn template_error(name: &str, e: minijinja::Error) -> TrackerError {
+    let mut message = format!("{}: {}", name, e);
+    if let Some(detail) = e.detail() {
+        message.push_str(&format!(" ({})", detail));
+    }
+    TrackerError::Template(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding() -> Finding {
+        Finding {
+            id: "SEC-001".to_string(),
+            title: "SQL injection".to_string(),
+            file: "src/db.py".into(),
+            line: 42,
+            description: "Query built with string formatting".to_string(),
+            acceptance_criteria: vec!["Use parameters".to_string(), "Add a test".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn context(finding: &Finding) -> TemplateContext<'_> {
+        TemplateContext {
+            finding,
+            reviewer_id: "security",
+            fingerprint: "0123456789ab",
+            model: "sonnet",
+            location: finding.location(),
+            permalink: None,
+            cluster: Some(ClusterContext {
+                name: "Unsafe queries",
+                rationale: "same helper",
+                size: 3,
+            }),
+            auto_fix: None,
+        }
+    }
+
+    #[test]
+    fn test_renders_fields_and_loops() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("issue.md"),
+            "{{ finding.description }} at {{ location }}\n\
+             {% for c in finding.acceptance_criteria %}- [ ] {{ c }}\n{% endfor %}\
+             {% if cluster %}Part of {{ cluster.name }} ({{ cluster.size }}){% endif %}\
+             {% if permalink %}{{ permalink }}{% endif %}",
+        )
+        .unwrap();
+        let config = IssueTemplateConfig {
+            title: Some(
+                "[{{ finding.priority }}]\n{{ finding.title }} ({{ reviewer_id }})".to_string(),
+            ),
+            body: Some("issue.md".into()),
+        };
+        let template = IssueTemplate::load(&config, dir.path()).unwrap().unwrap();
+
+        let finding = finding();
+        let ctx = context(&finding);
+        assert_eq!(
+            template.render_title(&ctx).unwrap().unwrap(),
+            "[p1] SQL injection (security)"
+        );
+        let body = template.render_body(&ctx).unwrap().unwrap();
+        assert_eq!(
+            body,
+            "<!-- polyrev:fp:0123456789ab -->\n\n\
+             Query built with string formatting at src/db.py:42\n\
+             - [ ] Use parameters\n- [ ] Add a test\n\
+             Part of Unsafe queries (3)"
+        );
+    }
+
+    #[test]
+    fn test_marker_not_duplicated_and_title_only() {
+        assert_eq!(
+            ensure_marker(
+                "x <!-- polyrev:fp:0123456789ab -->".to_string(),
+                "0123456789ab"
+            ),
+            "x <!-- polyrev:fp:0123456789ab -->"
+        );
+
+        let config = IssueTemplateConfig {
+            title: Some("{{ finding.title }}".to_string()),
+            body: None,
+        };
+        let template = IssueTemplate::load(&config, Path::new("."))
+            .unwrap()
+            .unwrap();
+        let finding = finding();
+        assert!(template.render_body(&context(&finding)).unwrap().is_none());
+
+        assert!(
+            IssueTemplate::load(&IssueTemplateConfig::default(), Path::new("."))
+                .unwrap()
+                .is_none()
+        );
+        let broken = IssueTemplateConfig {
+            title: Some("{% if %}".to_string()),
+            body: None,
+        };
+        assert!(matches!(
+            IssueTemplate::load(&broken, Path::new(".")),
+            Err(TrackerError::Template(_))
+        ));
+    }
+}
@@ -202,13 +202,34 @@ pub fn draw_finding_detail(frame: &mut Frame<'_>, area: Rect, state: &FindingsVi
                     .fg(priority_color(finding.priority))
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(format!(
-                " · File: {}:{}",
-                finding.file.display(),
-                finding.line
-            )),
+            Span::raw(format!(" · File: {}", finding.location())),
         ]));
 
+        // Confidence and taxonomy
+        let mut meta: Vec<Span> = Vec::new();
+        if let Some(confidence) = finding.confidence {
+            meta.push(Span::raw("Confidence: "));
+            meta.push(Span::styled(
+                confidence.to_string(),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        let tags: Vec<&str> = finding
+            .cwe
+            .iter()
+            .chain(finding.owasp.iter())
+            .map(String::as_str)
+            .collect();
+        if !tags.is_empty() {
+            if !meta.is_empty() {
+                meta.push(Span::raw(" · "));
+            }
+            meta.push(Span::raw(format!("Tags: {}", tags.join(", "))));
+        }
+        if !meta.is_empty() {
+            lines.push(Line::from(meta));
+        }
+
         if let Some(model) = &finding.model {
             lines.push(Line::from(vec![
                 Span::raw("Model: "),
@@ -216,6 +237,30 @@ pub fn draw_finding_detail(frame: &mut Frame<'_>, area: Rect, state: &FindingsVi
             ]));
         }
 
+        if let Some(status) = finding.verified {
+            let color = if status.is_located() {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            lines.push(Line::from(vec![
+                Span::raw("Location: "),
+                Span::styled(status.to_string(), Style::default().fg(color)),
+            ]));
+        }
+
+        if finding.suggested_patch.is_some() {
+            let (label, color) = match finding.patch_applies {
+                Some(true) => ("applies", Color::Green),
+                Some(false) => ("does not apply", Color::Red),
+                None => ("not checked", Color::DarkGray),
+            };
+            lines.push(Line::from(vec![
+                Span::raw("Patch: "),
+                Span::styled(label, Style::default().fg(color)),
+            ]));
+        }
+
         lines.push(Line::default());
 
         // Description
@@ -242,6 +287,24 @@ pub fn draw_finding_detail(frame: &mut Frame<'_>, area: Rect, state: &FindingsVi
             }
         }
 
+        // Related locations
+        if !finding.related_locations.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from(vec![Span::styled(
+                "Related Locations:",
+                Style::default().add_modifier(Modifier::BOLD),
+            )]));
+            for related in &finding.related_locations {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {}", related.location()),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(format!(" {}", sanitize_text(&related.message))),
+                ]));
+            }
+        }
+
         // Remediation
         lines.push(Line::default());
         lines.push(Line::from(vec![Span::styled(
@@ -0,0 +1,478 @@
+//! Verify stage: a second model confirms, downgrades or refutes findings
+//!
+//! Each finding at one of `verify.priorities` is sent, with the code around
+//! it, to a different provider (or model) than the one that reduced it. The
+//! verdict is stored on the finding in `reduced.json`, or in the reviewers'
+//! `*.findings.json` when postprocess didn't run. `polyrev issue` skips
+//! refuted findings.
+
+use crate::config::{Config, Priority, Provider};
+use crate::error::VerifyError;
+use crate::parser::{Finding, Verdict, Verification};
+use crate::postprocess::extract_json;
+use crate::provider::{create_runner_for_provider, ProviderConfig, Runner};
+use crate::runner::is_within_target;
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Verdict counts for one verify run
+#[derive(Debug, Default)]
+pub struct VerifySummary {
+    pub confirmed: usize,
+    pub downgraded: usize,
+    pub refuted: usize,
+    /// Findings the verifier could not judge (left unverified)
+    pub failed: usize,
+}
+
+impl VerifySummary {
+    fn record(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Confirmed => self.confirmed += 1,
+            Verdict::Downgraded => self.downgraded += 1,
+            Verdict::Refuted => self.refuted += 1,
+        }
+    }
+}
+
+/// What we ask the verifier to return
+#[derive(Debug, Deserialize)]
+struct VerdictResponse {
+    verdict: Verdict,
+    #[serde(default, alias = "reason", alias = "reasoning")]
+    rationale: String,
+    /// Suggested priority for a downgrade
+    #[serde(default)]
+    priority: Option<Priority>,
+}
+
+/// A finding's verdict and its priority afterwards
+type VerifyResult = Result<(Verification, Priority), VerifyError>;
+
+/// Shared settings for verifying one report directory
+struct Verifier<'a> {
+    runner: &'a dyn Runner,
+    prompt: &'a str,
+    /// `provider/model`, recorded on each verdict
+    label: String,
+    target: &'a Path,
+    priorities: &'a [Priority],
+    context_lines: u32,
+    timeout: Duration,
+    concurrency: usize,
+}
+
+/// Run the verify stage over a report directory
+pub async fn run_verify(
+    config: &Config,
+    report_dir: &Path,
+) -> Result<Option<VerifySummary>, VerifyError> {
+    if !config.verify.enabled {
+        debug!("Verify disabled, skipping");
+        return Ok(None);
+    }
+
+    // Prefer a second opinion from the provider that didn't reduce the findings
+    let provider = config
+        .verify
+        .provider
+        .unwrap_or(match config.postprocess.tool.as_str() {
+            "claude_cli" | "claude" => Provider::CodexCli,
+            _ => Provider::ClaudeCli,
+        });
+    let (binary, default_model) = match provider {
+        Provider::ClaudeCli => (
+            config.providers.claude_cli.binary.clone(),
+            config.providers.claude_cli.model.clone(),
+        ),
+        Provider::CodexCli => (
+            config.providers.codex_cli.binary.clone(),
+            config.providers.codex_cli.model.clone(),
+        ),
+    };
+    let model = config.verify.model.clone().unwrap_or(default_model);
+    let runner = create_runner_for_provider(
+        provider,
+        ProviderConfig {
+            binary,
+            model: model.clone(),
+            tools: vec![], // The code is in the prompt
+            permission_mode: config.providers.claude_cli.permission_mode.clone(),
+        },
+    );
+
+    let prompt = load_prompt(&config.verify.prompt_file)?;
+    let verifier = Verifier {
+        runner: runner.as_ref(),
+        prompt: &prompt,
+        label: format!("{}/{}", provider, model),
+        target: &config.target,
+        priorities: &config.verify.priorities,
+        context_lines: config.verify.context_lines,
+        timeout: Duration::from_secs(config.verify.timeout_sec),
+        concurrency: config.concurrency.max(1),
+    };
+
+    let reduced = report_dir.join("reduced.json");
+    let files = if reduced.exists() {
+        vec![reduced]
+    } else {
+        findings_files(report_dir)?
+    };
+
+    info!("Verifying findings with {}", verifier.label);
+    let mut summary = VerifySummary::default();
+    for path in files {
+        verify_file(&verifier, &path, &mut summary).await?;
+    }
+
+    info!(
+        "Verify complete: {} confirmed, {} downgraded, {} refuted, {} failed",
+        summary.confirmed, summary.downgraded, summary.refuted, summary.failed
+    );
+    Ok(Some(summary))
+}
+
+/// Verify the unverified findings in one `reduced.json` or `*.findings.json`, in place
+///
+/// Findings are patched as JSON so fields the `Finding` type doesn't know
+/// (e.g. `merged_from`) survive.
+async fn verify_file(
+    verifier: &Verifier<'_>,
+    path: &Path,
+    summary: &mut VerifySummary,
+) -> Result<(), VerifyError> {
+    let mut doc: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    // reduced.json wraps its findings; reviewer files are bare arrays
+    let items = if doc.is_array() {
+        doc.as_array_mut()
+    } else {
+        doc.get_mut("findings").and_then(Value::as_array_mut)
+    };
+    let Some(items) = items else {
+        warn!("Skipping {:?}: no findings array", path);
+        return Ok(());
+    };
+
+    let mut candidates: Vec<(usize, Finding)> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let finding: Finding = match serde_json::from_value(item.clone()) {
+            Ok(finding) => finding,
+            Err(e) => {
+                summary.failed += 1;
+                warn!("Could not read finding {} in {:?}: {}", i, path, e);
+                continue;
+            }
+        };
+        if finding.verification.is_none() && verifier.priorities.contains(&finding.priority) {
+            candidates.push((i, finding));
+        }
+    }
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    debug!("Verifying {} findings from {:?}", candidates.len(), path);
+
+    let verdicts: Vec<(usize, VerifyResult)> = stream::iter(candidates)
+        .map(|(i, finding)| async move { (i, verify_finding(verifier, &finding).await) })
+        .buffer_unordered(verifier.concurrency)
+        .collect()
+        .await;
+
+    for (i, result) in verdicts {
+        match result {
+            Ok((verification, priority)) => {
+                summary.record(verification.verdict);
+                let item = &mut items[i];
+                if verification.original_priority.is_some() {
+                    item["priority"] = serde_json::to_value(priority)?;
+                }
+                item["verification"] = serde_json::to_value(&verification)?;
+            }
+            Err(e) => {
+                summary.failed += 1;
+                warn!("Could not verify finding {} in {:?}: {}", i, path, e);
+            }
+        }
+    }
+
+    std::fs::write(path, serde_json::to_string_pretty(&doc)?)?;
+    Ok(())
+}
+
+/// Ask the verifier about one finding; returns the verdict and the finding's new priority
+async fn verify_finding(verifier: &Verifier<'_>, finding: &Finding) -> VerifyResult {
+    let code = code_context(verifier.target, finding, verifier.context_lines);
+    let prompt = format!(
+        "{}\n\n## Finding\n\n```json\n{}\n```\n\n## Code\n\n{}",
+        verifier.prompt,
+        serde_json::to_string_pretty(finding)?,
+        code
+    );
+    let output = verifier
+        .runner
+        .execute(&prompt, &[], verifier.timeout, None)
+        .await?;
+    let response = parse_verdict(&output.stdout)?;
+    Ok(to_verification(response, finding.priority, &verifier.label))
+}
+
+/// Turn the verifier's answer into the stored verdict
+///
+/// A downgrade lowers the priority to the suggested one, or by one level when
+/// the suggestion is missing or not actually lower.
+fn to_verification(
+    response: VerdictResponse,
+    current: Priority,
+    label: &str,
+) -> (Verification, Priority) {
+    let lowered = match response.verdict {
+        Verdict::Downgraded => {
+            response
+                .priority
+                .filter(|p| p.rank() > current.rank())
+                .or(match current {
+                    Priority::P0 => Some(Priority::P1),
+                    Priority::P1 => Some(Priority::P2),
+                    Priority::P2 => None,
+                })
+        }
+        _ => None,
+    };
+    let verification = Verification {
+        verdict: response.verdict,
+        rationale: response.rationale,
+        original_priority: lowered.map(|_| current),
+        verifier: label.to_string(),
+    };
+    (verification, lowered.unwrap_or(current))
+}
+
+/// Extract the verdict JSON from CLI output (Claude wraps it in `{"result": ...}`)
+fn parse_verdict(raw: &str) -> Result<VerdictResponse, VerifyError> {
+    #[derive(Deserialize)]
+    struct ClaudeOutput {
+        result: String,
+    }
+
+    let text = serde_json::from_str::<ClaudeOutput>(raw)
+        .map(|o| o.result)
+        .unwrap_or_else(|_| raw.to_string());
+    let json = extract_json(&text)
+        .ok_or_else(|| VerifyError::ParseOutput("no JSON object in verifier output".to_string()))?;
+    serde_json::from_str(&json).map_err(|e| VerifyError::ParseOutput(e.to_string()))
+}
+
+/// The finding's lines plus `context` lines either side, numbered
+fn code_context(target: &Path, finding: &Finding, context: u32) -> String {
+    if !is_within_target(&finding.file) {
+        return format!("`{}` is outside the target.", finding.file.display());
+    }
+    let path = target.join(&finding.file);
+    let Ok(source) = std::fs::read_to_string(&path) else {
+        return format!("`{}` could not be read.", finding.file.display());
+    };
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = if finding.line == 0 {
+        (1, 2 * context + 1)
+    } else {
+        let end = finding.end_line.unwrap_or(finding.line).max(finding.line);
+        (finding.line.saturating_sub(context).max(1), end + context)
+    };
+    let end = end.min(lines.len() as u32);
+
+    let numbered = (start..=end)
+        .map(|n| format!("{:>5} | {}", n, lines[n as usize - 1]))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "`{}` lines {}-{}:\n\n```\n{}\n```",
+        finding.file.display(),
+        start,
+        end,
+        numbered
+    )
+}
+
+/// Load the verification prompt, falling back to the embedded default
+fn load_prompt(path: &Path) -> Result<String, VerifyError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!(
+                "Prompt file '{}' not found, using embedded default",
+                path.display()
+            );
+            Ok(include_str!("../prompts/verify.md").to_string())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reviewer findings files in the report directory
+fn findings_files(report_dir: &Path) -> Result<Vec<PathBuf>, VerifyError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(report_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(".findings.json"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ProviderError;
+    use crate::provider::{ProviderOutput, SessionInfo};
+    use async_trait::async_trait;
+
+    /// Refutes findings whose prompt mentions "false positive", confirms the rest
+    struct StubRunner;
+
+    #[async_trait]
+    impl Runner for StubRunner {
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+
+        async fn execute(
+            &self,
+            prompt: &str,
+            _files: &[PathBuf],
+            _timeout: Duration,
+            _session: Option<&SessionInfo>,
+        ) -> Result<ProviderOutput, ProviderError> {
+            let verdict = if prompt.contains("false positive") {
+                r#"{"verdict": "refuted", "rationale": "input is a constant"}"#
+            } else {
+                r#"{"verdict": "downgrade", "rationale": "internal only"}"#
+            };
+            Ok(ProviderOutput {
+                stdout: serde_json::json!({ "result": verdict }).to_string(),
+                stderr: String::new(),
+                duration: Duration::ZERO,
+                exit_code: 0,
+                session_id: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_file_patches_findings_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("db.py"), "a\nb\nquery(user_input)\nd\n").unwrap();
+        let reduced = dir.path().join("reduced.json");
+        std::fs::write(
+            &reduced,
+            serde_json::json!({
+                "original_count": 3,
+                "findings": [
+                    {"merged_from": ["aaa", "bbb"], "id": "SEC-1", "title": "SQL injection",
+                     "priority": "p0", "file": "db.py", "line": 3, "description": "d"},
+                    {"merged_from": ["ccc"], "id": "SEC-2", "title": "Looks like a false positive",
+                     "priority": "p1", "file": "db.py", "line": 1, "description": "d"},
+                    {"merged_from": ["ddd"], "id": "STY-1", "title": "Naming",
+                     "priority": "p2", "file": "db.py", "line": 4, "description": "d"},
+                    {"merged_from": ["eee"], "id": "SEC-3", "title": "Odd priority",
+                     "priority": "critical", "file": "db.py", "line": 2, "description": "d"},
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let verifier = Verifier {
+            runner: &StubRunner,
+            prompt: "Verify this finding.",
+            label: "stub/model".to_string(),
+            target: dir.path(),
+            priorities: &[Priority::P0, Priority::P1],
+            context_lines: 1,
+            timeout: Duration::from_secs(5),
+            concurrency: 2,
+        };
+        let mut summary = VerifySummary::default();
+        verify_file(&verifier, &reduced, &mut summary)
+            .await
+            .unwrap();
+        // The finding with a priority `Finding` doesn't accept counts as failed
+        assert_eq!(
+            (summary.downgraded, summary.refuted, summary.failed),
+            (1, 1, 1)
+        );
+
+        let doc: Value = serde_json::from_str(&std::fs::read_to_string(&reduced).unwrap()).unwrap();
+        let findings = doc["findings"].as_array().unwrap();
+        assert_eq!(
+            findings[0]["merged_from"],
+            serde_json::json!(["aaa", "bbb"])
+        );
+        assert_eq!(findings[0]["priority"], "p1");
+        assert_eq!(findings[0]["verification"]["original_priority"], "p0");
+        assert_eq!(findings[0]["verification"]["verifier"], "stub/model");
+        assert_eq!(findings[1]["verification"]["verdict"], "refuted");
+        assert!(findings[2].get("verification").is_none());
+
+        // Already-verified findings are not sent again
+        let mut summary = VerifySummary::default();
+        verify_file(&verifier, &reduced, &mut summary)
+            .await
+            .unwrap();
+        assert_eq!(summary.downgraded + summary.refuted, 0);
+    }
+
+    #[test]
+    fn test_to_verification_downgrades() {
+        let response = |priority| VerdictResponse {
+            verdict: Verdict::Downgraded,
+            rationale: String::new(),
+            priority,
+        };
+        let (v, priority) = to_verification(response(Some(Priority::P2)), Priority::P0, "x");
+        assert_eq!(priority, Priority::P2);
+        assert_eq!(v.original_priority, Some(Priority::P0));
+        // A "downgrade" to the same or higher priority lowers by one level
+        let (_, priority) = to_verification(response(Some(Priority::P0)), Priority::P1, "x");
+        assert_eq!(priority, Priority::P2);
+        let (v, priority) = to_verification(response(None), Priority::P2, "x");
+        assert_eq!((priority, v.original_priority), (Priority::P2, None));
+    }
+
+    #[test]
+    fn test_parse_verdict_and_code_context() {
+        let fenced =
+            "Checked it.\n```json\n{\"verdict\": \"confirmed\", \"reason\": \"tainted\"}\n```";
+        let response = parse_verdict(fenced).unwrap();
+        assert_eq!(response.verdict, Verdict::Confirmed);
+        assert_eq!(response.rationale, "tainted");
+        assert!(parse_verdict("no idea").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.py"), "one\ntwo\nthree\nfour\n").unwrap();
+        let finding = Finding {
+            file: "a.py".into(),
+            line: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            code_context(dir.path(), &finding, 1),
+            "`a.py` lines 1-3:\n\n```\n    1 | one\n    2 | two\n    3 | three\n```"
+        );
+        let outside = Finding {
+            file: "../a.py".into(),
+            ..finding
+        };
+        assert_eq!(
+            code_context(dir.path(), &outside, 1),
+            "`../a.py` is outside the target."
+        );
+    }
+}